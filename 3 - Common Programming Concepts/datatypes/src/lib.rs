@@ -0,0 +1 @@
+pub mod typed_rows;
@@ -0,0 +1,771 @@
+// Schema-driven parsing of delimited text (CSV / TSV) into typed tuples.
+//
+// The tuple type itself is the schema:
+//
+//     let reader = TupleReader::<_, (i32, f64, u8)>::new(input, Dialect::csv());
+//
+// reads every record as `(i32, f64, u8)`, just like
+// `let tup: (i32, f64, u8) = (500, 6.4, 1);` in main.rs.
+//
+// Blank lines between records are skipped, except for one-column tuples,
+// where a blank line is a record with one empty field. The writer quotes
+// an empty sole field as `""` so it never looks like a blank line.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::marker::PhantomData;
+use std::num::IntErrorKind;
+
+// ============================================================================
+// Dialect
+// ============================================================================
+
+/// How fields are separated and quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dialect {
+    pub delimiter: char,
+    pub quote: Option<char>,
+    pub has_header: bool,
+}
+
+impl Dialect {
+    /// Comma separated, `"` quoting, no header
+    pub fn csv() -> Dialect {
+        Dialect {
+            delimiter: ',',
+            quote: Some('"'),
+            has_header: false,
+        }
+    }
+
+    /// Tab separated, no quoting, no header
+    pub fn tsv() -> Dialect {
+        Dialect {
+            delimiter: '\t',
+            quote: None,
+            has_header: false,
+        }
+    }
+
+    pub fn with_header(self, has_header: bool) -> Dialect {
+        Dialect { has_header, ..self }
+    }
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Why a single field could not be turned into its column type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldErrorKind {
+    /// The value is a number, but outside the range of the type
+    OutOfRange,
+    /// The value is not a valid literal for the type at all
+    Invalid,
+    /// The field is empty but the type needs a value
+    Empty,
+}
+
+/// Error produced by a `Field` implementation, before row/column are known
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub kind: FieldErrorKind,
+    pub expected: &'static str,
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(io::Error),
+    /// The record has a different number of columns than the tuple
+    Arity {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A quoted field was still open at end of input
+    UnterminatedQuote {
+        row: usize,
+        column: usize,
+    },
+    /// A closing quote was followed by something other than a delimiter
+    TextAfterQuote {
+        row: usize,
+        column: usize,
+    },
+    /// A column could not be parsed as its type
+    Field {
+        row: usize,
+        column: usize,
+        value: String,
+        error: FieldError,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "I/O error: {err}"),
+            ParseError::Arity {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row}: expected {expected} columns, found {found}"),
+            ParseError::UnterminatedQuote { row, column } => {
+                write!(f, "row {row}, column {column}: unterminated quoted field")
+            }
+            ParseError::TextAfterQuote { row, column } => {
+                write!(f, "row {row}, column {column}: text after closing quote")
+            }
+            ParseError::Field {
+                row,
+                column,
+                value,
+                error,
+            } => {
+                write!(f, "row {row}, column {column}: ")?;
+                match error.kind {
+                    FieldErrorKind::OutOfRange => {
+                        write!(f, "{value} does not fit in {}", error.expected)
+                    }
+                    FieldErrorKind::Invalid => {
+                        write!(f, "{value:?} is not a valid {}", error.expected)
+                    }
+                    FieldErrorKind::Empty => write!(f, "empty field, expected {}", error.expected),
+                }
+            }
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> ParseError {
+        ParseError::Io(err)
+    }
+}
+
+// ============================================================================
+// Field: one column type
+// ============================================================================
+
+/// A scalar type that can be read from and written to a single field
+pub trait Field: Sized {
+    /// Name used in error messages, e.g. "u8"
+    const TYPE_NAME: &'static str;
+
+    fn parse_field(text: &str) -> Result<Self, FieldError>;
+
+    fn write_field(&self, out: &mut String);
+}
+
+fn field_error<T: Field>(kind: FieldErrorKind) -> FieldError {
+    FieldError {
+        kind,
+        expected: T::TYPE_NAME,
+    }
+}
+
+macro_rules! impl_int_field {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                const TYPE_NAME: &'static str = stringify!($ty);
+
+                fn parse_field(text: &str) -> Result<Self, FieldError> {
+                    text.trim().parse::<$ty>().map_err(|err| {
+                        let kind = match err.kind() {
+                            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                                FieldErrorKind::OutOfRange
+                            }
+                            IntErrorKind::Empty => FieldErrorKind::Empty,
+                            _ => FieldErrorKind::Invalid,
+                        };
+                        field_error::<$ty>(kind)
+                    })
+                }
+
+                fn write_field(&self, out: &mut String) {
+                    out.push_str(&self.to_string());
+                }
+            }
+        )*
+    };
+}
+
+impl_int_field!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+macro_rules! impl_float_field {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                const TYPE_NAME: &'static str = stringify!($ty);
+
+                fn parse_field(text: &str) -> Result<Self, FieldError> {
+                    let text = text.trim();
+                    if text.is_empty() {
+                        return Err(field_error::<$ty>(FieldErrorKind::Empty));
+                    }
+                    let value = text
+                        .parse::<$ty>()
+                        .map_err(|_| field_error::<$ty>(FieldErrorKind::Invalid))?;
+                    // "1e999" parses to infinity; treat that as out of range
+                    if value.is_infinite() && !text.to_ascii_lowercase().contains("inf") {
+                        return Err(field_error::<$ty>(FieldErrorKind::OutOfRange));
+                    }
+                    Ok(value)
+                }
+
+                fn write_field(&self, out: &mut String) {
+                    // Debug keeps the decimal point: 6.0 is written as "6.0"
+                    out.push_str(&format!("{self:?}"));
+                }
+            }
+        )*
+    };
+}
+
+impl_float_field!(f32, f64);
+
+impl Field for bool {
+    const TYPE_NAME: &'static str = "bool";
+
+    fn parse_field(text: &str) -> Result<Self, FieldError> {
+        match text.trim() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            "" => Err(field_error::<bool>(FieldErrorKind::Empty)),
+            _ => Err(field_error::<bool>(FieldErrorKind::Invalid)),
+        }
+    }
+
+    fn write_field(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+}
+
+impl Field for char {
+    const TYPE_NAME: &'static str = "char";
+
+    fn parse_field(text: &str) -> Result<Self, FieldError> {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            (None, _) => Err(field_error::<char>(FieldErrorKind::Empty)),
+            _ => Err(field_error::<char>(FieldErrorKind::Invalid)),
+        }
+    }
+
+    fn write_field(&self, out: &mut String) {
+        out.push(*self);
+    }
+}
+
+impl Field for String {
+    const TYPE_NAME: &'static str = "String";
+
+    fn parse_field(text: &str) -> Result<Self, FieldError> {
+        Ok(text.to_string())
+    }
+
+    fn write_field(&self, out: &mut String) {
+        out.push_str(self);
+    }
+}
+
+// ============================================================================
+// Row: a whole tuple
+// ============================================================================
+
+/// A tuple whose elements are all `Field`s
+pub trait Row: Sized {
+    const ARITY: usize;
+
+    /// Parses exactly `ARITY` fields. On failure returns the 0-based column
+    /// index together with the field error.
+    fn from_fields(fields: &[&str]) -> Result<Self, (usize, FieldError)>;
+
+    fn write_fields(&self, out: &mut Vec<String>);
+}
+
+macro_rules! impl_row {
+    ($arity:expr; $($name:ident $idx:tt),+) => {
+        impl<$($name: Field),+> Row for ($($name,)+) {
+            const ARITY: usize = $arity;
+
+            fn from_fields(fields: &[&str]) -> Result<Self, (usize, FieldError)> {
+                Ok(($(
+                    $name::parse_field(fields[$idx]).map_err(|err| ($idx, err))?,
+                )+))
+            }
+
+            fn write_fields(&self, out: &mut Vec<String>) {
+                $(
+                    let mut field = String::new();
+                    self.$idx.write_field(&mut field);
+                    out.push(field);
+                )+
+            }
+        }
+    };
+}
+
+impl_row!(1; A 0);
+impl_row!(2; A 0, B 1);
+impl_row!(3; A 0, B 1, C 2);
+impl_row!(4; A 0, B 1, C 2, D 3);
+impl_row!(5; A 0, B 1, C 2, D 3, E 4);
+impl_row!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+impl_row!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_row!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+impl_row!(9; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+impl_row!(10; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+impl_row!(11; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+impl_row!(12; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Streams records from any `BufRead`, one line (or quoted multi-line
+/// record) at a time, so the whole input never has to be in memory.
+pub struct TupleReader<R, T> {
+    input: R,
+    dialect: Dialect,
+    line: String,
+    fields: Vec<String>,
+    line_number: usize,
+    header: Option<Vec<String>>,
+    header_read: bool,
+    _row: PhantomData<T>,
+}
+
+impl<R: BufRead, T: Row> TupleReader<R, T> {
+    pub fn new(input: R, dialect: Dialect) -> TupleReader<R, T> {
+        TupleReader {
+            input,
+            dialect,
+            line: String::new(),
+            fields: Vec::new(),
+            line_number: 0,
+            header: None,
+            header_read: false,
+            _row: PhantomData,
+        }
+    }
+
+    /// Column names, if the dialect has a header and it has been read
+    pub fn header(&mut self) -> Result<Option<&[String]>, ParseError> {
+        self.read_header()?;
+        Ok(self.header.as_deref())
+    }
+
+    fn read_header(&mut self) -> Result<(), ParseError> {
+        if self.dialect.has_header && !self.header_read {
+            self.header_read = true;
+            if self.read_record()?.is_some() {
+                self.header = Some(self.fields.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn read_line(&mut self) -> io::Result<bool> {
+        self.line.clear();
+        if self.input.read_line(&mut self.line)? == 0 {
+            return Ok(false);
+        }
+        self.line_number += 1;
+        if self.line.ends_with('\n') {
+            self.line.pop();
+            if self.line.ends_with('\r') {
+                self.line.pop();
+            }
+        }
+        Ok(true)
+    }
+
+    /// Splits the next record into `self.fields`. Returns the row number of
+    /// the record's first line, or `None` at end of input.
+    fn read_record(&mut self) -> Result<Option<usize>, ParseError> {
+        // Skip blank lines between records, unless a blank line is a valid
+        // one-column record
+        loop {
+            if !self.read_line()? {
+                return Ok(None);
+            }
+            if !self.line.is_empty() || T::ARITY == 1 {
+                break;
+            }
+        }
+        let row = self.line_number;
+        self.fields.clear();
+
+        let delimiter = self.dialect.delimiter;
+        let Some(quote) = self.dialect.quote else {
+            self.fields
+                .extend(self.line.split(delimiter).map(str::to_string));
+            return Ok(Some(row));
+        };
+
+        let mut field = String::new();
+        let mut in_quotes = false;
+        // Just after a closing quote, where only a delimiter may follow
+        let mut closed = false;
+        loop {
+            let mut chars = self.line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c == quote {
+                        if chars.peek() == Some(&quote) {
+                            // "" inside quotes is an escaped quote
+                            field.push(quote);
+                            chars.next();
+                        } else {
+                            in_quotes = false;
+                            closed = true;
+                        }
+                    } else {
+                        field.push(c);
+                    }
+                } else if c == delimiter {
+                    self.fields.push(std::mem::take(&mut field));
+                    closed = false;
+                } else if closed {
+                    return Err(ParseError::TextAfterQuote {
+                        row,
+                        column: self.fields.len() + 1,
+                    });
+                } else if c == quote && field.is_empty() {
+                    in_quotes = true;
+                } else {
+                    field.push(c);
+                }
+            }
+            if !in_quotes {
+                break;
+            }
+            // A quoted field continues onto the next line
+            if !self.read_line()? {
+                return Err(ParseError::UnterminatedQuote {
+                    row,
+                    column: self.fields.len() + 1,
+                });
+            }
+            field.push('\n');
+        }
+        self.fields.push(field);
+        Ok(Some(row))
+    }
+
+    fn next_row(&mut self) -> Result<Option<T>, ParseError> {
+        self.read_header()?;
+        let Some(row) = self.read_record()? else {
+            return Ok(None);
+        };
+        if self.fields.len() != T::ARITY {
+            return Err(ParseError::Arity {
+                row,
+                expected: T::ARITY,
+                found: self.fields.len(),
+            });
+        }
+        let fields: Vec<&str> = self.fields.iter().map(String::as_str).collect();
+        T::from_fields(&fields)
+            .map(Some)
+            .map_err(|(column, error)| ParseError::Field {
+                row,
+                column: column + 1,
+                value: fields[column].to_string(),
+                error,
+            })
+    }
+}
+
+impl<R: BufRead, T: Row> Iterator for TupleReader<R, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_row().transpose()
+    }
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/// Writes tuples back out in the same dialect they were read with
+pub struct TupleWriter<W> {
+    output: W,
+    dialect: Dialect,
+    fields: Vec<String>,
+    line: String,
+}
+
+impl<W: Write> TupleWriter<W> {
+    pub fn new(output: W, dialect: Dialect) -> TupleWriter<W> {
+        TupleWriter {
+            output,
+            dialect,
+            fields: Vec::new(),
+            line: String::new(),
+        }
+    }
+
+    pub fn write_header(&mut self, names: &[&str]) -> io::Result<()> {
+        self.fields.clear();
+        self.fields
+            .extend(names.iter().map(|name| name.to_string()));
+        self.flush_fields()
+    }
+
+    pub fn write<T: Row>(&mut self, row: &T) -> io::Result<()> {
+        self.fields.clear();
+        row.write_fields(&mut self.fields);
+        self.flush_fields()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    fn flush_fields(&mut self) -> io::Result<()> {
+        let delimiter = self.dialect.delimiter;
+        self.line.clear();
+        if let ([field], Some(quote)) = (self.fields.as_slice(), self.dialect.quote)
+            && field.is_empty()
+        {
+            // An empty line would be read back as no record at all
+            self.line.extend([quote, quote, '\n']);
+            return self.output.write_all(self.line.as_bytes());
+        }
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                self.line.push(delimiter);
+            }
+            match self.dialect.quote {
+                Some(quote)
+                    if field.contains([delimiter, quote, '\n', '\r'])
+                        || field.starts_with(' ')
+                        || field.ends_with(' ') =>
+                {
+                    self.line.push(quote);
+                    for c in field.chars() {
+                        if c == quote {
+                            self.line.push(quote);
+                        }
+                        self.line.push(c);
+                    }
+                    self.line.push(quote);
+                }
+                Some(_) => self.line.push_str(field),
+                None => {
+                    if field.contains([delimiter, '\n', '\r']) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("field {field:?} cannot be written without quoting"),
+                        ));
+                    }
+                    self.line.push_str(field);
+                }
+            }
+        }
+        self.line.push('\n');
+        self.output.write_all(self.line.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_all<T: Row>(rows: &[T], dialect: Dialect) -> String {
+        let mut writer = TupleWriter::new(Vec::new(), dialect);
+        for row in rows {
+            writer.write(row).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    fn read_all<T: Row>(text: &str, dialect: Dialect) -> Result<Vec<T>, ParseError> {
+        TupleReader::new(text.as_bytes(), dialect).collect()
+    }
+
+    #[test]
+    fn round_trip_every_column_type() {
+        let rows = vec![
+            (500i32, 6.4f64, 1u8, true, 'z', String::from("plain")),
+            (
+                -1,
+                6.0,
+                255,
+                false,
+                ',',
+                String::from("comma, \"quote\"\nnewline"),
+            ),
+            (0, -0.5, 0, true, ' ', String::from(" padded ")),
+            (i32::MAX, 1e300, 7, false, 'é', String::new()),
+        ];
+        let text = write_all(&rows, Dialect::csv());
+        assert_eq!(
+            read_all::<(i32, f64, u8, bool, char, String)>(&text, Dialect::csv()).unwrap(),
+            rows
+        );
+
+        // TSV has no quoting, so only fields without tabs or newlines
+        let simple = vec![(1u16, String::from("a b")), (2, String::new())];
+        let text = write_all(&simple, Dialect::tsv());
+        assert_eq!(text, "1\ta b\n2\t\n");
+        assert_eq!(
+            read_all::<(u16, String)>(&text, Dialect::tsv()).unwrap(),
+            simple
+        );
+    }
+
+    #[test]
+    fn empty_sole_fields_survive_a_round_trip() {
+        let rows = vec![(String::from("a"),), (String::new(),), (String::from("b"),)];
+        let text = write_all(&rows, Dialect::csv());
+        assert_eq!(text, "a\n\"\"\nb\n");
+        assert_eq!(read_all::<(String,)>(&text, Dialect::csv()).unwrap(), rows);
+
+        // Without quoting the empty field is a blank line, which a one-column
+        // reader keeps
+        let text = write_all(&rows, Dialect::tsv());
+        assert_eq!(text, "a\n\nb\n");
+        assert_eq!(read_all::<(String,)>(&text, Dialect::tsv()).unwrap(), rows);
+        assert_eq!(
+            read_all::<(String,)>("a\n\nb\n", Dialect::csv()).unwrap(),
+            rows
+        );
+    }
+
+    #[test]
+    fn blank_lines_between_wider_records_are_skipped() {
+        let rows = read_all::<(i32, i32)>("1,2\n\n3,4\r\n\n", Dialect::csv()).unwrap();
+        assert_eq!(rows, [(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn quoted_fields() {
+        let rows = read_all::<(String, String)>(
+            "\"a,b\",\"say \"\"hi\"\"\"\n\"two\nlines\",\"\"\n",
+            Dialect::csv(),
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            [
+                (String::from("a,b"), String::from("say \"hi\"")),
+                (String::from("two\nlines"), String::new()),
+            ]
+        );
+        // A quote inside an unquoted field is kept as it is
+        let rows = read_all::<(String,)>("ab\"c\n", Dialect::csv()).unwrap();
+        assert_eq!(rows, [(String::from("ab\"c"),)]);
+    }
+
+    #[test]
+    fn header() {
+        let text = "name,score\nalice,90\n";
+        let mut reader =
+            TupleReader::<_, (String, u8)>::new(text.as_bytes(), Dialect::csv().with_header(true));
+        assert_eq!(
+            reader.header().unwrap().unwrap(),
+            [String::from("name"), String::from("score")]
+        );
+        assert_eq!(reader.next().unwrap().unwrap(), (String::from("alice"), 90));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn text_after_closing_quote_is_an_error() {
+        let err = read_all::<(String, String)>("x,y\n\"ab\"c,d\n", Dialect::csv()).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::TextAfterQuote { row: 2, column: 1 }
+        ));
+        let err = read_all::<(String, String)>("a,\"b\" \n", Dialect::csv()).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::TextAfterQuote { row: 1, column: 2 }
+        ));
+        assert_eq!(err.to_string(), "row 1, column 2: text after closing quote");
+    }
+
+    #[test]
+    fn error_positions() {
+        let err = read_all::<(i32, i32)>("1,2\n3\n", Dialect::csv()).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Arity {
+                row: 2,
+                expected: 2,
+                found: 1
+            }
+        ));
+
+        let err =
+            read_all::<(String, String)>("a,b\n\"open,c\nmore\n", Dialect::csv()).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::UnterminatedQuote { row: 2, column: 1 }
+        ));
+
+        // Rows count lines, including blank and continuation lines
+        let err = read_all::<(String, u8)>("\"x\ny\",1\n\nz,256\n", Dialect::csv()).unwrap_err();
+        let ParseError::Field {
+            row,
+            column,
+            value,
+            error,
+        } = err
+        else {
+            panic!("expected a field error, got {err:?}");
+        };
+        assert_eq!((row, column, value.as_str()), (4, 2, "256"));
+        assert_eq!(error.kind, FieldErrorKind::OutOfRange);
+        assert_eq!(error.expected, "u8");
+    }
+
+    #[test]
+    fn field_error_kinds() {
+        let kind = |text: &str| read_all::<(i8, f32, bool)>(text, Dialect::csv()).unwrap_err();
+        for (text, column, expected) in [
+            ("x,1.0,true\n", 1, FieldErrorKind::Invalid),
+            (",1.0,true\n", 1, FieldErrorKind::Empty),
+            ("1,1e99,true\n", 2, FieldErrorKind::OutOfRange),
+            ("1,1.0,yes\n", 3, FieldErrorKind::Invalid),
+        ] {
+            match kind(text) {
+                ParseError::Field {
+                    column: found,
+                    error,
+                    ..
+                } => {
+                    assert_eq!(found, column, "{text:?}");
+                    assert_eq!(error.kind, expected, "{text:?}");
+                }
+                err => panic!("{text:?}: {err:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn tsv_refuses_fields_it_cannot_write() {
+        let mut writer = TupleWriter::new(Vec::new(), Dialect::tsv());
+        let err = writer.write(&(String::from("a\tb"),)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,80 @@
+use functions::units::{BaseUnit, Measurement, Prefix, Unit};
+
+fn main() {
+    println!("=== UNITS OF MEASURE ===\n");
+
+    // ========================================================================
+    // 1. FROM A CHAR LABEL TO A REAL UNIT
+    // ========================================================================
+    println!("1. PARSING:");
+
+    // print_labeled_measurement(5, 'h') printed "5h"; now "5h" means something
+    for text in [
+        "5h",
+        "2.5 km",
+        "30 °C",
+        "98.6°F",
+        "-1.5e3 mg",
+        "12 furlongs",
+        "abc m",
+    ] {
+        match text.parse::<Measurement>() {
+            Ok(m) => println!("   {text:>10} -> {m} ({})", m.dimension()),
+            Err(err) => println!("   {text:>10} -> error: {err}"),
+        }
+    }
+
+    // ========================================================================
+    // 2. CONVERSION
+    // ========================================================================
+    println!("\n2. CONVERSION:");
+
+    let hours: Measurement = "5h".parse().unwrap();
+    let minutes = hours.to(Unit::new(BaseUnit::Minute)).unwrap();
+    println!("   {hours} = {minutes}");
+
+    let distance: Measurement = "2.5 km".parse().unwrap();
+    let miles = distance.to(Unit::new(BaseUnit::Mile)).unwrap();
+    println!("   {distance} = {miles:.3}");
+
+    let temperature: Measurement = "30 °C".parse().unwrap();
+    let fahrenheit = temperature.to(Unit::new(BaseUnit::Fahrenheit)).unwrap();
+    let kelvin = temperature.to(Unit::new(BaseUnit::Kelvin)).unwrap();
+    println!("   {temperature} = {fahrenheit:.1} = {kelvin:.2}");
+
+    // ========================================================================
+    // 3. ARITHMETIC
+    // ========================================================================
+    println!("\n3. ARITHMETIC:");
+
+    let walk: Measurement = "800 m".parse().unwrap();
+    let total = (distance + walk).unwrap();
+    println!("   {distance} + {walk} = {total}");
+
+    let warmer = (temperature + "10 K".parse().unwrap()).unwrap();
+    println!("   {temperature} + 10 K = {warmer}");
+
+    println!("   {hours} * 3 = {}", hours * 3.0);
+
+    match distance + hours {
+        Ok(sum) => println!("   {distance} + {hours} = {sum}"),
+        Err(err) => println!("   {distance} + {hours} -> error: {err}"),
+    }
+
+    // ========================================================================
+    // 4. FORMATTING WITH A CHOSEN UNIT AND PRECISION
+    // ========================================================================
+    println!("\n4. FORMATTING:");
+
+    let mass: Measurement = "1234.5678 g".parse().unwrap();
+    let kg = Unit::prefixed(Prefix::Kilo, BaseUnit::Gram).unwrap();
+    println!("   {}", mass.format_as(kg, 2).unwrap());
+    println!(
+        "   {}",
+        mass.format_as(Unit::new(BaseUnit::Pound), 3).unwrap()
+    );
+    match mass.format_as(Unit::new(BaseUnit::Second), 0) {
+        Ok(text) => println!("   {text}"),
+        Err(err) => println!("   as seconds -> error: {err}"),
+    }
+}
@@ -0,0 +1,649 @@
+// Units of measure: the grown-up version of
+// `print_labeled_measurement(value: i32, unit_label: char)`.
+//
+// A `Measurement` is a value plus a `Unit`. Units belong to a `Dimension`
+// (time, length, mass, temperature) and only units of the same dimension
+// can be converted into each other or added together.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+use std::str::FromStr;
+
+// ============================================================================
+// Dimensions, prefixes and units
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Time,
+    Length,
+    Mass,
+    Temperature,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dimension::Time => "time",
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Temperature => "temperature",
+        };
+        f.write_str(name)
+    }
+}
+
+/// SI prefixes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefix {
+    Nano,
+    Micro,
+    Milli,
+    Centi,
+    Deci,
+    Deca,
+    Hecto,
+    Kilo,
+    Mega,
+    Giga,
+}
+
+impl Prefix {
+    const ALL: [Prefix; 10] = [
+        Prefix::Nano,
+        Prefix::Micro,
+        Prefix::Milli,
+        Prefix::Centi,
+        Prefix::Deci,
+        Prefix::Deca,
+        Prefix::Hecto,
+        Prefix::Kilo,
+        Prefix::Mega,
+        Prefix::Giga,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Prefix::Nano => "n",
+            Prefix::Micro => "µ",
+            Prefix::Milli => "m",
+            Prefix::Centi => "c",
+            Prefix::Deci => "d",
+            Prefix::Deca => "da",
+            Prefix::Hecto => "h",
+            Prefix::Kilo => "k",
+            Prefix::Mega => "M",
+            Prefix::Giga => "G",
+        }
+    }
+
+    pub fn factor(self) -> f64 {
+        match self {
+            Prefix::Nano => 1e-9,
+            Prefix::Micro => 1e-6,
+            Prefix::Milli => 1e-3,
+            Prefix::Centi => 1e-2,
+            Prefix::Deci => 1e-1,
+            Prefix::Deca => 1e1,
+            Prefix::Hecto => 1e2,
+            Prefix::Kilo => 1e3,
+            Prefix::Mega => 1e6,
+            Prefix::Giga => 1e9,
+        }
+    }
+
+    // Splits "km" into (Kilo, "m"). "u" is accepted for micro.
+    fn strip(symbol: &str) -> Option<(Prefix, &str)> {
+        if let Some(rest) = symbol.strip_prefix('u') {
+            return Some((Prefix::Micro, rest));
+        }
+        // "da" must be tried before "d"
+        let mut prefixes = Prefix::ALL;
+        prefixes.sort_by_key(|p| std::cmp::Reverse(p.symbol().len()));
+        prefixes
+            .into_iter()
+            .find_map(|p| symbol.strip_prefix(p.symbol()).map(|rest| (p, rest)))
+    }
+}
+
+/// Named units without a prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Metre,
+    Inch,
+    Foot,
+    Mile,
+    Gram,
+    Tonne,
+    Pound,
+    Ounce,
+    Kelvin,
+    Celsius,
+    Fahrenheit,
+}
+
+impl BaseUnit {
+    const ALL: [BaseUnit; 15] = [
+        BaseUnit::Second,
+        BaseUnit::Minute,
+        BaseUnit::Hour,
+        BaseUnit::Day,
+        BaseUnit::Metre,
+        BaseUnit::Inch,
+        BaseUnit::Foot,
+        BaseUnit::Mile,
+        BaseUnit::Gram,
+        BaseUnit::Tonne,
+        BaseUnit::Pound,
+        BaseUnit::Ounce,
+        BaseUnit::Kelvin,
+        BaseUnit::Celsius,
+        BaseUnit::Fahrenheit,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BaseUnit::Second => "s",
+            BaseUnit::Minute => "min",
+            BaseUnit::Hour => "h",
+            BaseUnit::Day => "d",
+            BaseUnit::Metre => "m",
+            BaseUnit::Inch => "in",
+            BaseUnit::Foot => "ft",
+            BaseUnit::Mile => "mi",
+            BaseUnit::Gram => "g",
+            BaseUnit::Tonne => "t",
+            BaseUnit::Pound => "lb",
+            BaseUnit::Ounce => "oz",
+            BaseUnit::Kelvin => "K",
+            BaseUnit::Celsius => "°C",
+            BaseUnit::Fahrenheit => "°F",
+        }
+    }
+
+    pub fn dimension(self) -> Dimension {
+        match self {
+            BaseUnit::Second | BaseUnit::Minute | BaseUnit::Hour | BaseUnit::Day => Dimension::Time,
+            BaseUnit::Metre | BaseUnit::Inch | BaseUnit::Foot | BaseUnit::Mile => Dimension::Length,
+            BaseUnit::Gram | BaseUnit::Tonne | BaseUnit::Pound | BaseUnit::Ounce => Dimension::Mass,
+            BaseUnit::Kelvin | BaseUnit::Celsius | BaseUnit::Fahrenheit => Dimension::Temperature,
+        }
+    }
+
+    // value_in_si = value * scale + offset
+    // SI here means s, m, g and K.
+    fn scale(self) -> f64 {
+        match self {
+            BaseUnit::Second => 1.0,
+            BaseUnit::Minute => 60.0,
+            BaseUnit::Hour => 3600.0,
+            BaseUnit::Day => 86400.0,
+            BaseUnit::Metre => 1.0,
+            BaseUnit::Inch => 0.0254,
+            BaseUnit::Foot => 0.3048,
+            BaseUnit::Mile => 1609.344,
+            BaseUnit::Gram => 1.0,
+            BaseUnit::Tonne => 1e6,
+            BaseUnit::Pound => 453.592_37,
+            BaseUnit::Ounce => 28.349_523_125,
+            BaseUnit::Kelvin => 1.0,
+            BaseUnit::Celsius => 1.0,
+            BaseUnit::Fahrenheit => 5.0 / 9.0,
+        }
+    }
+
+    fn offset(self) -> f64 {
+        match self {
+            BaseUnit::Celsius => 273.15,
+            BaseUnit::Fahrenheit => 273.15 - 32.0 * 5.0 / 9.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Only the SI base units take prefixes ("km", "ms", "mg", "mK")
+    pub fn takes_prefix(self) -> bool {
+        matches!(
+            self,
+            BaseUnit::Second | BaseUnit::Metre | BaseUnit::Gram | BaseUnit::Kelvin
+        )
+    }
+
+    fn from_symbol(symbol: &str) -> Option<BaseUnit> {
+        match symbol {
+            "degC" | "℃" => return Some(BaseUnit::Celsius),
+            "degF" | "℉" => return Some(BaseUnit::Fahrenheit),
+            _ => {}
+        }
+        BaseUnit::ALL.into_iter().find(|u| u.symbol() == symbol)
+    }
+}
+
+/// An optionally prefixed unit such as `km` or `°C`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unit {
+    pub prefix: Option<Prefix>,
+    pub base: BaseUnit,
+}
+
+impl Unit {
+    pub const fn new(base: BaseUnit) -> Unit {
+        Unit { prefix: None, base }
+    }
+
+    pub fn prefixed(prefix: Prefix, base: BaseUnit) -> Result<Unit, UnitError> {
+        if !base.takes_prefix() {
+            return Err(UnitError::UnknownUnit(format!(
+                "{}{}",
+                prefix.symbol(),
+                base.symbol()
+            )));
+        }
+        Ok(Unit {
+            prefix: Some(prefix),
+            base,
+        })
+    }
+
+    pub fn dimension(self) -> Dimension {
+        self.base.dimension()
+    }
+
+    fn scale(self) -> f64 {
+        self.prefix.map_or(1.0, Prefix::factor) * self.base.scale()
+    }
+
+    fn si_value(self, value: f64) -> f64 {
+        value * self.scale() + self.base.offset()
+    }
+
+    fn value_from_si(self, si: f64) -> f64 {
+        (si - self.base.offset()) / self.scale()
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(prefix) = self.prefix {
+            f.write_str(prefix.symbol())?;
+        }
+        f.write_str(self.base.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = UnitError;
+
+    fn from_str(symbol: &str) -> Result<Unit, UnitError> {
+        // Exact names win, so "min" is minutes, "mi" is miles and "m" is metres
+        if let Some(base) = BaseUnit::from_symbol(symbol) {
+            return Ok(Unit::new(base));
+        }
+        if let Some((prefix, rest)) = Prefix::strip(symbol)
+            && let Some(base) = BaseUnit::from_symbol(rest).filter(|b| b.takes_prefix())
+        {
+            return Ok(Unit {
+                prefix: Some(prefix),
+                base,
+            });
+        }
+        Err(UnitError::UnknownUnit(symbol.to_string()))
+    }
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    /// Nothing to parse
+    Empty,
+    /// The numeric part is not a number
+    InvalidNumber(String),
+    /// The unit symbol is missing or not known
+    UnknownUnit(String),
+    /// The two units measure different things (e.g. metres and seconds)
+    Incompatible { left: Dimension, right: Dimension },
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::Empty => write!(f, "empty measurement"),
+            UnitError::InvalidNumber(text) => write!(f, "{text:?} is not a number"),
+            UnitError::UnknownUnit(text) if text.is_empty() => write!(f, "missing unit"),
+            UnitError::UnknownUnit(text) => write!(f, "unknown unit {text:?}"),
+            UnitError::Incompatible { left, right } => {
+                write!(f, "cannot combine {left} with {right}")
+            }
+        }
+    }
+}
+
+impl Error for UnitError {}
+
+// ============================================================================
+// Measurement
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Measurement {
+    pub fn new(value: f64, unit: Unit) -> Measurement {
+        Measurement { value, unit }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.unit.dimension()
+    }
+
+    fn check_compatible(&self, unit: Unit) -> Result<(), UnitError> {
+        if self.dimension() == unit.dimension() {
+            Ok(())
+        } else {
+            Err(UnitError::Incompatible {
+                left: self.dimension(),
+                right: unit.dimension(),
+            })
+        }
+    }
+
+    /// Converts to another unit of the same dimension
+    pub fn to(&self, unit: Unit) -> Result<Measurement, UnitError> {
+        self.check_compatible(unit)?;
+        Ok(Measurement {
+            value: unit.value_from_si(self.unit.si_value(self.value)),
+            unit,
+        })
+    }
+
+    /// Adds `other`, expressed in this measurement's unit.
+    ///
+    /// For temperatures the right-hand side is treated as a difference,
+    /// so 30 °C + 10 °C is 40 °C and 30 °C + 10 K is also 40 °C.
+    pub fn checked_add(&self, other: &Measurement) -> Result<Measurement, UnitError> {
+        self.check_compatible(other.unit)?;
+        let delta = other.value * other.unit.scale() / self.unit.scale();
+        Ok(Measurement::new(self.value + delta, self.unit))
+    }
+
+    /// Subtracts `other`, expressed in this measurement's unit.
+    ///
+    /// As with `checked_add`, a temperature on the right-hand side is a
+    /// difference: 30 °C - 10 °C is 20 °C, not the 20 K between the two
+    /// readings. Convert both sides to kelvin first for that.
+    pub fn checked_sub(&self, other: &Measurement) -> Result<Measurement, UnitError> {
+        self.checked_add(&Measurement::new(-other.value, other.unit))
+    }
+
+    /// Formats in `unit` with a fixed number of decimals
+    pub fn format_as(&self, unit: Unit, precision: usize) -> Result<String, UnitError> {
+        let converted = self.to(unit)?;
+        Ok(format!("{converted:.precision$}"))
+    }
+}
+
+impl fmt::Display for Measurement {
+    // Honours the formatter's precision: format!("{m:.2}")
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.precision$} {}", self.value, self.unit),
+            None => write!(f, "{} {}", self.value, self.unit),
+        }
+    }
+}
+
+impl FromStr for Measurement {
+    type Err = UnitError;
+
+    /// Accepts "5h", "2.5 km", "30 °C", "-1.5e3 mg"
+    fn from_str(text: &str) -> Result<Measurement, UnitError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(UnitError::Empty);
+        }
+
+        // The number ends where the first character that can't be part of
+        // a float literal begins. "e" is only part of the number when it is
+        // followed by a digit or sign, so "5e" doesn't swallow a unit.
+        let bytes = text.as_bytes();
+        let mut end = 0;
+        while end < bytes.len() {
+            let b = bytes[end];
+            let is_exponent = (b == b'e' || b == b'E')
+                && end > 0
+                && bytes
+                    .get(end + 1)
+                    .is_some_and(|n| n.is_ascii_digit() || *n == b'-' || *n == b'+');
+            if b.is_ascii_digit() || b == b'.' || ((b == b'-' || b == b'+') && end == 0) {
+                end += 1;
+            } else if is_exponent {
+                end += 2;
+            } else {
+                break;
+            }
+        }
+
+        let (number, unit) = text.split_at(end);
+        if number.is_empty() {
+            return Err(UnitError::InvalidNumber(text.to_string()));
+        }
+        let value = number
+            .parse::<f64>()
+            .map_err(|_| UnitError::InvalidNumber(number.to_string()))?;
+        let unit = unit.trim_start().parse::<Unit>()?;
+        Ok(Measurement::new(value, unit))
+    }
+}
+
+impl Add for Measurement {
+    type Output = Result<Measurement, UnitError>;
+
+    fn add(self, other: Measurement) -> Self::Output {
+        self.checked_add(&other)
+    }
+}
+
+impl Sub for Measurement {
+    type Output = Result<Measurement, UnitError>;
+
+    fn sub(self, other: Measurement) -> Self::Output {
+        self.checked_sub(&other)
+    }
+}
+
+// Scales the number in its own unit, so 10 °C * 2 is 20 °C. For
+// temperatures that is not twice as hot; multiply kelvin for that.
+impl Mul<f64> for Measurement {
+    type Output = Measurement;
+
+    fn mul(self, factor: f64) -> Measurement {
+        Measurement::new(self.value * factor, self.unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(symbol: &str) -> Unit {
+        symbol.parse().unwrap()
+    }
+
+    fn assert_close(got: f64, expected: f64) {
+        assert!(
+            (got - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{got} != {expected}"
+        );
+    }
+
+    #[test]
+    fn exact_names_win_over_prefixes() {
+        let cases = [
+            ("m", None, BaseUnit::Metre),
+            ("mi", None, BaseUnit::Mile),
+            ("min", None, BaseUnit::Minute),
+            ("mm", Some(Prefix::Milli), BaseUnit::Metre),
+            ("ms", Some(Prefix::Milli), BaseUnit::Second),
+            ("d", None, BaseUnit::Day),
+            ("dm", Some(Prefix::Deci), BaseUnit::Metre),
+            ("dam", Some(Prefix::Deca), BaseUnit::Metre),
+            ("dag", Some(Prefix::Deca), BaseUnit::Gram),
+            ("h", None, BaseUnit::Hour),
+            ("hm", Some(Prefix::Hecto), BaseUnit::Metre),
+            ("hs", Some(Prefix::Hecto), BaseUnit::Second),
+            ("us", Some(Prefix::Micro), BaseUnit::Second),
+            ("µs", Some(Prefix::Micro), BaseUnit::Second),
+            ("Mm", Some(Prefix::Mega), BaseUnit::Metre),
+            ("mK", Some(Prefix::Milli), BaseUnit::Kelvin),
+            ("℃", None, BaseUnit::Celsius),
+            ("degF", None, BaseUnit::Fahrenheit),
+        ];
+        for (symbol, prefix, base) in cases {
+            assert_eq!(unit(symbol), Unit { prefix, base }, "{symbol:?}");
+            assert_eq!(unit(symbol).to_string().parse::<Unit>(), Ok(unit(symbol)));
+        }
+    }
+
+    #[test]
+    fn only_si_units_take_prefixes() {
+        for symbol in ["kmin", "mmi", "kh", "dd", "cft", "k°C", "kin", "x", "", "M"] {
+            assert_eq!(
+                symbol.parse::<Unit>(),
+                Err(UnitError::UnknownUnit(symbol.to_string())),
+                "{symbol:?}"
+            );
+        }
+        assert!(Unit::prefixed(Prefix::Kilo, BaseUnit::Hour).is_err());
+        assert_eq!(
+            Unit::prefixed(Prefix::Kilo, BaseUnit::Metre),
+            Ok(unit("km"))
+        );
+    }
+
+    #[test]
+    fn number_and_unit_split() {
+        let cases = [
+            ("5h", 5.0, "h"),
+            ("2.5 km", 2.5, "km"),
+            ("1e-5 m", 1e-5, "m"),
+            ("1e-5m", 1e-5, "m"),
+            ("-1.5e3 mg", -1500.0, "mg"),
+            ("+2E+2 s", 200.0, "s"),
+            ("3 min", 3.0, "min"),
+            ("  30 °C  ", 30.0, "°C"),
+        ];
+        for (text, value, symbol) in cases {
+            let m: Measurement = text.parse().unwrap();
+            assert_close(m.value, value);
+            assert_eq!(m.unit, unit(symbol), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let cases = [
+            ("", UnitError::Empty),
+            ("   ", UnitError::Empty),
+            ("km", UnitError::InvalidNumber("km".to_string())),
+            ("1.2.3 m", UnitError::InvalidNumber("1.2.3".to_string())),
+            ("-", UnitError::InvalidNumber("-".to_string())),
+            ("5", UnitError::UnknownUnit(String::new())),
+            // "e" without a digit after it is the start of the unit
+            ("5e", UnitError::UnknownUnit("e".to_string())),
+            ("5 em", UnitError::UnknownUnit("em".to_string())),
+        ];
+        for (text, error) in cases {
+            assert_eq!(text.parse::<Measurement>(), Err(error), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        let cases = [
+            ("1 h", "min", 60.0),
+            ("1 d", "s", 86400.0),
+            ("1 mi", "km", 1.609_344),
+            ("12 in", "ft", 1.0),
+            ("1 dam", "dm", 100.0),
+            ("1 hm", "m", 100.0),
+            ("1 lb", "oz", 16.0),
+            ("2.5 t", "kg", 2500.0),
+            ("1e-5 m", "µm", 10.0),
+        ];
+        for (text, target, expected) in cases {
+            let m: Measurement = text.parse().unwrap();
+            let converted = m.to(unit(target)).unwrap();
+            assert_close(converted.value, expected);
+            assert_eq!(converted.unit, unit(target));
+        }
+        let err = "1 m".parse::<Measurement>().unwrap().to(unit("s"));
+        assert_eq!(
+            err,
+            Err(UnitError::Incompatible {
+                left: Dimension::Length,
+                right: Dimension::Time
+            })
+        );
+    }
+
+    #[test]
+    fn temperature_conversions_apply_the_offset() {
+        let cases = [
+            ("0 °C", "K", 273.15),
+            ("100 °C", "°F", 212.0),
+            ("-40 °C", "°F", -40.0),
+            ("32 °F", "°C", 0.0),
+            ("0 K", "°C", -273.15),
+            ("0 K", "°F", -459.67),
+            ("300 mK", "K", 0.3),
+        ];
+        for (text, target, expected) in cases {
+            let m: Measurement = text.parse().unwrap();
+            assert_close(m.to(unit(target)).unwrap().value, expected);
+        }
+        let m: Measurement = "21.5 °C".parse().unwrap();
+        assert_eq!(m.format_as(unit("°F"), 1).unwrap(), "70.7 °F");
+    }
+
+    #[test]
+    fn temperature_arithmetic_treats_the_right_side_as_a_difference() {
+        let celsius = |value| Measurement::new(value, unit("°C"));
+        let thirty = celsius(30.0);
+
+        assert_close((thirty + celsius(10.0)).unwrap().value, 40.0);
+        assert_close((thirty - celsius(10.0)).unwrap().value, 20.0);
+        assert_close(
+            (thirty + Measurement::new(10.0, unit("K"))).unwrap().value,
+            40.0,
+        );
+        // A difference of 9 °F is 5 K, not -12.8 °C
+        let sum = (thirty + Measurement::new(9.0, unit("°F"))).unwrap();
+        assert_close(sum.value, 35.0);
+        assert_eq!(sum.unit, unit("°C"));
+
+        // Multiplication scales the reading in its own unit
+        assert_eq!(celsius(10.0) * 2.0, celsius(20.0));
+        assert!((thirty + Measurement::new(1.0, unit("m"))).is_err());
+    }
+
+    #[test]
+    fn addition_keeps_the_left_unit() {
+        let km: Measurement = "1.5 km".parse().unwrap();
+        let m: Measurement = "250 m".parse().unwrap();
+        let sum = (km + m).unwrap();
+        assert_close(sum.value, 1.75);
+        assert_eq!(sum.unit, unit("km"));
+        assert_close((m - km).unwrap().value, -1250.0);
+        assert_eq!(format!("{:.2}", sum), "1.75 km");
+    }
+}
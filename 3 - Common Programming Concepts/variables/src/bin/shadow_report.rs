@@ -0,0 +1,52 @@
+// Reports shadowed and unused bindings in the repo's .rs files.
+//
+// Usage:
+//   cargo run --bin shadow_report -- [--json] [PATH ...]
+//
+// With no PATH the current directory is scanned. From this crate,
+// `cargo run --bin shadow_report -- ../..` scans the whole repo.
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+use variables::shadowing::{self, Report};
+
+fn main() {
+    let mut json = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-h" | "--help" => {
+                println!("usage: shadow_report [--json] [PATH ...]");
+                return;
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(String::from("."));
+    }
+
+    let mut report = Report::default();
+    for path in &paths {
+        match shadowing::analyze_dir(Path::new(path)) {
+            Ok(found) => {
+                report.files_scanned += found.files_scanned;
+                report.shadows.extend(found.shadows);
+                report.unused.extend(found.unused);
+            }
+            Err(err) => {
+                eprintln!("{path}: {err}");
+                process::exit(1);
+            }
+        }
+    }
+
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        println!("{report}");
+    }
+}
@@ -0,0 +1,321 @@
+// A small, forgiving tokenizer for Rust source.
+//
+// It only knows as much Rust as the shadowing analyzer needs: identifiers,
+// literals, lifetimes and punctuation, with comments thrown away. Several
+// lesson files in this repo deliberately don't compile, so nothing here ever
+// fails - unexpected input just becomes a `Punct` token.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Ident,
+    Lifetime,
+    Str,
+    Char,
+    Number,
+    Punct,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub line: usize,
+}
+
+impl Token {
+    pub fn is_punct(&self, text: &str) -> bool {
+        self.kind == TokenKind::Punct && self.text == text
+    }
+
+    pub fn is_ident(&self, text: &str) -> bool {
+        self.kind == TokenKind::Ident && self.text == text
+    }
+}
+
+// Multi-character operators, longest first
+const PUNCTS: [&str; 23] = [
+    "..=", "...", "<<=", ">>=", "::", "->", "=>", "==", "!=", "<=", ">=", "&&", "||", "+=", "-=",
+    "*=", "/=", "%=", "^=", "&=", "|=", "..", "<<",
+];
+
+pub fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start_line = line;
+
+        if c == '\n' {
+            line += 1;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        // Comments
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            // Block comments nest in Rust
+            let mut depth = 0;
+            while i < chars.len() {
+                if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                    depth += 1;
+                    i += 2;
+                } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    if chars[i] == '\n' {
+                        line += 1;
+                    }
+                    i += 1;
+                }
+            }
+            continue;
+        }
+
+        // Raw strings: r"..", r#".."#, br#".."#
+        let raw_start = match (c, chars.get(i + 1), chars.get(i + 2)) {
+            ('r', Some('"' | '#'), _) => Some(i + 1),
+            ('b', Some('r'), Some('"' | '#')) => Some(i + 2),
+            _ => None,
+        };
+        if let Some(mut j) = raw_start {
+            let mut hashes = 0;
+            while chars.get(j) == Some(&'#') {
+                hashes += 1;
+                j += 1;
+            }
+            if chars.get(j) == Some(&'"') {
+                j += 1;
+                let body_start = j;
+                loop {
+                    if j >= chars.len() {
+                        break;
+                    }
+                    if chars[j] == '"' && (1..=hashes).all(|h| chars.get(j + h) == Some(&'#')) {
+                        break;
+                    }
+                    if chars[j] == '\n' {
+                        line += 1;
+                    }
+                    j += 1;
+                }
+                let text: String = chars[body_start..j.min(chars.len())].iter().collect();
+                tokens.push(Token {
+                    kind: TokenKind::Str,
+                    text,
+                    line: start_line,
+                });
+                i = j + 1 + hashes;
+                continue;
+            }
+        }
+
+        // Strings and byte strings
+        if c == '"' || (c == 'b' && chars.get(i + 1) == Some(&'"')) {
+            let mut j = if c == 'b' { i + 2 } else { i + 1 };
+            let body_start = j;
+            while j < chars.len() && chars[j] != '"' {
+                if chars[j] == '\\' {
+                    j += 1;
+                }
+                if chars.get(j) == Some(&'\n') {
+                    line += 1;
+                }
+                j += 1;
+            }
+            let text: String = chars[body_start..j.min(chars.len())].iter().collect();
+            tokens.push(Token {
+                kind: TokenKind::Str,
+                text,
+                line: start_line,
+            });
+            i = j + 1;
+            continue;
+        }
+
+        // Char literals vs lifetimes: 'a' and '\n' are chars, 'a is a lifetime
+        if c == '\'' || (c == 'b' && chars.get(i + 1) == Some(&'\'')) {
+            let j = if c == 'b' { i + 1 } else { i };
+            let is_char = matches!(
+                (chars.get(j + 1), chars.get(j + 2)),
+                (Some('\\'), _) | (Some(_), Some('\''))
+            );
+            if is_char {
+                let mut k = j + 1;
+                while k < chars.len() && chars[k] != '\'' {
+                    if chars[k] == '\\' {
+                        k += 1;
+                    }
+                    k += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Char,
+                    text: chars[j + 1..k.min(chars.len())].iter().collect(),
+                    line: start_line,
+                });
+                i = k + 1;
+                continue;
+            }
+            if c == '\'' {
+                let mut k = i + 1;
+                while k < chars.len() && (chars[k].is_alphanumeric() || chars[k] == '_') {
+                    k += 1;
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Lifetime,
+                    text: chars[i..k].iter().collect(),
+                    line: start_line,
+                });
+                i = k;
+                continue;
+            }
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let mut j = i;
+            while j < chars.len() && (chars[j].is_alphanumeric() || chars[j] == '_') {
+                j += 1;
+            }
+            tokens.push(Token {
+                kind: TokenKind::Ident,
+                text: chars[i..j].iter().collect(),
+                line: start_line,
+            });
+            i = j;
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let mut j = i;
+            while j < chars.len() {
+                let d = chars[j];
+                // 1..5 is a range, not a float
+                let is_decimal_point =
+                    d == '.' && chars.get(j + 1).is_some_and(|n| n.is_ascii_digit());
+                if d.is_alphanumeric() || d == '_' || is_decimal_point {
+                    j += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number,
+                text: chars[i..j].iter().collect(),
+                line: start_line,
+            });
+            i = j;
+            continue;
+        }
+
+        let rest: String = chars[i..(i + 3).min(chars.len())].iter().collect();
+        let punct = PUNCTS
+            .iter()
+            .find(|p| rest.starts_with(*p))
+            .map(|p| p.to_string())
+            .unwrap_or_else(|| c.to_string());
+        i += punct.chars().count();
+        tokens.push(Token {
+            kind: TokenKind::Punct,
+            text: punct,
+            line: start_line,
+        });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<(TokenKind, String)> {
+        tokenize(source)
+            .into_iter()
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    fn token(kind: TokenKind, text: &str) -> (TokenKind, String) {
+        (kind, text.to_string())
+    }
+
+    #[test]
+    fn comments_are_dropped_and_lines_counted() {
+        let tokens = tokenize("a // one\n/* two /* nested */\n still */ b\nc");
+        let lines: Vec<(&str, usize)> = tokens.iter().map(|t| (t.text.as_str(), t.line)).collect();
+        assert_eq!(lines, [("a", 1), ("b", 3), ("c", 4)]);
+    }
+
+    #[test]
+    fn literals() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds(r##""a\"b" b"x" r#"say "hi""# 'c' '\n' b'\'' 'a 1.5 1..5 0xff_u8"##),
+            [
+                token(Str, r#"a\"b"#),
+                token(Str, "x"),
+                token(Str, r#"say "hi""#),
+                token(Char, "c"),
+                token(Char, r"\n"),
+                token(Char, r"\'"),
+                token(Lifetime, "'a"),
+                token(Number, "1.5"),
+                token(Number, "1"),
+                token(Punct, ".."),
+                token(Number, "5"),
+                token(Number, "0xff_u8"),
+            ]
+        );
+    }
+
+    #[test]
+    fn multi_line_strings_keep_their_start_line() {
+        let tokens = tokenize("\"one\ntwo\" x r\"3\n4\" y");
+        let lines: Vec<usize> = tokens.iter().map(|t| t.line).collect();
+        assert_eq!(lines, [1, 2, 2, 3]);
+    }
+
+    #[test]
+    fn longest_punctuation_wins() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("a..=b && c::d -> e <<= 1"),
+            [
+                token(Ident, "a"),
+                token(Punct, "..="),
+                token(Ident, "b"),
+                token(Punct, "&&"),
+                token(Ident, "c"),
+                token(Punct, "::"),
+                token(Ident, "d"),
+                token(Punct, "->"),
+                token(Ident, "e"),
+                token(Punct, "<<="),
+                token(Number, "1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_input_does_not_panic() {
+        for source in ["\"open", "'", "r#\"open", "/* open", "b'", "0x"] {
+            tokenize(source);
+        }
+    }
+}
@@ -0,0 +1,873 @@
+// Finds shadowed and unused bindings in Rust source files.
+//
+// This is a token-level analysis, not a compiler: it tracks `{ }` scopes and
+// the bindings introduced by `let`, `if let` / `while let`, `for` loops and
+// function parameters. Closure parameters and `match` arm patterns are not
+// treated as bindings, so a name used only inside them counts as a use of
+// whatever outer binding has the same name.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::lexer::{self, Token, TokenKind};
+
+// ============================================================================
+// Report types
+// ============================================================================
+
+/// Whether a shadowing binding may have a different type than the one it hides
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeChange {
+    /// Both types are known and equal, or the new value is plain arithmetic
+    /// on the old one (`let x = x + 1`)
+    No,
+    /// Both types are known and differ
+    Yes,
+    /// The analysis can't tell (method calls, casts, patterns, ...)
+    Possibly,
+}
+
+impl TypeChange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TypeChange::No => "no",
+            TypeChange::Yes => "yes",
+            TypeChange::Possibly => "possibly",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shadow {
+    pub file: PathBuf,
+    pub name: String,
+    pub line: usize,
+    pub shadowed_line: usize,
+    /// The hidden binding lives in the same scope (`let x = 5; let x = x + 1;`)
+    /// rather than an enclosing one
+    pub same_scope: bool,
+    pub type_change: TypeChange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unused {
+    pub file: PathBuf,
+    pub name: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub files_scanned: usize,
+    pub shadows: Vec<Shadow>,
+    pub unused: Vec<Unused>,
+}
+
+// ============================================================================
+// Entry points
+// ============================================================================
+
+/// Analyzes every `.rs` file under `root`, skipping `target` and hidden
+/// directories. Paths in the report are relative to `root`.
+pub fn analyze_dir(root: &Path) -> io::Result<Report> {
+    let mut files = Vec::new();
+    collect_rs_files(root, &mut files)?;
+    files.sort();
+
+    let mut report = Report::default();
+    for path in files {
+        let source = fs::read_to_string(&path)?;
+        let relative = match path.strip_prefix(root) {
+            Ok(relative) if !relative.as_os_str().is_empty() => relative,
+            _ => &path,
+        };
+        analyze_source(relative, &source, &mut report);
+    }
+    Ok(report)
+}
+
+/// Analyzes one file's source and appends its findings to `report`
+pub fn analyze_source(file: &Path, source: &str, report: &mut Report) {
+    let tokens = lexer::tokenize(source);
+    let mut analyzer = Analyzer::new(file, &tokens, report);
+    analyzer.run();
+    report.files_scanned += 1;
+}
+
+fn collect_rs_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if dir.is_file() {
+        files.push(dir.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        if path.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_rs_files(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Analyzer
+// ============================================================================
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+struct Binding {
+    name: String,
+    line: usize,
+    type_annotation: Option<String>,
+    literal_type: Option<String>,
+    used: bool,
+}
+
+// A binding that has been parsed but isn't in scope yet
+struct Pending {
+    name: String,
+    line: usize,
+    type_annotation: Option<String>,
+}
+
+// `let` statement waiting for its `;`
+struct PendingLet {
+    depth: usize,
+    bindings: Vec<Pending>,
+    init_start: usize,
+}
+
+struct Analyzer<'a> {
+    file: &'a Path,
+    tokens: &'a [Token],
+    report: &'a mut Report,
+    bindings: Vec<Binding>,
+    scopes: Vec<Vec<usize>>,
+    // Bindings that come into scope at the next `{` (fn params, if let, for)
+    pending_block: Vec<Pending>,
+    pending_lets: Vec<PendingLet>,
+    // An `if let .. &&` chain already opened the block's scope
+    preopened: bool,
+}
+
+impl<'a> Analyzer<'a> {
+    fn new(file: &'a Path, tokens: &'a [Token], report: &'a mut Report) -> Analyzer<'a> {
+        Analyzer {
+            file,
+            tokens,
+            report,
+            bindings: Vec::new(),
+            scopes: vec![Vec::new()],
+            pending_block: Vec::new(),
+            pending_lets: Vec::new(),
+            preopened: false,
+        }
+    }
+
+    fn run(&mut self) {
+        let mut i = 0;
+        while i < self.tokens.len() {
+            let token = &self.tokens[i];
+            match token.kind {
+                TokenKind::Punct if token.text == "{" => {
+                    if !std::mem::take(&mut self.preopened) {
+                        self.scopes.push(Vec::new());
+                    }
+                    for pending in std::mem::take(&mut self.pending_block) {
+                        self.commit(pending, &[]);
+                    }
+                }
+                TokenKind::Punct if token.text == "}" => {
+                    self.close_scope();
+                    let depth = self.scopes.len() - 1;
+                    self.pending_lets.retain(|p| p.depth <= depth);
+                }
+                TokenKind::Punct if token.text == "&&" && !self.pending_block.is_empty() => {
+                    // `if let Some(a) = x && let Some(b) = a.get(..)`: `a` is
+                    // already in scope for the rest of the chain
+                    if !self.preopened {
+                        self.scopes.push(Vec::new());
+                        self.preopened = true;
+                    }
+                    for pending in std::mem::take(&mut self.pending_block) {
+                        self.commit(pending, &[]);
+                    }
+                }
+                TokenKind::Punct if token.text == ";" => {
+                    self.pending_block.clear();
+                    let depth = self.scopes.len() - 1;
+                    if self.pending_lets.last().is_some_and(|p| p.depth == depth) {
+                        let pending = self.pending_lets.pop().unwrap();
+                        let init = &self.tokens[pending.init_start.min(i)..i];
+                        for binding in pending.bindings {
+                            self.commit(binding, init);
+                        }
+                    }
+                }
+                TokenKind::Ident if token.text == "let" => {
+                    i = self.parse_let(i);
+                    continue;
+                }
+                TokenKind::Ident if token.text == "fn" => {
+                    i = self.parse_fn(i);
+                    continue;
+                }
+                TokenKind::Ident if token.text == "for" => {
+                    i = self.parse_for(i);
+                    continue;
+                }
+                TokenKind::Ident => self.visit_ident(i),
+                TokenKind::Str => self.visit_format_string(i),
+                _ => {}
+            }
+            i += 1;
+        }
+
+        while self.scopes.len() > 1 {
+            self.close_scope();
+        }
+        self.close_scope();
+    }
+
+    fn close_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+        for index in scope {
+            let binding = &self.bindings[index];
+            if !binding.used && !binding.name.starts_with('_') {
+                self.report.unused.push(Unused {
+                    file: self.file.to_path_buf(),
+                    name: binding.name.clone(),
+                    line: binding.line,
+                });
+            }
+        }
+        if self.scopes.is_empty() {
+            self.scopes.push(Vec::new());
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<(usize, bool)> {
+        let innermost = self.scopes.len() - 1;
+        for (depth, scope) in self.scopes.iter().enumerate().rev() {
+            if let Some(&index) = scope.iter().rev().find(|&&b| self.bindings[b].name == name) {
+                return Some((index, depth == innermost));
+            }
+        }
+        None
+    }
+
+    fn commit(&mut self, pending: Pending, init: &[Token]) {
+        let literal_type = literal_type(init);
+        if let Some((index, same_scope)) = self.lookup(&pending.name) {
+            let old = &self.bindings[index];
+            let type_change = type_change(old, &pending, literal_type.as_deref(), init);
+            self.report.shadows.push(Shadow {
+                file: self.file.to_path_buf(),
+                name: pending.name.clone(),
+                line: pending.line,
+                shadowed_line: old.line,
+                same_scope,
+                type_change,
+            });
+        }
+        self.bindings.push(Binding {
+            name: pending.name,
+            line: pending.line,
+            type_annotation: pending.type_annotation,
+            literal_type,
+            used: false,
+        });
+        let index = self.bindings.len() - 1;
+        self.scopes.last_mut().unwrap().push(index);
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        if let Some((index, _)) = self.lookup(name) {
+            self.bindings[index].used = true;
+        }
+    }
+
+    fn visit_ident(&mut self, i: usize) {
+        let token = &self.tokens[i];
+        if KEYWORDS.contains(&token.text.as_str()) {
+            return;
+        }
+        let prev = i.checked_sub(1).map(|p| &self.tokens[p]);
+        let next = self.tokens.get(i + 1);
+        // obj.field, path::item, macro!, and `field:` in struct literals
+        if prev.is_some_and(|p| p.is_punct(".") || p.is_punct("::"))
+            || next.is_some_and(|n| n.is_punct("::") || n.is_punct("!") || n.is_punct(":"))
+        {
+            return;
+        }
+        let name = token.text.clone();
+        self.mark_used(&name);
+    }
+
+    // Inline format arguments: "{x}", "{x:?}" and "{v:.prec$}" use their names
+    fn visit_format_string(&mut self, i: usize) {
+        let text = self.tokens[i].text.clone();
+        let mut rest = text.as_str();
+        while let Some(open) = rest.find('{') {
+            rest = &rest[open + 1..];
+            if let Some(escaped) = rest.strip_prefix('{') {
+                rest = escaped;
+                continue;
+            }
+            let Some(close) = rest.find('}') else {
+                break;
+            };
+            let placeholder = &rest[..close];
+            rest = &rest[close + 1..];
+
+            let (arg, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+            if is_identifier(arg) {
+                self.mark_used(arg);
+            }
+            // Width and precision can name arguments too: {:width$.prec$}
+            for part in spec.split('$').filter(|_| spec.contains('$')) {
+                let start = part
+                    .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .map_or(0, |p| p + 1);
+                if is_identifier(&part[start..]) {
+                    self.mark_used(&part[start..]);
+                }
+            }
+        }
+    }
+
+    // `let PATTERN [: TYPE] [= INIT];` or `if let PATTERN = EXPR {`
+    fn parse_let(&mut self, i: usize) -> usize {
+        let conditional = i
+            .checked_sub(1)
+            .map(|p| &self.tokens[p])
+            .is_some_and(|p| p.is_ident("if") || p.is_ident("while") || p.is_punct("&&"));
+
+        let mut depth = 0;
+        let mut j = i + 1;
+        let mut pattern_end = None;
+        let mut type_start = None;
+        while j < self.tokens.len() {
+            let t = &self.tokens[j];
+            if t.is_punct("(") || t.is_punct("[") || t.is_punct("{") {
+                depth += 1;
+            } else if t.is_punct(")") || t.is_punct("]") || t.is_punct("}") {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            } else if depth == 0 {
+                if t.is_punct(":") && type_start.is_none() {
+                    pattern_end.get_or_insert(j);
+                    type_start = Some(j + 1);
+                } else if t.is_punct("=") || t.is_punct(";") {
+                    break;
+                }
+            }
+            j += 1;
+        }
+        let pattern_end = pattern_end.unwrap_or(j);
+        let type_annotation = type_start.map(|start| join_tokens(&self.tokens[start..j]));
+
+        let bindings: Vec<Pending> = pattern_bindings(&self.tokens[i + 1..pattern_end])
+            .into_iter()
+            .map(|t| Pending {
+                name: t.text.clone(),
+                line: t.line,
+                type_annotation: type_annotation.clone(),
+            })
+            .collect();
+
+        let has_init = self.tokens.get(j).is_some_and(|t| t.is_punct("="));
+        if conditional {
+            self.pending_block.extend(bindings);
+        } else {
+            self.pending_lets.push(PendingLet {
+                depth: self.scopes.len() - 1,
+                bindings,
+                init_start: if has_init { j + 1 } else { j },
+            });
+        }
+        // Resume at the initializer (or the `;`) so its uses are seen
+        if has_init { j + 1 } else { j }
+    }
+
+    // `fn name<..>(a: A, mut b: B) -> R {`
+    fn parse_fn(&mut self, i: usize) -> usize {
+        // `fn(i32) -> i32` is a type, not an item
+        let Some(open) = (i + 1..self.tokens.len()).find(|&k| self.tokens[k].is_punct("(")) else {
+            return i + 1;
+        };
+        if open == i + 1 {
+            return i + 1;
+        }
+
+        let mut depth = 0;
+        let mut j = open;
+        let mut param_start = open + 1;
+        let mut params = Vec::new();
+        while j < self.tokens.len() {
+            let t = &self.tokens[j];
+            if t.is_punct("(") || t.is_punct("[") || t.is_punct("<") {
+                depth += 1;
+            } else if t.is_punct(")") || t.is_punct("]") || t.is_punct(">") {
+                depth -= 1;
+                if depth == 0 {
+                    params.push((param_start, j));
+                    break;
+                }
+            } else if t.is_punct(",") && depth == 1 {
+                params.push((param_start, j));
+                param_start = j + 1;
+            }
+            j += 1;
+        }
+
+        self.pending_block.clear();
+        for (start, end) in params {
+            let param = &self.tokens[start..end];
+            let Some(colon) = param.iter().position(|t| t.is_punct(":")) else {
+                continue;
+            };
+            let type_annotation = Some(join_tokens(&param[colon + 1..]));
+            for t in pattern_bindings(&param[..colon]) {
+                self.pending_block.push(Pending {
+                    name: t.text.clone(),
+                    line: t.line,
+                    type_annotation: type_annotation.clone(),
+                });
+            }
+        }
+        j + 1
+    }
+
+    // `for PATTERN in EXPR {`; `impl Trait for Type {` has no `in`
+    fn parse_for(&mut self, i: usize) -> usize {
+        let mut j = i + 1;
+        while j < self.tokens.len() {
+            let t = &self.tokens[j];
+            if t.is_ident("in") {
+                let bindings = pattern_bindings(&self.tokens[i + 1..j]);
+                for t in bindings {
+                    self.pending_block.push(Pending {
+                        name: t.text.clone(),
+                        line: t.line,
+                        type_annotation: None,
+                    });
+                }
+                return j + 1;
+            }
+            if t.is_punct("{") || t.is_punct(";") {
+                break;
+            }
+            j += 1;
+        }
+        i + 1
+    }
+}
+
+// Identifiers in a pattern that introduce bindings: lowercase names that
+// aren't paths, constructors, struct field labels or keywords.
+fn pattern_bindings(pattern: &[Token]) -> Vec<&Token> {
+    let mut names = Vec::new();
+    for (k, t) in pattern.iter().enumerate() {
+        if t.kind != TokenKind::Ident
+            || t.text == "_"
+            || KEYWORDS.contains(&t.text.as_str())
+            || !t.text.starts_with(|c: char| c.is_lowercase() || c == '_')
+        {
+            continue;
+        }
+        let prev = k.checked_sub(1).map(|p| &pattern[p]);
+        let next = pattern.get(k + 1);
+        if prev.is_some_and(|p| p.is_punct("::"))
+            || next.is_some_and(|n| {
+                n.is_punct("(") || n.is_punct("{") || n.is_punct("::") || n.is_punct(":")
+            })
+        {
+            continue;
+        }
+        names.push(t);
+    }
+    names
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn join_tokens(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+// Type of an initializer that is a single literal, e.g. `5`, `6.4`, `"hi"`
+fn literal_type(init: &[Token]) -> Option<String> {
+    let [token] = init else {
+        return None;
+    };
+    let ty = match token.kind {
+        TokenKind::Str => "&str".to_string(),
+        TokenKind::Char => "char".to_string(),
+        TokenKind::Ident if token.text == "true" || token.text == "false" => "bool".to_string(),
+        TokenKind::Number => {
+            let text = &token.text;
+            let suffix = [
+                "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+                "usize", "f32", "f64",
+            ]
+            .into_iter()
+            .find(|s| text.ends_with(s) && !text.starts_with("0x"));
+            match suffix {
+                Some(s) => s.to_string(),
+                None if text.contains('.')
+                    || text.contains(['e', 'E']) && !text.starts_with("0x") =>
+                {
+                    "{float}".to_string()
+                }
+                None => "{integer}".to_string(),
+            }
+        }
+        _ => return None,
+    };
+    Some(ty)
+}
+
+fn type_change(
+    old: &Binding,
+    new: &Pending,
+    new_literal: Option<&str>,
+    init: &[Token],
+) -> TypeChange {
+    let old_type = old
+        .type_annotation
+        .as_deref()
+        .or(old.literal_type.as_deref());
+    let new_type = new.type_annotation.as_deref().or(new_literal);
+    if let (Some(a), Some(b)) = (old_type, new_type) {
+        return if a == b {
+            TypeChange::No
+        } else {
+            TypeChange::Yes
+        };
+    }
+
+    // `let x = x + 1` / `let x = x * 2`: arithmetic on the old value
+    let arithmetic = !init.is_empty()
+        && new.type_annotation.is_none()
+        && init.iter().any(|t| t.text == old.name)
+        && init.iter().all(|t| match t.kind {
+            TokenKind::Ident => t.text == old.name,
+            TokenKind::Number => true,
+            TokenKind::Punct => ["+", "-", "*", "/", "%", "(", ")"].contains(&t.text.as_str()),
+            _ => false,
+        });
+    if arithmetic {
+        TypeChange::No
+    } else {
+        TypeChange::Possibly
+    }
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "=== SHADOWED BINDINGS ===")?;
+        for s in &self.shadows {
+            let scope = if s.same_scope {
+                "same scope"
+            } else {
+                "outer scope"
+            };
+            writeln!(
+                f,
+                "{}:{}: `{}` shadows the binding from line {} ({scope}, type may change: {})",
+                s.file.display(),
+                s.line,
+                s.name,
+                s.shadowed_line,
+                s.type_change.as_str(),
+            )?;
+        }
+
+        writeln!(f, "\n=== UNUSED BINDINGS ===")?;
+        for u in &self.unused {
+            writeln!(
+                f,
+                "{}:{}: `{}` is never used",
+                u.file.display(),
+                u.line,
+                u.name
+            )?;
+        }
+
+        write!(
+            f,
+            "\n{} files scanned, {} shadowed bindings, {} unused bindings",
+            self.files_scanned,
+            self.shadows.len(),
+            self.unused.len()
+        )
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        let shadows: Vec<String> = self
+            .shadows
+            .iter()
+            .map(|s| {
+                format!(
+                    "{{\"file\":{},\"line\":{},\"name\":{},\"shadowed_line\":{},\"same_scope\":{},\"type_change\":\"{}\"}}",
+                    json_string(&s.file.display().to_string()),
+                    s.line,
+                    json_string(&s.name),
+                    s.shadowed_line,
+                    s.same_scope,
+                    s.type_change.as_str(),
+                )
+            })
+            .collect();
+        let unused: Vec<String> = self
+            .unused
+            .iter()
+            .map(|u| {
+                format!(
+                    "{{\"file\":{},\"line\":{},\"name\":{}}}",
+                    json_string(&u.file.display().to_string()),
+                    u.line,
+                    json_string(&u.name),
+                )
+            })
+            .collect();
+        format!(
+            "{{\"files_scanned\":{},\"shadows\":[{}],\"unused\":[{}]}}",
+            self.files_scanned,
+            shadows.join(","),
+            unused.join(",")
+        )
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(source: &str) -> Report {
+        let mut report = Report::default();
+        analyze_source(Path::new("test.rs"), source, &mut report);
+        report
+    }
+
+    // (name, line, shadowed_line, same_scope, type_change)
+    fn shadows(report: &Report) -> Vec<(&str, usize, usize, bool, TypeChange)> {
+        report
+            .shadows
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.line,
+                    s.shadowed_line,
+                    s.same_scope,
+                    s.type_change,
+                )
+            })
+            .collect()
+    }
+
+    fn unused(report: &Report) -> Vec<(&str, usize)> {
+        report
+            .unused
+            .iter()
+            .map(|u| (u.name.as_str(), u.line))
+            .collect()
+    }
+
+    #[test]
+    fn same_scope_and_outer_scope_shadowing() {
+        let report = analyze(
+            "fn main() {
+                let x = 5;
+                let x = x + 1;
+                {
+                    let x = x * 2;
+                    println!(\"{x}\");
+                }
+                println!(\"{x}\");
+            }",
+        );
+        assert_eq!(
+            shadows(&report),
+            [
+                ("x", 3, 2, true, TypeChange::No),
+                ("x", 5, 3, false, TypeChange::No),
+            ]
+        );
+        assert!(unused(&report).is_empty());
+    }
+
+    #[test]
+    fn type_changes() {
+        let report = analyze(
+            "fn main() {
+                let spaces = \"   \";
+                let spaces = spaces.len();
+                let n: u8 = 1;
+                let n: u8 = 2;
+                let f = 1;
+                let f = 6.4;
+                println!(\"{spaces} {n} {f}\");
+            }",
+        );
+        assert_eq!(
+            shadows(&report),
+            [
+                ("spaces", 3, 2, true, TypeChange::Possibly),
+                ("n", 5, 4, true, TypeChange::No),
+                ("f", 7, 6, true, TypeChange::Yes),
+            ]
+        );
+        // The shadowed `n` and `f` are never read
+        assert_eq!(unused(&report), [("n", 4), ("f", 6)]);
+    }
+
+    #[test]
+    fn parameters_and_loop_variables_are_bindings() {
+        let report = analyze(
+            "fn add(x: i32, y: i32) -> i32 {
+                for x in 0..y {
+                    println!(\"{x}\");
+                }
+                y
+            }",
+        );
+        assert_eq!(shadows(&report), [("x", 2, 1, false, TypeChange::Possibly)]);
+        assert_eq!(unused(&report), [("x", 1)]);
+    }
+
+    #[test]
+    fn if_let_chains_bind_for_the_rest_of_the_chain() {
+        let report = analyze(
+            "fn main() {
+                let a = Some(1);
+                if let Some(a) = a
+                    && let Some(b) = a.checked_add(1)
+                    && b > 1
+                {
+                    println!(\"{b}\");
+                }
+                let b = 0;
+                println!(\"{b}\");
+            }",
+        );
+        // The inner `a` shadows the outer one; the chain's `b` is out of
+        // scope again after the block, so the last `let b` shadows nothing
+        assert_eq!(shadows(&report), [("a", 3, 2, false, TypeChange::Possibly)]);
+        assert!(unused(&report).is_empty());
+    }
+
+    #[test]
+    fn format_strings_use_their_arguments() {
+        let report = analyze(
+            "fn main() {
+                let name = \"x\";
+                let width = 8;
+                let prec = 2;
+                let value = 1.5;
+                let escaped = 0;
+                println!(\"{name:>width$} {value:.prec$} {{escaped}}\");
+            }",
+        );
+        assert_eq!(unused(&report), [("escaped", 6)]);
+    }
+
+    #[test]
+    fn unused_tuple_fields() {
+        // The tuple lesson in datatypes/src/main.rs
+        let report = analyze(
+            "fn main() {
+                let x: (i32, f64, u8) = (500, 6.4, 1);
+                let five_hundred = x.0;
+                let six_point_four = x.1;
+                let one = x.2;
+                let _ignored = one;
+            }",
+        );
+        assert_eq!(
+            unused(&report),
+            [("five_hundred", 3), ("six_point_four", 4)]
+        );
+        assert!(report.shadows.is_empty());
+    }
+
+    #[test]
+    fn paths_fields_and_macros_are_not_uses() {
+        let report = analyze(
+            "fn main() {
+                let len = 1;
+                let value = 2;
+                let s = S { len: 3 };
+                let n = s.len + std::value;
+                println!(\"{n}\");
+            }",
+        );
+        assert_eq!(unused(&report), [("len", 2), ("value", 3)]);
+    }
+
+    #[test]
+    fn underscore_names_are_never_unused() {
+        let report = analyze("fn main() { let _x = 1; let (_, _y) = (1, 2); }");
+        assert!(unused(&report).is_empty());
+    }
+
+    #[test]
+    fn json_escapes_strings() {
+        let mut report = Report::default();
+        analyze_source(
+            Path::new("a \"b\".rs"),
+            "fn f() { let x = 1; }",
+            &mut report,
+        );
+        assert_eq!(
+            report.to_json(),
+            "{\"files_scanned\":1,\"shadows\":[],\"unused\":[{\"file\":\"a \\\"b\\\".rs\",\"line\":1,\"name\":\"x\"}]}"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+// Replays sections 4, 6 and 7 of main.rs with runtime-tracked borrows and
+// draws when each reference was live.
+//
+// The compiler ends a borrow at its last use (non-lexical lifetimes). A
+// runtime guard lives until it is dropped, so each `drop(r)` below sits
+// exactly where NLL would end the borrow.
+
+use borrowing_references::borrow_timeline::{BorrowError, BorrowKind, Timeline};
+
+fn main() -> Result<(), BorrowError> {
+    println!("=== BORROW TIMELINE ===\n");
+    let timeline = Timeline::new();
+
+    // ========================================================================
+    // 4. RESTRICTIONS ON MUTABLE REFERENCES
+    // ========================================================================
+    println!("4. RESTRICTIONS ON MUTABLE REFERENCES:");
+    let s3 = timeline.track("s3", String::from("hello"));
+
+    let mut r1 = s3.borrow_mut("r1")?;
+    r1.push_str(", world");
+    println!("   Modified through r1: '{}'", *r1);
+    drop(r1); // last use of r1
+
+    let mut r2 = s3.borrow_mut("r2")?;
+    r2.push_str(" again!");
+    println!("   Modified through r2: '{}'", *r2);
+    drop(r2);
+
+    // ========================================================================
+    // 5. TWO MUTABLE REFERENCES AT ONCE
+    // ========================================================================
+    println!("\n5. TWO MUTABLE REFERENCES AT ONCE (the commented-out code):");
+    let s4 = timeline.track("s4", String::from("hello"));
+
+    let r1 = s4.borrow_mut("r1")?;
+    match s4.borrow_mut("r2") {
+        Ok(_) => unreachable!("two exclusive borrows"),
+        Err(err) => {
+            println!("   error: {err}");
+            assert_eq!(err.requested, BorrowKind::Exclusive);
+            assert_eq!(err.held_by[0].reference, "r1");
+        }
+    }
+    drop(r1);
+
+    // ========================================================================
+    // 6. MUTABLE AND IMMUTABLE REFERENCES
+    // ========================================================================
+    println!("\n6. MUTABLE AND IMMUTABLE REFERENCES:");
+    let s5 = timeline.track("s5", String::from("hello"));
+
+    let immut1 = s5.borrow("immut1")?;
+    let immut2 = s5.borrow("immut2")?;
+    println!("   Immutable references: '{}', '{}'", *immut1, *immut2);
+
+    // Too early: both shared borrows are still live
+    if let Err(err) = s5.borrow_mut("early_mut") {
+        println!("   error: {err}");
+        assert_eq!(err.held_by.len(), 2);
+    }
+    drop(immut1);
+    drop(immut2);
+
+    let mut mut_ref = s5.borrow_mut("mut_ref")?;
+    mut_ref.push_str(" world");
+    println!("   After immutable refs done: mutable ref = '{}'", *mut_ref);
+    drop(mut_ref);
+
+    // ========================================================================
+    // 7. REFERENCE SCOPES
+    // ========================================================================
+    println!("\n7. REFERENCE SCOPES (NLL):");
+    let s6 = timeline.track("s6", String::from("hello"));
+
+    let ref1 = s6.borrow("ref1")?;
+    let ref2 = s6.borrow("ref2")?;
+    println!("   Using immutable refs: {}, {}", *ref1, *ref2);
+    drop(ref1);
+    drop(ref2);
+
+    let mut ref3 = s6.borrow_mut("ref3")?;
+    ref3.push_str(" mutable");
+    println!(
+        "   Mutable reference after immutable ones done: '{}'",
+        *ref3
+    );
+
+    // ========================================================================
+    // TIMELINE
+    // ========================================================================
+    println!("\n=== TIMELINE ===");
+    println!("   [ starts a borrow, ] ends it, - shared, = exclusive, X refused, > still live\n");
+    for line in timeline.render().lines() {
+        println!("   {line}");
+    }
+
+    let conflicts = timeline.conflicts();
+    println!("\n=== CONFLICTS ({}) ===", conflicts.len());
+    for conflict in &conflicts {
+        println!("   {conflict}");
+    }
+    assert_eq!(conflicts.len(), 2);
+
+    // ref3 is still live, so s6 is not finished yet
+    assert!(timeline.records().last().unwrap().end.is_none());
+    drop(ref3);
+    println!("\n   s6 = '{}'", s6.into_inner());
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// Section 5 (PREVENTING DATA RACES) for real: several threads applying
+// bonuses to the same scores and reading the average.
+//
+// Run with optimizations for meaningful timings:
+//   cargo run --release --bin concurrent_scores
+
+use std::time::Duration;
+
+use borrowing_references::concurrent_scores::{self, Strategy, Workload};
+use ownership::lcg::Lcg;
+
+const STRESS_ROUNDS: usize = 40;
+
+fn main() {
+    println!("=== CONCURRENT SCORE UPDATES ===\n");
+
+    // ========================================================================
+    // 1. THE LESSON'S SCORES, FOUR THREADS
+    // ========================================================================
+    println!("1. FOUR THREADS, vec![85, 92, 78], 250 one-point bonuses per thread:");
+
+    let workload = Workload {
+        initial: vec![85, 92, 78],
+        threads: 4,
+        bonuses_per_thread: 250,
+        reads_per_thread: 250,
+        bonus: 1,
+    };
+    for strategy in Strategy::ALL {
+        let outcome = strategy.run(&workload);
+        println!(
+            "   {:<12} final {:?}, {} reads, {} torn",
+            strategy.name(),
+            outcome.scores,
+            outcome.reads,
+            outcome.torn_reads
+        );
+        assert!(outcome.is_correct(&workload));
+    }
+    println!("   Expected    {:?}", workload.expected_scores());
+    println!("   Sharing `&mut Vec` between threads would not compile; these three do");
+
+    // ========================================================================
+    // 2. STRESS TEST
+    // ========================================================================
+    println!("\n2. STRESS TEST ({STRESS_ROUNDS} runs per strategy, 1-8 threads):");
+
+    let mut rng = Lcg::new(0x5eed);
+    for strategy in Strategy::ALL {
+        let result = concurrent_scores::stress(strategy, STRESS_ROUNDS, 500, &mut rng);
+        println!(
+            "   {:<12} {} runs, {} bonuses: {} wrong totals, {} torn reads",
+            strategy.name(),
+            result.runs,
+            result.bonuses,
+            result.wrong,
+            result.torn_reads
+        );
+        assert_eq!(
+            (result.wrong, result.torn_reads),
+            (0, 0),
+            "{} lost updates",
+            strategy.name()
+        );
+    }
+
+    // ========================================================================
+    // 3. TIMING UNDER CONTENTION
+    // ========================================================================
+    println!("\n3. TIMING (1,000 scores, 2,000 operations per thread):");
+    println!(
+        "   {:<14} {:>7} {:>12} {:>12} {:>12}",
+        "mix", "threads", "Arc<Mutex>", "Arc<RwLock>", "channel"
+    );
+
+    for (mix, bonuses, reads) in [("write-heavy", 1_800, 200), ("read-heavy", 200, 1_800)] {
+        for threads in [1, 2, 4, 8] {
+            let workload = Workload {
+                initial: (0..1_000).collect(),
+                threads,
+                bonuses_per_thread: bonuses,
+                reads_per_thread: reads,
+                bonus: 1,
+            };
+            let times: Vec<Duration> = Strategy::ALL
+                .iter()
+                .map(|strategy| {
+                    let outcome = strategy.run(&workload);
+                    assert!(outcome.is_correct(&workload));
+                    outcome.elapsed
+                })
+                .collect();
+            println!(
+                "   {:<14} {:>7} {:>12} {:>12} {:>12}",
+                mix,
+                threads,
+                format!("{:.1?}", times[0]),
+                format!("{:.1?}", times[1]),
+                format!("{:.1?}", times[2])
+            );
+        }
+    }
+}
@@ -0,0 +1,200 @@
+// Gradebook CLI: import scores, apply and revert curves, export a report.
+//
+//   cargo run --bin gradebook                          # demo with the lesson's scores
+//   cargo run --bin gradebook -- [--book FILE] COMMAND
+//
+// Commands (the gradebook is kept in FILE, default gradebook.csv):
+//   import SCORES.csv        start a gradebook from `name,score` rows
+//   curve flat N             add N points to everyone
+//   curve scale-mean N       scale scores so the mean becomes N
+//   curve cap N              lower scores above N to N
+//   cutoffs A=90,B=80,...    set the letter grade cutoffs
+//   history                  list applied curves
+//   revert N                 undo curve #N and replay the others
+//   show                     print scores, letters and statistics
+//   report [OUT.csv]         export name,original,score,letter
+
+use std::fs::File;
+use std::io::{self, BufReader};
+
+use borrowing_references::gradebook::{Curve, GradeScale, Gradebook, GradebookError};
+use borrowing_references::stats;
+
+const USAGE: &str = "usage: gradebook [--book FILE] \
+    (import SCORES.csv | curve flat|scale-mean|cap N | cutoffs A=90,B=80,... \
+    | history | revert N | show | report [OUT.csv])";
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        demo();
+        return;
+    }
+
+    let mut book_path = String::from("gradebook.csv");
+    if args[0] == "--book" {
+        if args.len() < 3 {
+            exit_with(USAGE);
+        }
+        book_path = args.remove(1);
+        args.remove(0);
+    }
+
+    if let Err(err) = run(&book_path, &args) {
+        exit_with(&format!("error: {err}"));
+    }
+}
+
+fn run(book_path: &str, args: &[String]) -> Result<(), GradebookError> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["import", scores] => {
+            let book = Gradebook::import_csv(BufReader::new(File::open(scores)?))?;
+            book.save(book_path)?;
+            println!(
+                "Imported {} students into {book_path}",
+                book.students().len()
+            );
+        }
+        ["curve", kind, value] => {
+            let curve: Curve = format!("{kind} {value}").parse()?;
+            let mut book = Gradebook::load(book_path)?;
+            book.apply(curve)?;
+            book.save(book_path)?;
+            println!("Applied #{}: {curve}", book.history().len());
+        }
+        ["cutoffs", cutoffs] => {
+            let mut book = Gradebook::load(book_path)?;
+            book.set_scale(cutoffs.parse()?);
+            book.save(book_path)?;
+            println!("Cutoffs: {}", book.scale());
+        }
+        ["history"] => print_history(&Gradebook::load(book_path)?),
+        ["revert", number] => {
+            let Ok(number) = number.parse() else {
+                exit_with(USAGE);
+            };
+            let mut book = Gradebook::load(book_path)?;
+            let curve = book.revert(number)?;
+            book.save(book_path)?;
+            println!("Reverted #{number}: {curve}");
+        }
+        ["show"] => print_book(&Gradebook::load(book_path)?)?,
+        ["report"] => Gradebook::load(book_path)?.write_report(io::stdout().lock())?,
+        ["report", out] => {
+            Gradebook::load(book_path)?.write_report(File::create(out)?)?;
+            println!("Report written to {out}");
+        }
+        _ => exit_with(USAGE),
+    }
+    Ok(())
+}
+
+fn print_history(book: &Gradebook) {
+    if book.history().is_empty() {
+        println!("   No curves applied");
+    }
+    for (i, curve) in book.history().iter().enumerate() {
+        println!("   #{} {curve}", i + 1);
+    }
+}
+
+fn print_book(book: &Gradebook) -> Result<(), GradebookError> {
+    println!("   {:<20} {:>8} {:>8}  letter", "name", "original", "score");
+    for row in book.report() {
+        println!(
+            "   {:<20} {:>8.1} {:>8.1}  {}",
+            row.name, row.original, row.score, row.letter
+        );
+    }
+    let scores = book.scores();
+    println!(
+        "   mean {:.1}, median {:.1}, std dev {:.1}, cutoffs {}",
+        stats::mean(&scores)?,
+        stats::median(&scores)?,
+        stats::std_dev(&scores)?,
+        book.scale()
+    );
+    Ok(())
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
+
+// The lesson's `vec![85, 92, 78]`, curved without losing the originals
+fn demo() {
+    println!("=== GRADEBOOK ===\n");
+
+    // ========================================================================
+    // 1. IMPORT
+    // ========================================================================
+    println!("1. IMPORT FROM CSV:");
+    let csv = "name,score\nAlice,85\nBob,92\nCarol,78\n\"Dan, Jr.\",61\n";
+    let mut book = Gradebook::import_csv(csv.as_bytes()).unwrap();
+    print_book(&book).unwrap();
+
+    // ========================================================================
+    // 2. CURVES
+    // ========================================================================
+    println!("\n2. APPLYING CURVES:");
+    for curve in [
+        Curve::Flat(5.0),
+        Curve::ScaleToMean(88.0),
+        Curve::Cap(100.0),
+    ] {
+        book.apply(curve).unwrap();
+        println!("   applied {curve}: {:?}", rounded(&book.scores()));
+    }
+    assert_eq!(book.students()[1].score, 100.0);
+    assert!((stats::mean(&book.scores()).unwrap() - 87.6).abs() < 0.1);
+    print_history(&book);
+
+    // ========================================================================
+    // 3. REVERTING
+    // ========================================================================
+    println!("\n3. REVERTING #1 (flat 5):");
+    let removed = book.revert(1).unwrap();
+    println!("   removed {removed}, replayed the rest on the originals");
+    println!("   scores: {:?}", rounded(&book.scores()));
+    print_history(&book);
+    assert_eq!(
+        book.history(),
+        [Curve::ScaleToMean(88.0), Curve::Cap(100.0)]
+    );
+
+    book.revert(2).unwrap();
+    book.revert(1).unwrap();
+    let originals: Vec<f64> = book.students().iter().map(|s| s.original).collect();
+    assert_eq!(book.scores(), originals);
+    println!("   after reverting everything: {:?}", book.scores());
+    println!("   revert(1) again: {}", book.revert(1).unwrap_err());
+
+    // ========================================================================
+    // 4. LETTER GRADES AND REPORT
+    // ========================================================================
+    println!("\n4. CUSTOM CUTOFFS AND REPORT:");
+    let scale: GradeScale = "A=93,A-=90,B+=87,B=83,C=70,F=0".parse().unwrap();
+    book.set_scale(scale);
+    book.apply(Curve::Flat(3.0)).unwrap();
+    let letters: Vec<&str> = book.report().map(|row| row.letter).collect();
+    assert_eq!(letters, ["B+", "A", "C", "F"]);
+    let mut report = Vec::new();
+    book.write_report(&mut report).unwrap();
+    for line in String::from_utf8(report).unwrap().lines() {
+        println!("   {line}");
+    }
+
+    for bad in ["A=90,B=90", "A90", "A=x"] {
+        println!("   {bad:?} -> {}", bad.parse::<GradeScale>().unwrap_err());
+    }
+    println!(
+        "   \"bonus 5\" -> {}",
+        "bonus 5".parse::<Curve>().unwrap_err()
+    );
+}
+
+fn rounded(scores: &[f64]) -> Vec<f64> {
+    scores.iter().map(|s| (s * 10.0).round() / 10.0).collect()
+}
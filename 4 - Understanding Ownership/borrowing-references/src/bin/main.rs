@@ -1,9 +1,17 @@
+use ownership::alloc_counter::{CountingAllocator, Probe, Sections};
+
+// Counts every heap allocation so each section can report its heap traffic
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 fn main() {
     println!("=== RUST REFERENCES AND BORROWING ===\n");
+    let mut sections = Sections::with_capacity(16);
     
     // ========================================================================
     // 1. INTRODUCTION TO REFERENCES
     // ========================================================================
+    sections.begin("1. Introduction to references");
     println!("1. INTRODUCTION TO REFERENCES:");
     println!("   - References allow you to refer to a value without taking ownership");
     println!("   - Created using the & operator");
@@ -12,16 +20,20 @@ fn main() {
     // ========================================================================
     // 2. IMMUTABLE REFERENCES (BORROWING)
     // ========================================================================
+    sections.begin("2. Immutable references");
     println!("2. IMMUTABLE REFERENCES (Borrowing):");
     
     let s1 = String::from("hello");
     println!("   Created s1 = '{}'", s1);
     
     // Pass a reference to s1 (borrow it)
+    let probe = Probe::start();
     let len = calculate_length(&s1);
+    let borrow_heap = probe.finish();
     
     println!("   Length of '{}' is {}", s1, len);
     println!("   s1 is STILL valid - we only borrowed it!");
+    println!("   Heap traffic of the borrow: {}", borrow_heap);
     
     // Multiple immutable references allowed
     let r1 = &s1;
@@ -33,6 +45,7 @@ fn main() {
     // ========================================================================
     // 3. MUTABLE REFERENCES
     // ========================================================================
+    sections.begin("3. Mutable references");
     println!("\n3. MUTABLE REFERENCES:");
     
     let mut s2 = String::from("hello");
@@ -45,6 +58,7 @@ fn main() {
     // ========================================================================
     // 4. RESTRICTIONS ON MUTABLE REFERENCES
     // ========================================================================
+    sections.begin("4. Restrictions on mutable refs");
     println!("\n4. RESTRICTIONS ON MUTABLE REFERENCES:");
     
     let mut s3 = String::from("hello");
@@ -64,6 +78,7 @@ fn main() {
     // ========================================================================
     // 5. DATA RACES PREVENTION
     // ========================================================================
+    sections.begin("5. Preventing data races");
     println!("\n5. PREVENTING DATA RACES:");
     
     let mut s4 = String::from("hello");
@@ -76,10 +91,13 @@ fn main() {
     */
     println!("   Cannot have two mutable references at the same time");
     println!("   This prevents data races at compile time!");
+    println!("   Threads share data through Arc<Mutex>, RwLock or channels instead:");
+    println!("   cargo run --release --bin concurrent_scores");
     
     // ========================================================================
     // 6. MUTABLE AND IMMUTABLE REFERENCES CAN'T COEXIST
     // ========================================================================
+    sections.begin("6. Mutable and immutable refs");
     println!("\n6. MUTABLE AND IMMUTABLE REFERENCES:");
     
     let mut s5 = String::from("hello");
@@ -97,6 +115,7 @@ fn main() {
     // ========================================================================
     // 7. REFERENCE SCOPES
     // ========================================================================
+    sections.begin("7. Reference scopes");
     println!("\n7. REFERENCE SCOPES (NLL - Non-Lexical Lifetimes):");
     
     let mut s6 = String::from("hello");
@@ -114,6 +133,7 @@ fn main() {
     // ========================================================================
     // 8. DANGLING REFERENCES PREVENTION
     // ========================================================================
+    sections.begin("8. Preventing dangling refs");
     println!("\n8. PREVENTING DANGLING REFERENCES:");
     
     // This would create a dangling reference (commented out):
@@ -128,6 +148,7 @@ fn main() {
     // ========================================================================
     // 9. BORROWING WITH FUNCTIONS
     // ========================================================================
+    sections.begin("9. Borrowing with functions");
     println!("\n9. BORROWING WITH FUNCTIONS:");
     
     let s7 = String::from("I'm borrowed");
@@ -141,6 +162,7 @@ fn main() {
     // ========================================================================
     // 10. BORROWING RULES SUMMARY
     // ========================================================================
+    sections.begin("10. Borrowing rules summary");
     println!("\n=== BORROWING RULES SUMMARY ===");
     println!("1. At any given time, you can have EITHER:");
     println!("   - One mutable reference");
@@ -152,6 +174,7 @@ fn main() {
     // ========================================================================
     // 11. PRACTICAL EXAMPLES
     // ========================================================================
+    sections.begin("11. Practical examples");
     println!("\n=== PRACTICAL EXAMPLES ===");
     
     // Example 1: Reading without ownership
@@ -171,6 +194,10 @@ fn main() {
     let sum = calculate_sum(&data);
     let avg = calculate_average(&data);
     println!("   Data: {:?}, Sum: {}, Average: {}", data, sum, avg);
+
+    sections.end();
+    println!("\n=== HEAP TRAFFIC PER SECTION ===");
+    print!("{}", sections);
 }
 
 // ============================================================================
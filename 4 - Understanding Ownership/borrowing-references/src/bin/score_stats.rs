@@ -0,0 +1,187 @@
+// Statistics over borrowed score slices, compared with the lesson helpers.
+//
+//   cargo run --bin score_stats            # demo
+//   cargo run --bin score_stats FILE       # stream numbers from FILE, one per line
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use borrowing_references::stats::{self, Accumulator, StatsError};
+
+fn main() {
+    if let Some(path) = std::env::args().nth(1) {
+        stream_file(&path);
+        return;
+    }
+
+    println!("=== SCORE STATISTICS ===\n");
+
+    // ========================================================================
+    // 1. WHERE THE LESSON HELPERS BREAK
+    // ========================================================================
+    println!("1. WHERE THE LESSON HELPERS BREAK:");
+
+    let big = vec![i32::MAX, i32::MAX, 2];
+    println!(
+        "   calculate_sum({big:?}) with checked_add: {:?}",
+        big.iter().try_fold(0i32, |total, &x| total.checked_add(x))
+    );
+    println!("   stats::sum(&big) = {}", stats::sum(&big));
+    assert_eq!(stats::sum(&big), 2 * i32::MAX as i128 + 2);
+
+    let empty: Vec<i32> = Vec::new();
+    println!("   calculate_average(&[]) = {}", calculate_average(&empty));
+    println!("   stats::mean(&[]) = {:?}", stats::mean(&empty));
+    assert_eq!(stats::mean(&empty), Err(StatsError::Empty));
+
+    // ========================================================================
+    // 2. DESCRIBING A SCORE VECTOR
+    // ========================================================================
+    println!("\n2. DESCRIBING SCORES:");
+
+    let scores = vec![85, 92, 78, 92, 64, 71, 88, 92, 78, 99];
+    println!("   scores = {scores:?}");
+    // Each call only borrows `scores`
+    let mean = stats::mean(&scores).unwrap();
+    let median = stats::median(&scores).unwrap();
+    let (modes, count) = stats::mode(&scores).unwrap();
+    let variance = stats::variance(&scores).unwrap();
+    let std_dev = stats::std_dev(&scores).unwrap();
+    let sample_std_dev = stats::sample_std_dev(&scores).unwrap();
+    let quartiles = stats::percentiles(&scores, &[25.0, 50.0, 75.0, 90.0]).unwrap();
+    println!("   sum            = {}", stats::sum(&scores));
+    println!("   mean           = {mean:.2}");
+    println!("   median         = {median}");
+    println!("   mode           = {modes:?} ({count} times)");
+    println!("   variance       = {variance:.2}");
+    println!("   std dev        = {std_dev:.2} (sample: {sample_std_dev:.2})");
+    println!("   p25/p50/p75/p90 = {quartiles:?}");
+    println!(
+        "   scores still usable after borrowing: {} entries",
+        scores.len()
+    );
+
+    assert_eq!(mean, 83.9);
+    assert_eq!(median, 86.5);
+    assert_eq!((modes, count), (vec![92], 3));
+    assert!((variance - 107.49).abs() < 1e-9);
+    assert_eq!(stats::percentile(&scores, 0.0), Ok(64.0));
+    assert_eq!(stats::percentile(&scores, 100.0), Ok(99.0));
+
+    // ========================================================================
+    // 3. ANY NUMBER TYPE, AND ERRORS INSTEAD OF NONSENSE
+    // ========================================================================
+    println!("\n3. OTHER TYPES AND ERRORS:");
+
+    let bytes: [u8; 4] = [250, 250, 250, 250];
+    println!("   sum of {bytes:?} as u8 values = {}", stats::sum(&bytes));
+    let grades = [3.7f32, 2.0, 4.0, 3.3];
+    println!(
+        "   median of {grades:?} = {:?}",
+        stats::median(&grades).unwrap()
+    );
+    println!(
+        "   mode of [1, 2, 2, 3, 3] = {:?}",
+        stats::mode(&[1, 2, 2, 3, 3]).unwrap()
+    );
+
+    let errors = [
+        ("mean(&[] as &[f64])", stats::mean::<f64>(&[]).unwrap_err()),
+        (
+            "sample_variance(&[7])",
+            stats::sample_variance(&[7]).unwrap_err(),
+        ),
+        (
+            "median(&[1.0, NaN])",
+            stats::median(&[1.0, f64::NAN]).unwrap_err(),
+        ),
+        (
+            "percentile(&scores, 101.0)",
+            stats::percentile(&scores, 101.0).unwrap_err(),
+        ),
+    ];
+    for (call, err) in errors {
+        println!("   {call:<28} -> error: {err}");
+    }
+
+    // ========================================================================
+    // 4. STREAMING ACCUMULATOR
+    // ========================================================================
+    println!("\n4. STREAMING ACCUMULATOR:");
+
+    // Same answers as the slice functions
+    let acc: Accumulator<i32> = scores.iter().copied().collect();
+    assert_eq!(acc.mean(), stats::mean(&scores));
+    assert!((acc.variance().unwrap() - variance).abs() < 1e-9);
+
+    // Ten million values that are never stored
+    let mut acc = Accumulator::new();
+    acc.extend((0..10_000_000u64).map(|i| i * 1_000_003 % 1_000));
+    println!(
+        "   10,000,000 generated values: count {}, sum {}, mean {:.3}, std dev {:.3}, min {}, max {}",
+        acc.count(),
+        acc.sum(),
+        acc.mean().unwrap(),
+        acc.std_dev().unwrap(),
+        acc.min().unwrap(),
+        acc.max().unwrap()
+    );
+
+    // Chunks can be summarized separately and merged
+    let (left, right) = scores.split_at(4);
+    let mut merged: Accumulator<i32> = left.iter().copied().collect();
+    merged.merge(&right.iter().copied().collect());
+    assert_eq!(merged.count(), scores.len());
+    assert!((merged.mean().unwrap() - mean).abs() < 1e-9);
+    assert!((merged.variance().unwrap() - variance).abs() < 1e-9);
+    println!(
+        "   merged halves {:?} + {:?}: mean {:.2}, variance {:.2}",
+        left,
+        right,
+        merged.mean().unwrap(),
+        merged.variance().unwrap()
+    );
+}
+
+// Reads one number per line without loading the file into memory
+fn stream_file(path: &str) {
+    let file = File::open(path).unwrap_or_else(|e| {
+        eprintln!("cannot open {path}: {e}");
+        std::process::exit(1);
+    });
+    let mut acc = Accumulator::<f64>::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("{path}: {e}");
+            std::process::exit(1);
+        });
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(value) => acc.push(value),
+            Err(_) => eprintln!("{path}:{}: skipping {line:?}", i + 1),
+        }
+    }
+    println!("count    {}", acc.count());
+    for (name, value) in [
+        ("sum", Ok(acc.sum())),
+        ("mean", acc.mean()),
+        ("std dev", acc.std_dev()),
+        ("min", acc.min()),
+        ("max", acc.max()),
+    ] {
+        match value {
+            Ok(value) => println!("{name:<8} {value}"),
+            Err(err) => println!("{name:<8} error: {err}"),
+        }
+    }
+}
+
+// The lesson's version, for comparison (signature kept as in main.rs)
+#[allow(clippy::ptr_arg)]
+fn calculate_average(numbers: &Vec<i32>) -> f64 {
+    let sum: i32 = numbers.iter().sum();
+    sum as f64 / numbers.len() as f64
+}
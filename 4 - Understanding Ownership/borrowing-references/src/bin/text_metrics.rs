@@ -0,0 +1,151 @@
+// Bytes, chars, grapheme clusters and display columns, side by side.
+//
+//   cargo run --bin text_metrics             # demo
+//   cargo run --bin text_metrics TEXT...     # measure your own strings
+
+use borrowing_references::text_metrics::{self, Metrics};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        print_header();
+        for text in &args {
+            print_row(text);
+        }
+        return;
+    }
+
+    println!("=== TEXT METRICS ===\n");
+
+    // ========================================================================
+    // 1. FOUR LENGTHS
+    // ========================================================================
+    println!("1. calculate_length(&s) IS THE BYTE COUNT:");
+    print_header();
+
+    // (text, chars, graphemes, columns)
+    let samples = [
+        ("hello", 5, 5, 5),
+        ("नमस्ते", 6, 3, 4),
+        ("e\u{301}te\u{301}", 5, 3, 3),
+        ("👍🏽", 2, 1, 2),
+        ("👨‍👩‍👧‍👦", 7, 1, 2),
+        ("🇯🇵🇫🇷", 4, 2, 4),
+        ("❤️", 2, 1, 2),
+        ("한국어", 3, 3, 6),
+        ("\u{1112}\u{1161}\u{11AB}", 3, 1, 2),
+        ("日本語 text", 8, 8, 11),
+        ("a\r\nb", 4, 3, 2),
+    ];
+    for (text, chars, graphemes, columns) in samples {
+        print_row(text);
+        let expected = Metrics {
+            bytes: text.len(),
+            chars,
+            graphemes,
+            columns,
+        };
+        assert_eq!(text_metrics::metrics(text), expected, "{text:?}");
+    }
+
+    let clusters: Vec<&str> = text_metrics::graphemes("नमस्ते").collect();
+    println!("\n   graphemes(\"नमस्ते\") = [{}]", quote_all(&clusters));
+    assert_eq!(clusters, ["न", "म", "स्ते"]);
+
+    // ========================================================================
+    // 2. SLICING WITHOUT SPLITTING CLUSTERS
+    // ========================================================================
+    println!("\n2. SLICING BY GRAPHEMES AND COLUMNS:");
+
+    let family = "hi 👨‍👩‍👧‍👦 and 🇯🇵!";
+    println!("   text: {}", quote(family));
+    let by_graphemes = text_metrics::slice_graphemes(family, 3..4);
+    println!("   slice_graphemes(3..4) = {}", quote(by_graphemes));
+    assert_eq!(by_graphemes, "👨‍👩‍👧‍👦");
+
+    // Column 4 is the middle of the wide family emoji, so it is left out
+    let by_columns = text_metrics::slice_columns(family, 0..4);
+    println!("   slice_columns(0..4)   = {}", quote(by_columns));
+    assert_eq!(by_columns, "hi ");
+    assert_eq!(text_metrics::slice_columns(family, 3..5), "👨‍👩‍👧‍👦");
+
+    println!(
+        "   byte slice &text[0..5] would panic: 5 is inside the emoji (char boundary: {})",
+        family.is_char_boundary(5)
+    );
+
+    // ========================================================================
+    // 3. TRUNCATING WITH AN ELLIPSIS
+    // ========================================================================
+    println!("\n3. TRUNCATING:");
+
+    let title = "नमस्ते दुनिया 🌍 – hello world";
+    println!(
+        "   text: {} ({} columns)",
+        quote(title),
+        text_metrics::width(title)
+    );
+    for max in [30, 12, 8, 5] {
+        let cut = text_metrics::truncate_columns(title, max, Some("…"));
+        println!(
+            "   truncate_columns({max:>2}, \"…\") = {} ({} columns)",
+            pad(&quote(&cut), 34),
+            text_metrics::width(&cut)
+        );
+        assert!(text_metrics::width(&cut) <= max);
+    }
+    for max in [6, 3] {
+        let cut = text_metrics::truncate_graphemes(title, max, Some("…"));
+        println!("   truncate_graphemes({max}, \"…\") = {}", quote(&cut));
+    }
+    assert_eq!(text_metrics::truncate_graphemes("नमस्ते", 2, None), "नम");
+    assert_eq!(text_metrics::truncate_columns("👍🏽👍🏽", 3, None), "👍🏽");
+}
+
+fn print_header() {
+    println!(
+        "   {:<24} {:>6} {:>6} {:>10} {:>8}",
+        "text", "bytes", "chars", "graphemes", "columns"
+    );
+}
+
+fn print_row(text: &str) {
+    let m = text_metrics::metrics(text);
+    println!(
+        "   {} {:>6} {:>6} {:>10} {:>8}",
+        pad(&quote(text), 24),
+        m.bytes,
+        m.chars,
+        m.graphemes,
+        m.columns
+    );
+}
+
+// Quotes text, escaping only control characters, so combining marks and
+// emoji sequences are shown as they render
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        if c.is_control() {
+            quoted.extend(c.escape_default());
+        } else {
+            quoted.push(c);
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn quote_all(texts: &[&str]) -> String {
+    texts
+        .iter()
+        .map(|t| quote(t))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Pads to `columns` display columns; `{:<n}` would count chars instead
+fn pad(text: &str, columns: usize) -> String {
+    let padding = columns.saturating_sub(text_metrics::width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
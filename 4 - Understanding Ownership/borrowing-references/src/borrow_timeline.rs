@@ -0,0 +1,469 @@
+// Runtime borrow tracking with a timeline of who borrowed what, and when.
+//
+// The compiler checks borrows statically, so sections 4, 6 and 7 of main.rs
+// can only describe when `r1`, `immut1` or `ref3` stop being live. Here a
+// `Tracked<T>` keeps its value in a `RefCell` (the same rules, checked at
+// runtime) and every borrow is given a name. Starting and ending a borrow
+// advances a shared clock, so the whole history can be drawn as a timeline:
+//
+//     time             0123456
+//     s5  immut1  &    [---]
+//         immut2  &     [-]
+//         mut_ref &mut      [=]
+//
+// Rows belong to a `Tracked` value, not to a name: a shadowed `s` and the
+// `s` that shadows it are two variables, and get two groups of rows.
+//
+// A borrow that breaks the rules is refused with a `BorrowError` naming the
+// borrows in the way, instead of the panic `RefCell::borrow_mut` would give.
+
+use std::cell::{Ref, RefCell, RefMut};
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorrowKind {
+    /// `&value`
+    Shared,
+    /// `&mut value`
+    Exclusive,
+}
+
+impl BorrowKind {
+    fn sigil(self) -> &'static str {
+        match self {
+            BorrowKind::Shared => "&",
+            BorrowKind::Exclusive => "&mut",
+        }
+    }
+
+    fn fill(self) -> char {
+        match self {
+            BorrowKind::Shared => '-',
+            BorrowKind::Exclusive => '=',
+        }
+    }
+}
+
+/// A live borrow that blocked a new one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    pub reference: String,
+    pub kind: BorrowKind,
+    pub since: usize,
+}
+
+/// A refused borrow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowError {
+    pub variable: String,
+    pub reference: String,
+    pub requested: BorrowKind,
+    pub at: usize,
+    pub held_by: Vec<Holder>,
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wanted = match self.requested {
+            BorrowKind::Shared => "shared",
+            BorrowKind::Exclusive => "mutable",
+        };
+        write!(
+            f,
+            "t={}: cannot borrow `{}` as {wanted} (`{}`): already borrowed by",
+            self.at, self.variable, self.reference
+        )?;
+        for (i, holder) in self.held_by.iter().enumerate() {
+            let sep = if i == 0 { "" } else { "," };
+            write!(
+                f,
+                "{sep} `{}` ({} since t={})",
+                holder.reference,
+                holder.kind.sigil(),
+                holder.since
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for BorrowError {}
+
+/// One borrow, successful or refused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Which `track` call the variable came from, counting from 0; two
+    /// variables can have the same name
+    pub variable_id: usize,
+    pub variable: String,
+    pub reference: String,
+    pub kind: BorrowKind,
+    pub start: usize,
+    /// None while the borrow is still live (or if it was refused)
+    pub end: Option<usize>,
+    pub refused: bool,
+}
+
+#[derive(Debug, Default)]
+struct Log {
+    clock: usize,
+    variables: Vec<String>,
+    records: Vec<Record>,
+    conflicts: Vec<BorrowError>,
+}
+
+impl Log {
+    fn tick(&mut self) -> usize {
+        self.clock += 1;
+        self.clock - 1
+    }
+
+    fn holders(&self, variable_id: usize) -> Vec<Holder> {
+        self.records
+            .iter()
+            .filter(|r| r.variable_id == variable_id && r.end.is_none() && !r.refused)
+            .map(|r| Holder {
+                reference: r.reference.clone(),
+                kind: r.kind,
+                since: r.start,
+            })
+            .collect()
+    }
+
+    fn start(&mut self, variable_id: usize, reference: &str, kind: BorrowKind, ok: bool) -> usize {
+        let at = self.tick();
+        let variable = &self.variables[variable_id];
+        if !ok {
+            let error = BorrowError {
+                variable: variable.clone(),
+                reference: reference.to_string(),
+                requested: kind,
+                at,
+                held_by: self.holders(variable_id),
+            };
+            self.conflicts.push(error);
+        }
+        self.records.push(Record {
+            variable_id,
+            variable: variable.clone(),
+            reference: reference.to_string(),
+            kind,
+            start: at,
+            end: None,
+            refused: !ok,
+        });
+        self.records.len() - 1
+    }
+
+    fn end(&mut self, id: usize) {
+        let at = self.tick();
+        self.records[id].end = Some(at);
+    }
+}
+
+// ============================================================================
+// Timeline
+// ============================================================================
+
+/// Shared clock and history for any number of tracked variables
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    log: Rc<RefCell<Log>>,
+}
+
+impl Timeline {
+    pub fn new() -> Timeline {
+        Timeline::default()
+    }
+
+    /// Starts tracking `value` under the name `variable`
+    pub fn track<T>(&self, variable: &str, value: T) -> Tracked<T> {
+        let mut log = self.log.borrow_mut();
+        log.variables.push(variable.to_string());
+        Tracked {
+            id: log.variables.len() - 1,
+            variable: variable.to_string(),
+            value: RefCell::new(value),
+            log: Rc::clone(&self.log),
+        }
+    }
+
+    pub fn records(&self) -> Vec<Record> {
+        self.log.borrow().records.clone()
+    }
+
+    pub fn conflicts(&self) -> Vec<BorrowError> {
+        self.log.borrow().conflicts.clone()
+    }
+
+    /// Current time; the next borrow starts here
+    pub fn now(&self) -> usize {
+        self.log.borrow().clock
+    }
+
+    /// One row per borrow, grouped by tracked variable. `[` marks the start, `]` the
+    /// end, `-` a shared and `=` an exclusive borrow, `X` a refused one and
+    /// `>` a borrow that is still live.
+    pub fn render(&self) -> String {
+        let log = self.log.borrow();
+        let name_width = log.variables.iter().map(String::len).max().unwrap_or(0);
+        let ref_width = log
+            .records
+            .iter()
+            .map(|r| r.reference.len())
+            .max()
+            .unwrap_or(0);
+        let label_width = name_width + ref_width + 8;
+
+        let mut out = String::new();
+        let digits: String = (0..log.clock)
+            .map(|t| char::from_digit((t % 10) as u32, 10).unwrap())
+            .collect();
+        let _ = writeln!(out, "{:<label_width$}{digits}", "time");
+
+        for (id, variable) in log.variables.iter().enumerate() {
+            let mut first = true;
+            for record in log.records.iter().filter(|r| r.variable_id == id) {
+                let name = if first { variable.as_str() } else { "" };
+                first = false;
+                let label = format!(
+                    "{name:<name_width$}  {:<ref_width$} {}",
+                    record.reference,
+                    record.kind.sigil()
+                );
+                let _ = write!(out, "{label:<label_width$}");
+                if record.refused {
+                    let _ = writeln!(out, "{:>1$} refused", 'X', record.start + 1);
+                    continue;
+                }
+                for t in 0..log.clock {
+                    let c = match record.end {
+                        _ if t < record.start => ' ',
+                        _ if t == record.start => '[',
+                        Some(end) if t == end => ']',
+                        Some(end) if t > end => ' ',
+                        Some(_) | None => record.kind.fill(),
+                    };
+                    out.push(c);
+                }
+                if record.end.is_none() {
+                    out.push('>');
+                }
+                // Columns after the end are spaces; trim them
+                let trimmed = out.trim_end_matches(' ').len();
+                out.truncate(trimmed);
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+// ============================================================================
+// Tracked values and their borrows
+// ============================================================================
+
+/// A value whose borrows are checked at runtime and recorded by name
+pub struct Tracked<T> {
+    id: usize,
+    variable: String,
+    value: RefCell<T>,
+    log: Rc<RefCell<Log>>,
+}
+
+impl<T> Tracked<T> {
+    /// `let reference = &variable;`
+    pub fn borrow(&self, reference: &str) -> Result<Shared<'_, T>, BorrowError> {
+        let attempt = self.value.try_borrow();
+        let id =
+            self.log
+                .borrow_mut()
+                .start(self.id, reference, BorrowKind::Shared, attempt.is_ok());
+        match attempt {
+            Ok(value) => Ok(Shared {
+                value,
+                log: &self.log,
+                id,
+            }),
+            Err(_) => Err(self.last_conflict()),
+        }
+    }
+
+    /// `let reference = &mut variable;`
+    pub fn borrow_mut(&self, reference: &str) -> Result<Exclusive<'_, T>, BorrowError> {
+        let attempt = self.value.try_borrow_mut();
+        let id =
+            self.log
+                .borrow_mut()
+                .start(self.id, reference, BorrowKind::Exclusive, attempt.is_ok());
+        match attempt {
+            Ok(value) => Ok(Exclusive {
+                value,
+                log: &self.log,
+                id,
+            }),
+            Err(_) => Err(self.last_conflict()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.variable
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn last_conflict(&self) -> BorrowError {
+        self.log.borrow().conflicts.last().cloned().unwrap()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracked")
+            .field("variable", &self.variable)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// A recorded `&T`; the borrow ends when it is dropped
+pub struct Shared<'a, T> {
+    value: Ref<'a, T>,
+    log: &'a RefCell<Log>,
+    id: usize,
+}
+
+impl<T> Deref for Shared<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Drop for Shared<'_, T> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().end(self.id);
+    }
+}
+
+/// A recorded `&mut T`; the borrow ends when it is dropped
+pub struct Exclusive<'a, T> {
+    value: RefMut<'a, T>,
+    log: &'a RefCell<Log>,
+    id: usize,
+}
+
+impl<T> Deref for Exclusive<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Exclusive<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T> Drop for Exclusive<'_, T> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().end(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shadowed_variables_get_their_own_rows() {
+        let timeline = Timeline::new();
+        let s = timeline.track("s", String::from("hello"));
+        let r1 = s.borrow("r1").unwrap();
+        // `let s = ...;` again: a new variable that happens to share the name
+        let s = {
+            let len = r1.len();
+            drop(r1);
+            timeline.track("s", len)
+        };
+        let r2 = s.borrow_mut("r2").unwrap();
+        drop(r2);
+
+        let records = timeline.records();
+        assert_eq!(records[0].variable_id, 0);
+        assert_eq!(records[1].variable_id, 1);
+        assert_eq!(
+            timeline.render(),
+            concat!("time       0123\n", "s  r1 &    []\n", "s  r2 &mut   []\n",)
+        );
+    }
+
+    #[test]
+    fn a_live_borrow_of_one_variable_does_not_block_its_namesake() {
+        let timeline = Timeline::new();
+        let a = timeline.track("v", 1);
+        let b = timeline.track("v", 2);
+        let ra = a.borrow_mut("ra").unwrap();
+        let rb = b.borrow_mut("rb").unwrap();
+        assert_eq!((*ra, *rb), (1, 2));
+        drop(ra);
+        drop(rb);
+        assert!(timeline.conflicts().is_empty());
+    }
+
+    #[test]
+    fn conflicts_name_only_the_holders_of_the_same_variable() {
+        let timeline = Timeline::new();
+        let a = timeline.track("v", 1);
+        let b = timeline.track("v", 2);
+        let _ra = a.borrow("ra").unwrap();
+        let _rb = b.borrow("rb").unwrap();
+        let Err(err) = b.borrow_mut("rb_mut") else {
+            panic!("rb is still live");
+        };
+        assert_eq!(err.variable, "v");
+        assert_eq!(err.at, 2);
+        assert_eq!(
+            err.held_by,
+            [Holder {
+                reference: "rb".to_string(),
+                kind: BorrowKind::Shared,
+                since: 1,
+            }]
+        );
+        assert_eq!(
+            err.to_string(),
+            "t=2: cannot borrow `v` as mutable (`rb_mut`): already borrowed by `rb` (& since t=1)"
+        );
+    }
+
+    #[test]
+    fn render_marks_refused_and_live_borrows() {
+        let timeline = Timeline::new();
+        let s = timeline.track("s", ());
+        let r1 = s.borrow("r1").unwrap();
+        assert!(s.borrow_mut("r2").is_err());
+        let records = timeline.records();
+        assert!(records[1].refused);
+        assert_eq!(records[0].end, None);
+        assert_eq!(
+            timeline.render(),
+            concat!(
+                "time       01\n",
+                "s  r1 &    [->\n",
+                "   r2 &mut  X refused\n",
+            )
+        );
+        drop(r1);
+        assert_eq!(timeline.records()[0].end, Some(2));
+        assert_eq!(timeline.now(), 3);
+    }
+}
@@ -0,0 +1,367 @@
+// The scores example, shared between threads.
+//
+// Section 5 of main.rs shows that two `&mut` to the same data do not
+// compile. Across threads the rule is the same, and the three ways to share
+// a `Vec` of scores anyway are:
+//
+//   Mutex    - `Arc<Mutex<Vec<_>>>`: one thread at a time, readers included
+//   RwLock   - `Arc<RwLock<Vec<_>>>`: one writer or many readers
+//   Channel  - one owner thread holds the Vec; workers send it messages
+//
+// Workers apply `add_bonus` to every score and read the average. A bonus
+// updates all scores under one lock (or one message), so a reader must
+// never see some scores with a bonus and others without: `Outcome::torn_reads`
+// counts the times that happened, and should always be 0.
+//
+// With the whole Vec behind one lock that count can't be anything but 0:
+// the type system only lets a reader in between two complete bonuses. It is
+// there for the version that goes wrong, a `Vec<Mutex<i64>>` locked one
+// score at a time, where a reader can land in the middle of a bonus. The
+// tests build that version and check that it gets caught.
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ownership::lcg::Lcg;
+
+use crate::stats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Mutex,
+    RwLock,
+    Channel,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 3] = [Strategy::Mutex, Strategy::RwLock, Strategy::Channel];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Mutex => "Arc<Mutex>",
+            Strategy::RwLock => "Arc<RwLock>",
+            Strategy::Channel => "channel",
+        }
+    }
+
+    pub fn run(self, workload: &Workload) -> Outcome {
+        match self {
+            Strategy::Mutex => run_mutex(workload),
+            Strategy::RwLock => run_rwlock(workload),
+            Strategy::Channel => run_channel(workload),
+        }
+    }
+}
+
+/// What every worker thread does
+#[derive(Debug, Clone)]
+pub struct Workload {
+    pub initial: Vec<i64>,
+    pub threads: usize,
+    /// `add_bonus` calls per thread
+    pub bonuses_per_thread: usize,
+    /// Average reads per thread
+    pub reads_per_thread: usize,
+    pub bonus: i64,
+}
+
+impl Workload {
+    /// Scores once every bonus has been applied, whatever the interleaving
+    pub fn expected_scores(&self) -> Vec<i64> {
+        let total_bonus = self.bonus * (self.threads * self.bonuses_per_thread) as i64;
+        self.initial.iter().map(|s| s + total_bonus).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub scores: Vec<i64>,
+    pub elapsed: Duration,
+    pub reads: usize,
+    /// Reads that saw only part of a bonus applied
+    pub torn_reads: usize,
+}
+
+impl Outcome {
+    pub fn is_correct(&self, workload: &Workload) -> bool {
+        self.scores == workload.expected_scores() && self.torn_reads == 0
+    }
+}
+
+/// Totals over the runs of `stress`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StressResult {
+    pub runs: usize,
+    pub bonuses: usize,
+    /// Runs whose final scores were not the expected ones
+    pub wrong: usize,
+    pub torn_reads: usize,
+}
+
+/// Runs `strategy` on `runs` random workloads: up to 64 scores, 1-8
+/// threads and up to `max_steps` bonuses and reads per thread
+pub fn stress(strategy: Strategy, runs: usize, max_steps: usize, rng: &mut Lcg) -> StressResult {
+    let mut result = StressResult::default();
+    for _ in 0..runs {
+        let workload = Workload {
+            initial: (0..1 + rng.below(64)).map(|i| i as i64 * 7).collect(),
+            threads: 1 + rng.below(8),
+            bonuses_per_thread: rng.below(max_steps),
+            reads_per_thread: rng.below(max_steps),
+            bonus: rng.below(11) as i64 - 5,
+        };
+        let outcome = strategy.run(&workload);
+        result.runs += 1;
+        result.bonuses += workload.threads * workload.bonuses_per_thread;
+        result.torn_reads += outcome.torn_reads;
+        if outcome.scores != workload.expected_scores() {
+            result.wrong += 1;
+        }
+    }
+    result
+}
+
+// Same as the lesson's helper, on a slice
+pub fn add_bonus(scores: &mut [i64], bonus: i64) {
+    for score in scores.iter_mut() {
+        *score += bonus;
+    }
+}
+
+// Reads the average and checks that every score got the same number of
+// bonuses (true if consistent; no scores are always consistent)
+fn read_average(scores: &[i64], initial: &[i64]) -> (f64, bool) {
+    let average = stats::mean(scores).unwrap_or(0.0);
+    let (Some(score), Some(start)) = (scores.first(), initial.first()) else {
+        return (average, true);
+    };
+    let first = score - start;
+    let consistent = scores.iter().zip(initial).all(|(s, i)| s - i == first);
+    (average, consistent)
+}
+
+// Runs `reads` and `bonuses` interleaved, returning (reads, torn reads)
+fn work(
+    workload: &Workload,
+    mut bonus: impl FnMut(),
+    mut read: impl FnMut() -> (f64, bool),
+) -> (usize, usize) {
+    let mut torn = 0;
+    let steps = workload.bonuses_per_thread.max(workload.reads_per_thread);
+    for step in 0..steps {
+        if step < workload.bonuses_per_thread {
+            bonus();
+        }
+        if step < workload.reads_per_thread {
+            let (average, consistent) = read();
+            std::hint::black_box(average);
+            if !consistent {
+                torn += 1;
+            }
+        }
+    }
+    (workload.reads_per_thread, torn)
+}
+
+fn join_all(handles: Vec<thread::JoinHandle<(usize, usize)>>) -> (usize, usize) {
+    handles
+        .into_iter()
+        .map(|h| h.join().expect("worker panicked"))
+        .fold((0, 0), |(r, t), (reads, torn)| (r + reads, t + torn))
+}
+
+fn run_mutex(workload: &Workload) -> Outcome {
+    let start = Instant::now();
+    let scores = Arc::new(Mutex::new(workload.initial.clone()));
+    let initial = Arc::new(workload.initial.clone());
+
+    let handles = (0..workload.threads)
+        .map(|_| {
+            let scores = Arc::clone(&scores);
+            let initial = Arc::clone(&initial);
+            let workload = workload.clone();
+            thread::spawn(move || {
+                work(
+                    &workload,
+                    || add_bonus(&mut scores.lock().unwrap(), workload.bonus),
+                    || read_average(&scores.lock().unwrap(), &initial),
+                )
+            })
+        })
+        .collect();
+    let (reads, torn_reads) = join_all(handles);
+
+    // Every worker has finished, so this is the last Arc
+    let scores = Arc::try_unwrap(scores).unwrap().into_inner().unwrap();
+    Outcome {
+        scores,
+        elapsed: start.elapsed(),
+        reads,
+        torn_reads,
+    }
+}
+
+fn run_rwlock(workload: &Workload) -> Outcome {
+    let start = Instant::now();
+    let scores = Arc::new(RwLock::new(workload.initial.clone()));
+    let initial = Arc::new(workload.initial.clone());
+
+    let handles = (0..workload.threads)
+        .map(|_| {
+            let scores = Arc::clone(&scores);
+            let initial = Arc::clone(&initial);
+            let workload = workload.clone();
+            thread::spawn(move || {
+                work(
+                    &workload,
+                    || add_bonus(&mut scores.write().unwrap(), workload.bonus),
+                    || read_average(&scores.read().unwrap(), &initial),
+                )
+            })
+        })
+        .collect();
+    let (reads, torn_reads) = join_all(handles);
+
+    let scores = Arc::try_unwrap(scores).unwrap().into_inner().unwrap();
+    Outcome {
+        scores,
+        elapsed: start.elapsed(),
+        reads,
+        torn_reads,
+    }
+}
+
+enum Command {
+    Bonus(i64),
+    Average(mpsc::Sender<(f64, bool)>),
+}
+
+fn run_channel(workload: &Workload) -> Outcome {
+    let start = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+
+    // The Vec moves into the owner thread; nobody else can touch it
+    let mut scores = workload.initial.clone();
+    let initial = workload.initial.clone();
+    let owner = thread::spawn(move || {
+        for command in receiver {
+            match command {
+                Command::Bonus(bonus) => add_bonus(&mut scores, bonus),
+                Command::Average(reply) => {
+                    let _ = reply.send(read_average(&scores, &initial));
+                }
+            }
+        }
+        // All senders are gone: hand the Vec back
+        scores
+    });
+
+    let handles = (0..workload.threads)
+        .map(|_| {
+            let sender = sender.clone();
+            let workload = workload.clone();
+            thread::spawn(move || {
+                let (reply_to, replies) = mpsc::channel();
+                work(
+                    &workload,
+                    || sender.send(Command::Bonus(workload.bonus)).unwrap(),
+                    || {
+                        sender.send(Command::Average(reply_to.clone())).unwrap();
+                        replies.recv().unwrap()
+                    },
+                )
+            })
+        })
+        .collect();
+    drop(sender);
+    let (reads, torn_reads) = join_all(handles);
+
+    let scores = owner.join().expect("owner panicked");
+    Outcome {
+        scores,
+        elapsed: start.elapsed(),
+        reads,
+        torn_reads,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_scores_are_consistent() {
+        assert_eq!(read_average(&[], &[]), (0.0, true));
+        let workload = Workload {
+            initial: Vec::new(),
+            threads: 3,
+            bonuses_per_thread: 20,
+            reads_per_thread: 20,
+            bonus: 1,
+        };
+        for strategy in Strategy::ALL {
+            let outcome = strategy.run(&workload);
+            assert!(outcome.is_correct(&workload), "{}", strategy.name());
+            assert_eq!(outcome.reads, 60);
+        }
+    }
+
+    // A smaller version of the stress test in bin/concurrent_scores.rs
+    #[test]
+    fn stress_every_strategy() {
+        let mut rng = Lcg::new(0x5eed);
+        for strategy in Strategy::ALL {
+            let result = stress(strategy, 10, 200, &mut rng);
+            assert_eq!(result.runs, 10);
+            assert_eq!(
+                (result.wrong, result.torn_reads),
+                (0, 0),
+                "{}",
+                strategy.name()
+            );
+        }
+    }
+
+    #[test]
+    fn read_average_spots_a_partial_bonus() {
+        let initial = [85, 92, 78];
+        assert_eq!(read_average(&[87, 94, 80], &initial), (87.0, true));
+        assert!(!read_average(&[87, 94, 78], &initial).1);
+    }
+
+    // The broken design: one lock per score. A bonus locks them one by one,
+    // so a reader that takes the same locks can run between two of them.
+    // The writer pauses after the first score to make that happen every time.
+    #[test]
+    fn per_score_locking_is_caught() {
+        let initial = vec![85, 92, 78];
+        let scores: Arc<Vec<Mutex<i64>>> =
+            Arc::new(initial.iter().map(|&s| Mutex::new(s)).collect());
+        let (halfway, reader_done) = (mpsc::channel(), mpsc::channel::<()>());
+
+        let writer = {
+            let scores = Arc::clone(&scores);
+            thread::spawn(move || {
+                for (i, score) in scores.iter().enumerate() {
+                    *score.lock().unwrap() += 5;
+                    if i == 0 {
+                        halfway.0.send(()).unwrap();
+                        reader_done.1.recv().unwrap();
+                    }
+                }
+            })
+        };
+
+        halfway.1.recv().unwrap();
+        let snapshot: Vec<i64> = scores.iter().map(|s| *s.lock().unwrap()).collect();
+        reader_done.0.send(()).unwrap();
+        writer.join().unwrap();
+
+        assert_eq!(snapshot, [90, 92, 78]);
+        assert!(!read_average(&snapshot, &initial).1);
+        let last: Vec<i64> = scores.iter().map(|s| *s.lock().unwrap()).collect();
+        assert!(read_average(&last, &initial).1);
+    }
+}
@@ -0,0 +1,707 @@
+// A gradebook: students, curves and letter grades.
+//
+// `add_bonus(&mut Vec<i32>, bonus)` changes the scores in place and forgets
+// what they were. Here every student keeps their original score, and the
+// curves applied on top are recorded in order. Current scores are always
+// "originals with the history replayed", so any curve can be reverted by
+// dropping it from the history and replaying the rest.
+//
+// Students are imported from CSV (`name,score` with a header line). A
+// gradebook is saved as CSV too, one record per line:
+//
+//     student,Ada Lovelace,85
+//     curve,flat,5
+//     cutoff,A,90
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use datatypes::typed_rows::{Dialect, ParseError, TupleReader, TupleWriter};
+
+use crate::stats::{self, StatsError};
+
+#[derive(Debug)]
+pub enum GradebookError {
+    Io(io::Error),
+    Csv(ParseError),
+    Stats(StatsError),
+    /// A name appears twice in the imported scores
+    DuplicateStudent(String),
+    /// A score is NaN or infinite
+    InvalidScore {
+        name: String,
+        score: f64,
+    },
+    /// Curves look like "flat 5", "scale-mean 80" or "cap 100"
+    InvalidCurve(String),
+    /// Cutoffs look like "A=90,B=80,C=70,D=60,F=0"
+    InvalidCutoffs(String),
+    /// Adjustments are numbered from 1
+    NoSuchAdjustment {
+        number: usize,
+        applied: usize,
+    },
+    /// A saved gradebook has a record we do not understand
+    InvalidRecord {
+        number: usize,
+        text: String,
+    },
+}
+
+impl fmt::Display for GradebookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GradebookError::Io(err) => write!(f, "I/O error: {err}"),
+            GradebookError::Csv(err) => write!(f, "{err}"),
+            GradebookError::Stats(err) => write!(f, "cannot compute statistics: {err}"),
+            GradebookError::DuplicateStudent(name) => write!(f, "student {name:?} listed twice"),
+            GradebookError::InvalidScore { name, score } => {
+                write!(
+                    f,
+                    "student {name:?} has score {score}, expected a finite number"
+                )
+            }
+            GradebookError::InvalidCurve(curve) => write!(
+                f,
+                "invalid curve {curve:?} (expected flat N, scale-mean N or cap N)"
+            ),
+            GradebookError::InvalidCutoffs(cutoffs) => write!(
+                f,
+                "invalid cutoffs {cutoffs:?} (expected e.g. A=90,B=80,C=70,D=60,F=0)"
+            ),
+            GradebookError::NoSuchAdjustment { number, applied } => {
+                write!(f, "no adjustment #{number} ({applied} applied)")
+            }
+            GradebookError::InvalidRecord { number, text } => {
+                write!(f, "record {number}: cannot read {text:?}")
+            }
+        }
+    }
+}
+
+impl Error for GradebookError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GradebookError::Io(err) => Some(err),
+            GradebookError::Csv(err) => Some(err),
+            GradebookError::Stats(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GradebookError {
+    fn from(err: io::Error) -> GradebookError {
+        GradebookError::Io(err)
+    }
+}
+
+impl From<ParseError> for GradebookError {
+    fn from(err: ParseError) -> GradebookError {
+        GradebookError::Csv(err)
+    }
+}
+
+impl From<StatsError> for GradebookError {
+    fn from(err: StatsError) -> GradebookError {
+        GradebookError::Stats(err)
+    }
+}
+
+// ============================================================================
+// Curves
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// Adds the same number of points to everyone
+    Flat(f64),
+    /// Multiplies every score so the class mean becomes the target
+    ScaleToMean(f64),
+    /// Lowers anything above the cap to the cap; NaN stays NaN
+    Cap(f64),
+}
+
+impl Curve {
+    pub fn apply(&self, scores: &mut [f64]) -> Result<(), GradebookError> {
+        match *self {
+            Curve::Flat(bonus) => {
+                for score in scores.iter_mut() {
+                    *score += bonus;
+                }
+            }
+            Curve::ScaleToMean(target) => {
+                let mean = stats::mean(scores)?;
+                if mean == 0.0 {
+                    return Err(GradebookError::InvalidCurve(format!(
+                        "{self} (the mean is 0)"
+                    )));
+                }
+                for score in scores.iter_mut() {
+                    *score *= target / mean;
+                }
+            }
+            Curve::Cap(max) => {
+                // Not f64::min, which would turn a NaN into `max`
+                for score in scores.iter_mut().filter(|score| **score > max) {
+                    *score = max;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn parts(&self) -> (&'static str, f64) {
+        match *self {
+            Curve::Flat(n) => ("flat", n),
+            Curve::ScaleToMean(n) => ("scale-mean", n),
+            Curve::Cap(n) => ("cap", n),
+        }
+    }
+
+    fn from_parts(kind: &str, value: &str) -> Option<Curve> {
+        let value: f64 = value.trim().parse().ok().filter(|v: &f64| v.is_finite())?;
+        match kind.trim() {
+            "flat" => Some(Curve::Flat(value)),
+            "scale-mean" => Some(Curve::ScaleToMean(value)),
+            "cap" => Some(Curve::Cap(value)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, value) = self.parts();
+        write!(f, "{kind} {value}")
+    }
+}
+
+impl FromStr for Curve {
+    type Err = GradebookError;
+
+    /// Parses "flat 5", "scale-mean 80" or "cap 100"
+    fn from_str(s: &str) -> Result<Curve, GradebookError> {
+        s.trim()
+            .split_once(' ')
+            .and_then(|(kind, value)| Curve::from_parts(kind, value))
+            .ok_or_else(|| GradebookError::InvalidCurve(s.to_string()))
+    }
+}
+
+// ============================================================================
+// Letter grades
+// ============================================================================
+
+/// Letter grades by minimum score, highest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradeScale {
+    cutoffs: Vec<(String, f64)>,
+}
+
+impl GradeScale {
+    /// Builds a scale from (letter, minimum score) pairs in any order
+    pub fn new(cutoffs: Vec<(String, f64)>) -> Result<GradeScale, GradebookError> {
+        let invalid = || {
+            let text: Vec<String> = cutoffs.iter().map(|(l, m)| format!("{l}={m}")).collect();
+            GradebookError::InvalidCutoffs(text.join(","))
+        };
+        if cutoffs.is_empty() || cutoffs.iter().any(|(l, m)| l.is_empty() || !m.is_finite()) {
+            return Err(invalid());
+        }
+        let mut sorted = cutoffs.clone();
+        sorted.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let duplicate = |w: &[(String, f64)]| w[0].1 == w[1].1;
+        let mut letters: Vec<&String> = sorted.iter().map(|(l, _)| l).collect();
+        letters.sort();
+        letters.dedup();
+        if sorted.windows(2).any(duplicate) || letters.len() != sorted.len() {
+            return Err(invalid());
+        }
+        Ok(GradeScale { cutoffs: sorted })
+    }
+
+    /// The letter for a score; anything below every cutoff gets the lowest
+    pub fn letter(&self, score: f64) -> &str {
+        self.cutoffs
+            .iter()
+            .find(|(_, min)| score >= *min)
+            .or(self.cutoffs.last())
+            .map(|(letter, _)| letter.as_str())
+            .unwrap_or_default()
+    }
+
+    pub fn cutoffs(&self) -> &[(String, f64)] {
+        &self.cutoffs
+    }
+}
+
+impl Default for GradeScale {
+    /// A=90, B=80, C=70, D=60, F below that
+    fn default() -> GradeScale {
+        GradeScale {
+            cutoffs: [
+                ("A", 90.0),
+                ("B", 80.0),
+                ("C", 70.0),
+                ("D", 60.0),
+                ("F", 0.0),
+            ]
+            .into_iter()
+            .map(|(letter, min)| (letter.to_string(), min))
+            .collect(),
+        }
+    }
+}
+
+impl fmt::Display for GradeScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (letter, min)) in self.cutoffs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{letter}={min}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GradeScale {
+    type Err = GradebookError;
+
+    /// Parses "A=90,B=80,C=70,D=60,F=0"
+    fn from_str(s: &str) -> Result<GradeScale, GradebookError> {
+        let invalid = || GradebookError::InvalidCutoffs(s.to_string());
+        let cutoffs = s
+            .split(',')
+            .map(|pair| {
+                let (letter, min) = pair.split_once('=').ok_or_else(invalid)?;
+                let min = min.trim().parse().map_err(|_| invalid())?;
+                Ok((letter.trim().to_string(), min))
+            })
+            .collect::<Result<Vec<_>, GradebookError>>()?;
+        GradeScale::new(cutoffs).map_err(|_| invalid())
+    }
+}
+
+// ============================================================================
+// Gradebook
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Student {
+    pub name: String,
+    /// Score as imported, before any curve
+    pub original: f64,
+    /// Score after the curves in the history
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Gradebook {
+    students: Vec<Student>,
+    history: Vec<Curve>,
+    scale: GradeScale,
+}
+
+/// One line of the exported report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRow<'a> {
+    pub name: &'a str,
+    pub original: f64,
+    pub score: f64,
+    pub letter: &'a str,
+}
+
+impl Gradebook {
+    pub fn new(students: Vec<(String, f64)>) -> Result<Gradebook, GradebookError> {
+        let mut book = Gradebook::default();
+        for (name, score) in students {
+            book.add_student(name, score)?;
+        }
+        Ok(book)
+    }
+
+    /// Reads `name,score` records after a header line
+    pub fn import_csv(input: impl BufRead) -> Result<Gradebook, GradebookError> {
+        let rows = TupleReader::<_, (String, f64)>::new(input, Dialect::csv().with_header(true));
+        let students = rows.collect::<Result<Vec<_>, ParseError>>()?;
+        Gradebook::new(students)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Gradebook, GradebookError> {
+        let file = BufReader::new(File::open(path)?);
+        let mut book = Gradebook::default();
+        let mut cutoffs = Vec::new();
+        let records = TupleReader::<_, (String, String, String)>::new(file, Dialect::csv());
+        for (i, record) in records.enumerate() {
+            let (kind, a, b) = record?;
+            let text = format!("{kind},{a},{b}");
+            let invalid = || GradebookError::InvalidRecord {
+                number: i + 1,
+                text: text.clone(),
+            };
+            match kind.as_str() {
+                "student" => book.add_student(a, b.parse().map_err(|_| invalid())?)?,
+                "curve" => book.apply(Curve::from_parts(&a, &b).ok_or_else(invalid)?)?,
+                "cutoff" => cutoffs.push((a, b.parse().map_err(|_| invalid())?)),
+                _ => return Err(invalid()),
+            }
+        }
+        if !cutoffs.is_empty() {
+            book.scale = GradeScale::new(cutoffs)?;
+        }
+        Ok(book)
+    }
+
+    /// Writes to a temporary file first and renames it over `path` once it
+    /// is synced to disk, so a failed save or a crash never leaves a
+    /// half-written gradebook behind
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GradebookError> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        let result = self
+            .write_records(&tmp)
+            .and_then(|()| Ok(fs::rename(&tmp, path)?));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result
+    }
+
+    fn write_records(&self, path: &Path) -> Result<(), GradebookError> {
+        let mut writer = TupleWriter::new(BufWriter::new(File::create(path)?), Dialect::csv());
+        for student in &self.students {
+            writer.write(&(
+                "student".to_string(),
+                student.name.clone(),
+                student.original,
+            ))?;
+        }
+        for curve in &self.history {
+            let (kind, value) = curve.parts();
+            writer.write(&("curve".to_string(), kind.to_string(), value))?;
+        }
+        for (letter, min) in self.scale.cutoffs() {
+            writer.write(&("cutoff".to_string(), letter.clone(), *min))?;
+        }
+        writer
+            .into_inner()
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+        Ok(())
+    }
+
+    pub fn students(&self) -> &[Student] {
+        &self.students
+    }
+
+    /// Applied curves, oldest first; `revert` numbers them from 1
+    pub fn history(&self) -> &[Curve] {
+        &self.history
+    }
+
+    pub fn scale(&self) -> &GradeScale {
+        &self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: GradeScale) {
+        self.scale = scale;
+    }
+
+    pub fn scores(&self) -> Vec<f64> {
+        self.students.iter().map(|s| s.score).collect()
+    }
+
+    /// Applies a curve to every current score and records it
+    pub fn apply(&mut self, curve: Curve) -> Result<(), GradebookError> {
+        let mut scores = self.scores();
+        curve.apply(&mut scores)?;
+        for (student, score) in self.students.iter_mut().zip(scores) {
+            student.score = score;
+        }
+        self.history.push(curve);
+        Ok(())
+    }
+
+    /// Removes adjustment `number` (1-based) and replays the others on the
+    /// original scores. Returns the removed curve.
+    pub fn revert(&mut self, number: usize) -> Result<Curve, GradebookError> {
+        if number == 0 || number > self.history.len() {
+            return Err(GradebookError::NoSuchAdjustment {
+                number,
+                applied: self.history.len(),
+            });
+        }
+        let mut history = self.history.clone();
+        let removed = history.remove(number - 1);
+
+        let mut scores: Vec<f64> = self.students.iter().map(|s| s.original).collect();
+        for curve in &history {
+            curve.apply(&mut scores)?;
+        }
+        for (student, score) in self.students.iter_mut().zip(scores) {
+            student.score = score;
+        }
+        self.history = history;
+        Ok(removed)
+    }
+
+    pub fn report(&self) -> impl Iterator<Item = ReportRow<'_>> {
+        self.students.iter().map(|student| ReportRow {
+            name: &student.name,
+            original: student.original,
+            score: student.score,
+            letter: self.scale.letter(student.score),
+        })
+    }
+
+    /// Writes the report as CSV: name,original,score,letter
+    pub fn write_report(&self, output: impl Write) -> io::Result<()> {
+        let mut writer = TupleWriter::new(output, Dialect::csv());
+        writer.write_header(&["name", "original", "score", "letter"])?;
+        for row in self.report() {
+            writer.write(&(
+                row.name.to_string(),
+                round(row.original),
+                round(row.score),
+                row.letter.to_string(),
+            ))?;
+        }
+        writer.into_inner().flush()
+    }
+
+    fn add_student(&mut self, name: String, score: f64) -> Result<(), GradebookError> {
+        if !score.is_finite() {
+            return Err(GradebookError::InvalidScore { name, score });
+        }
+        if self.students.iter().any(|s| s.name == name) {
+            return Err(GradebookError::DuplicateStudent(name));
+        }
+        self.students.push(Student {
+            name,
+            original: score,
+            score,
+        });
+        Ok(())
+    }
+}
+
+// Two decimals are plenty for a report
+fn round(score: f64) -> f64 {
+    (score * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn book(scores: &[(&str, f64)]) -> Gradebook {
+        Gradebook::new(
+            scores
+                .iter()
+                .map(|&(name, score)| (name.to_string(), score))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    // A fresh directory per test, removed again at the end
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir = std::env::temp_dir().join(format!("gradebook-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn curves() {
+        let mut scores = [60.0, 80.0, 100.0];
+        Curve::Flat(5.0).apply(&mut scores).unwrap();
+        assert_eq!(scores, [65.0, 85.0, 105.0]);
+        Curve::Cap(100.0).apply(&mut scores).unwrap();
+        assert_eq!(scores, [65.0, 85.0, 100.0]);
+        Curve::ScaleToMean(50.0).apply(&mut scores).unwrap();
+        for (score, expected) in scores.iter().zip([39.0, 51.0, 60.0]) {
+            assert!((score - expected).abs() < 1e-9, "{score}");
+        }
+
+        assert!(matches!(
+            Curve::ScaleToMean(80.0).apply(&mut [0.0, 0.0]),
+            Err(GradebookError::InvalidCurve(_))
+        ));
+        assert!(matches!(
+            Curve::ScaleToMean(80.0).apply(&mut []),
+            Err(GradebookError::Stats(StatsError::Empty))
+        ));
+    }
+
+    #[test]
+    fn cap_keeps_nan() {
+        let mut scores = [f64::NAN, 120.0];
+        Curve::Cap(100.0).apply(&mut scores).unwrap();
+        assert!(scores[0].is_nan());
+        assert_eq!(scores[1], 100.0);
+    }
+
+    #[test]
+    fn curve_parsing() {
+        assert_eq!("flat 5".parse::<Curve>().unwrap(), Curve::Flat(5.0));
+        assert_eq!(
+            " scale-mean 80 ".parse::<Curve>().unwrap(),
+            Curve::ScaleToMean(80.0)
+        );
+        assert_eq!("cap -1.5".parse::<Curve>().unwrap(), Curve::Cap(-1.5));
+        for bad in ["flat", "flat x", "cap NaN", "cap inf", "bend 5", ""] {
+            assert!(bad.parse::<Curve>().is_err(), "{bad:?}");
+        }
+        assert_eq!(Curve::ScaleToMean(80.0).to_string(), "scale-mean 80");
+    }
+
+    #[test]
+    fn import_rejects_non_finite_scores() {
+        for score in ["NaN", "inf", "-inf"] {
+            let csv = format!("name,score\nAda,90\nBob,{score}\n");
+            match Gradebook::import_csv(csv.as_bytes()) {
+                Err(GradebookError::InvalidScore { name, .. }) => assert_eq!(name, "Bob"),
+                other => panic!("{score}: {other:?}"),
+            }
+        }
+        let book =
+            Gradebook::import_csv("name,score\nAda,90\n\"Lovelace, A\",85.5\n".as_bytes()).unwrap();
+        assert_eq!(book.scores(), [90.0, 85.5]);
+        assert!(matches!(
+            Gradebook::import_csv("name,score\nAda,90\nAda,80\n".as_bytes()),
+            Err(GradebookError::DuplicateStudent(name)) if name == "Ada"
+        ));
+        assert!(matches!(
+            Gradebook::import_csv("name,score\nAda,ninety\n".as_bytes()),
+            Err(GradebookError::Csv(_))
+        ));
+    }
+
+    #[test]
+    fn revert_replays_the_other_curves() {
+        let mut book = book(&[("Ada", 70.0), ("Bob", 96.0)]);
+        book.apply(Curve::Flat(10.0)).unwrap();
+        book.apply(Curve::Cap(100.0)).unwrap();
+        assert_eq!(book.scores(), [80.0, 100.0]);
+
+        assert_eq!(book.revert(1).unwrap(), Curve::Flat(10.0));
+        assert_eq!(book.scores(), [70.0, 96.0]);
+        assert_eq!(book.history(), [Curve::Cap(100.0)]);
+        assert!(matches!(
+            book.revert(2),
+            Err(GradebookError::NoSuchAdjustment {
+                number: 2,
+                applied: 1
+            })
+        ));
+        assert!(book.revert(0).is_err());
+    }
+
+    #[test]
+    fn grade_scales() {
+        let scale: GradeScale = "F=0, A=90,B=80".parse().unwrap();
+        assert_eq!(scale.to_string(), "A=90,B=80,F=0");
+        assert_eq!(scale.letter(95.0), "A");
+        assert_eq!(scale.letter(80.0), "B");
+        assert_eq!(scale.letter(-5.0), "F");
+        for bad in ["", "A", "A=x", "A=90,B=90", "A=90,A=80", "=90", "A=inf"] {
+            assert!(bad.parse::<GradeScale>().is_err(), "{bad:?}");
+        }
+        assert_eq!(GradeScale::default().letter(89.99), "B");
+    }
+
+    #[test]
+    fn report() {
+        let mut book = book(&[("Ada", 88.0), ("Bob", 59.5)]);
+        book.apply(Curve::ScaleToMean(80.0)).unwrap();
+        let mut out = Vec::new();
+        book.write_report(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "name,original,score,letter\nAda,88.0,95.46,A\nBob,59.5,64.54,D\n"
+        );
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = TempDir::new("round-trip");
+        let path = dir.0.join("book.csv");
+        let mut book = book(&[("Ada Lovelace", 85.0), ("Brown, Bob", 91.25)]);
+        book.apply(Curve::Flat(5.0)).unwrap();
+        book.apply(Curve::Cap(95.0)).unwrap();
+        book.set_scale("A=85,B=70,F=0".parse().unwrap());
+        book.save(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let loaded = Gradebook::load(&path).unwrap();
+        assert_eq!(loaded.students(), book.students());
+        assert_eq!(loaded.history(), book.history());
+        assert_eq!(loaded.scale(), book.scale());
+
+        // Saving again replaces the file
+        let mut smaller = loaded;
+        smaller.revert(2).unwrap();
+        smaller.save(&path).unwrap();
+        assert_eq!(
+            Gradebook::load(&path).unwrap().history(),
+            [Curve::Flat(5.0)]
+        );
+    }
+
+    #[test]
+    fn failed_save_keeps_the_old_file() {
+        let dir = TempDir::new("failed-save");
+        let path = dir.0.join("book.csv");
+        book(&[("Ada", 1.0)]).save(&path).unwrap();
+        let before = fs::read_to_string(&path).unwrap();
+
+        // Something in the way of the temporary file makes the save fail
+        fs::create_dir(path.with_extension("tmp")).unwrap();
+        assert!(matches!(
+            book(&[("Ada", 2.0)]).save(&path),
+            Err(GradebookError::Io(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn load_rejects_bad_records() {
+        let dir = TempDir::new("bad-records");
+        let path = dir.0.join("book.csv");
+        for (text, number) in [
+            ("student,Ada,85\ngrade,Ada,A\n", 2),
+            ("student,Ada,eighty\n", 1),
+            ("student,Ada,85\ncurve,bend,5\n", 2),
+        ] {
+            fs::write(&path, text).unwrap();
+            match Gradebook::load(&path) {
+                Err(GradebookError::InvalidRecord { number: n, .. }) => assert_eq!(n, number),
+                other => panic!("{text:?}: {other:?}"),
+            }
+        }
+        fs::write(&path, "student,Ada,NaN\n").unwrap();
+        assert!(matches!(
+            Gradebook::load(&path),
+            Err(GradebookError::InvalidScore { .. })
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod borrow_timeline;
+pub mod concurrent_scores;
+pub mod gradebook;
+pub mod stats;
+pub mod text_metrics;
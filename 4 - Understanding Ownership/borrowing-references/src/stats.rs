@@ -0,0 +1,610 @@
+// Descriptive statistics over borrowed slices.
+//
+// `calculate_sum(&Vec<i32>) -> i32` overflows once the scores add up past
+// i32::MAX, and `calculate_average` divides by zero (giving NaN) for an
+// empty vector. Everything here borrows a `&[T]` for any primitive number
+// type, sums integers in 128 bits so the total cannot overflow, and returns
+// a `StatsError` instead of a meaningless number.
+//
+// "Any primitive number type" stops short of i128 and u128: a total that
+// cannot overflow would need a type wider than they are, and Rust has none.
+// Float means are computed incrementally, so `[f64::MAX, f64::MAX]` has a
+// mean of f64::MAX rather than an infinite sum divided by two.
+//
+// `Accumulator` computes the same count/sum/mean/variance/min/max one value
+// at a time, for data that is streamed rather than held in memory. Median,
+// mode and percentiles need to see all values, so they only exist for
+// slices.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Sub};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StatsError {
+    /// No values at all
+    Empty,
+    /// The statistic needs at least `needed` values
+    TooFewValues { needed: usize, got: usize },
+    /// A float input contained NaN at `index`
+    NotANumber { index: usize },
+    /// Percentiles go from 0 to 100
+    PercentileOutOfRange(f64),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Empty => write!(f, "no values"),
+            StatsError::TooFewValues { needed, got } => {
+                write!(f, "need at least {needed} values, got {got}")
+            }
+            StatsError::NotANumber { index } => write!(f, "value at index {index} is NaN"),
+            StatsError::PercentileOutOfRange(p) => {
+                write!(f, "percentile {p} is outside 0..=100")
+            }
+        }
+    }
+}
+
+impl Error for StatsError {}
+
+// ============================================================================
+// Sample: the number types we accept
+// ============================================================================
+
+/// A primitive number that can be summarized
+pub trait Sample: Copy + PartialOrd + fmt::Debug {
+    /// Type wide enough to hold the sum of any realistic slice
+    /// (i128 for signed, u128 for unsigned integers, f64 for floats)
+    type Sum: Copy
+        + Default
+        + PartialOrd
+        + Add<Output = Self::Sum>
+        + Sub<Output = Self::Sum>
+        + fmt::Debug
+        + fmt::Display;
+
+    /// Whether `Sum` arithmetic is exact, so a running total can subtract
+    /// values again; false for floats, which round
+    const EXACT_SUM: bool = true;
+
+    fn widen(self) -> Self::Sum;
+    fn sum_to_f64(sum: Self::Sum) -> f64;
+    fn to_f64(self) -> f64;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    fn is_nan(self) -> bool {
+        false
+    }
+}
+
+macro_rules! int_sample {
+    ($sum:ty => $($t:ty),*) => {$(
+        impl Sample for $t {
+            type Sum = $sum;
+
+            fn widen(self) -> $sum {
+                self as $sum
+            }
+
+            fn sum_to_f64(sum: $sum) -> f64 {
+                sum as f64
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn total_cmp(&self, other: &$t) -> Ordering {
+                self.cmp(other)
+            }
+        }
+    )*};
+}
+
+// i128/u128 are left out: their sums could overflow the 128-bit total (see
+// the note at the top)
+int_sample!(i128 => i8, i16, i32, i64, isize);
+int_sample!(u128 => u8, u16, u32, u64, usize);
+
+macro_rules! float_sample {
+    ($($t:ty),*) => {$(
+        impl Sample for $t {
+            type Sum = f64;
+
+            const EXACT_SUM: bool = false;
+
+            fn widen(self) -> f64 {
+                self as f64
+            }
+
+            fn sum_to_f64(sum: f64) -> f64 {
+                sum
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn total_cmp(&self, other: &$t) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+        }
+    )*};
+}
+
+float_sample!(f32, f64);
+
+// Rejects empty input and NaNs, which have no place in an ordering
+fn check<T: Sample>(data: &[T]) -> Result<(), StatsError> {
+    if data.is_empty() {
+        return Err(StatsError::Empty);
+    }
+    match data.iter().position(|x| x.is_nan()) {
+        Some(index) => Err(StatsError::NotANumber { index }),
+        None => Ok(()),
+    }
+}
+
+fn sorted<T: Sample>(data: &[T]) -> Vec<T> {
+    let mut copy = data.to_vec();
+    copy.sort_unstable_by(T::total_cmp);
+    copy
+}
+
+// ============================================================================
+// Slice statistics
+// ============================================================================
+
+/// Sum in a wider type. Zero for an empty slice.
+pub fn sum<T: Sample>(data: &[T]) -> T::Sum {
+    data.iter()
+        .fold(T::Sum::default(), |total, &x| total + x.widen())
+}
+
+/// Exact for integers. Floats use `Accumulator`'s running mean, since their
+/// sum can overflow when the mean doesn't.
+pub fn mean<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    check(data)?;
+    if T::EXACT_SUM {
+        Ok(T::sum_to_f64(sum(data)) / data.len() as f64)
+    } else {
+        data.iter().copied().collect::<Accumulator<T>>().mean()
+    }
+}
+
+/// Middle value; the average of the two middle values for even lengths
+pub fn median<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    percentile(data, 50.0)
+}
+
+/// Most frequent values in ascending order (several when tied), and how
+/// often each occurs
+pub fn mode<T: Sample>(data: &[T]) -> Result<(Vec<T>, usize), StatsError> {
+    check(data)?;
+    let values = sorted(data);
+    let mut modes = Vec::new();
+    let mut best = 0;
+    for run in values.chunk_by(|a, b| a.total_cmp(b) == Ordering::Equal) {
+        match run.len().cmp(&best) {
+            Ordering::Greater => {
+                best = run.len();
+                modes.clear();
+                modes.push(run[0]);
+            }
+            Ordering::Equal => modes.push(run[0]),
+            Ordering::Less => {}
+        }
+    }
+    Ok((modes, best))
+}
+
+/// Population variance (divides by n)
+pub fn variance<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    check(data)?;
+    Ok(data.iter().copied().collect::<Accumulator<T>>().m2 / data.len() as f64)
+}
+
+/// Sample variance (divides by n - 1); needs at least two values
+pub fn sample_variance<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    check(data)?;
+    if data.len() < 2 {
+        return Err(StatsError::TooFewValues {
+            needed: 2,
+            got: data.len(),
+        });
+    }
+    Ok(data.iter().copied().collect::<Accumulator<T>>().m2 / (data.len() - 1) as f64)
+}
+
+/// Population standard deviation
+pub fn std_dev<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    variance(data).map(f64::sqrt)
+}
+
+/// Sample standard deviation
+pub fn sample_std_dev<T: Sample>(data: &[T]) -> Result<f64, StatsError> {
+    sample_variance(data).map(f64::sqrt)
+}
+
+/// Value below which `p` percent of the data falls, interpolating linearly
+/// between neighbouring values (0 is the minimum, 100 the maximum)
+pub fn percentile<T: Sample>(data: &[T], p: f64) -> Result<f64, StatsError> {
+    Ok(percentiles(data, &[p])?[0])
+}
+
+/// Several percentiles at once, sorting the data only once
+pub fn percentiles<T: Sample>(data: &[T], ps: &[f64]) -> Result<Vec<f64>, StatsError> {
+    check(data)?;
+    if let Some(&p) = ps.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+        return Err(StatsError::PercentileOutOfRange(p));
+    }
+    let values = sorted(data);
+    let last = values.len() - 1;
+    Ok(ps
+        .iter()
+        .map(|p| {
+            let rank = p / 100.0 * last as f64;
+            let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+            let (low, high) = (values[below].to_f64(), values[above].to_f64());
+            low + (high - low) * (rank - below as f64)
+        })
+        .collect())
+}
+
+// ============================================================================
+// Accumulator: streaming statistics
+// ============================================================================
+
+/// Running count, sum, mean, variance, min and max in constant memory.
+/// Uses Welford's update, which stays accurate for long streams.
+#[derive(Debug, Clone)]
+pub struct Accumulator<T: Sample> {
+    count: usize,
+    sum: T::Sum,
+    mean: f64,
+    m2: f64,
+    min: Option<T>,
+    max: Option<T>,
+    first_nan: Option<usize>,
+}
+
+impl<T: Sample> Accumulator<T> {
+    pub fn new() -> Accumulator<T> {
+        Accumulator {
+            count: 0,
+            sum: T::Sum::default(),
+            mean: 0.0,
+            m2: 0.0,
+            min: None,
+            max: None,
+            first_nan: None,
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if value.is_nan() {
+            self.first_nan.get_or_insert(self.count);
+        }
+        self.count += 1;
+        self.sum = self.sum + value.widen();
+
+        let x = value.to_f64();
+        let n = self.count as f64;
+        let delta = x - self.mean;
+        // x - mean overflows when both are huge with opposite signs
+        self.mean += if delta.is_finite() {
+            delta / n
+        } else {
+            x / n - self.mean / n
+        };
+        self.m2 += delta * (x - self.mean);
+
+        if self
+            .min
+            .is_none_or(|min| value.total_cmp(&min) == Ordering::Less)
+        {
+            self.min = Some(value);
+        }
+        if self
+            .max
+            .is_none_or(|max| value.total_cmp(&max) == Ordering::Greater)
+        {
+            self.max = Some(value);
+        }
+    }
+
+    /// Combines two accumulators, e.g. from chunks processed separately
+    pub fn merge(&mut self, other: &Accumulator<T>) {
+        if other.count == 0 {
+            return;
+        }
+        if let Some(index) = other.first_nan {
+            self.first_nan.get_or_insert(self.count + index);
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let share = other.count as f64 / count as f64;
+        self.mean = if delta.is_finite() {
+            self.mean + delta * share
+        } else {
+            self.mean * (1.0 - share) + other.mean * share
+        };
+        self.m2 += other.m2 + delta * delta * self.count as f64 * other.count as f64 / count as f64;
+        self.count = count;
+        self.sum = self.sum + other.sum;
+        for value in [other.min, other.max].into_iter().flatten() {
+            if self
+                .min
+                .is_none_or(|min| value.total_cmp(&min) == Ordering::Less)
+            {
+                self.min = Some(value);
+            }
+            if self
+                .max
+                .is_none_or(|max| value.total_cmp(&max) == Ordering::Greater)
+            {
+                self.max = Some(value);
+            }
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn sum(&self) -> T::Sum {
+        self.sum
+    }
+
+    pub fn mean(&self) -> Result<f64, StatsError> {
+        self.check(1)?;
+        Ok(self.mean)
+    }
+
+    pub fn variance(&self) -> Result<f64, StatsError> {
+        self.check(1)?;
+        Ok(self.m2 / self.count as f64)
+    }
+
+    pub fn sample_variance(&self) -> Result<f64, StatsError> {
+        self.check(2)?;
+        Ok(self.m2 / (self.count - 1) as f64)
+    }
+
+    pub fn std_dev(&self) -> Result<f64, StatsError> {
+        self.variance().map(f64::sqrt)
+    }
+
+    pub fn sample_std_dev(&self) -> Result<f64, StatsError> {
+        self.sample_variance().map(f64::sqrt)
+    }
+
+    pub fn min(&self) -> Result<T, StatsError> {
+        self.check(1)?;
+        Ok(self.min.unwrap())
+    }
+
+    pub fn max(&self) -> Result<T, StatsError> {
+        self.check(1)?;
+        Ok(self.max.unwrap())
+    }
+
+    fn check(&self, needed: usize) -> Result<(), StatsError> {
+        if let Some(index) = self.first_nan {
+            return Err(StatsError::NotANumber { index });
+        }
+        match self.count {
+            0 => Err(StatsError::Empty),
+            got if got < needed => Err(StatsError::TooFewValues { needed, got }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: Sample> Default for Accumulator<T> {
+    fn default() -> Accumulator<T> {
+        Accumulator::new()
+    }
+}
+
+impl<T: Sample> Extend<T> for Accumulator<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Sample> FromIterator<T> for Accumulator<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Accumulator<T> {
+        let mut acc = Accumulator::new();
+        acc.extend(values);
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(got: f64, expected: f64) {
+        assert!(
+            (got - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{got} != {expected}"
+        );
+    }
+
+    #[test]
+    fn integer_sums_do_not_overflow() {
+        assert_eq!(sum(&[i32::MAX, i32::MAX, 2]), 2 * i32::MAX as i128 + 2);
+        assert_eq!(sum(&[i64::MIN, i64::MIN]), 2 * i64::MIN as i128);
+        assert_eq!(sum(&[u64::MAX; 3]), 3 * u64::MAX as u128);
+        assert_eq!(sum(&[255u8; 4]), 1020);
+        assert_eq!(sum::<i32>(&[]), 0);
+        assert_close(mean(&[i64::MAX, i64::MAX]).unwrap(), i64::MAX as f64);
+    }
+
+    #[test]
+    fn float_means_do_not_overflow() {
+        assert_eq!(mean(&[f64::MAX, f64::MAX]), Ok(f64::MAX));
+        assert_eq!(mean(&[f64::MAX, -f64::MAX]), Ok(0.0));
+        assert_eq!(
+            mean(&[f64::MAX, f64::MAX, -f64::MAX]).map(f64::is_finite),
+            Ok(true)
+        );
+        assert_eq!(mean(&[f32::MAX, f32::MAX]), Ok(f32::MAX as f64));
+
+        let mut left: Accumulator<f64> = [f64::MAX, f64::MAX].into_iter().collect();
+        let right: Accumulator<f64> = [-f64::MAX, -f64::MAX].into_iter().collect();
+        assert_eq!(left.mean(), Ok(f64::MAX));
+        left.merge(&right);
+        assert_eq!(left.mean(), Ok(0.0));
+    }
+
+    #[test]
+    fn errors() {
+        let empty: [f64; 0] = [];
+        assert_eq!(mean(&empty), Err(StatsError::Empty));
+        assert_eq!(median(&empty), Err(StatsError::Empty));
+        assert_eq!(mode(&empty), Err(StatsError::Empty));
+        assert_eq!(
+            sample_variance(&[1.0]),
+            Err(StatsError::TooFewValues { needed: 2, got: 1 })
+        );
+        assert_eq!(
+            mean(&[1.0, f64::NAN, f64::NAN]),
+            Err(StatsError::NotANumber { index: 1 })
+        );
+        assert_eq!(
+            percentile(&[1, 2], 100.5),
+            Err(StatsError::PercentileOutOfRange(100.5))
+        );
+        assert!(percentiles(&[1, 2], &[50.0, -1.0]).is_err());
+        assert_eq!(
+            StatsError::TooFewValues { needed: 2, got: 1 }.to_string(),
+            "need at least 2 values, got 1"
+        );
+    }
+
+    #[test]
+    fn median_and_percentiles_interpolate() {
+        assert_eq!(median(&[3, 1, 2]), Ok(2.0));
+        assert_eq!(median(&[4, 1, 3, 2]), Ok(2.5));
+        assert_eq!(median(&[7u8]), Ok(7.0));
+        let scores = [85, 92, 78, 95, 88, 76, 89, 91, 84, 90];
+        assert_eq!(
+            percentiles(&scores, &[0.0, 25.0, 50.0, 100.0]),
+            Ok(vec![76.0, 84.25, 88.5, 95.0])
+        );
+        // Negative zero and negative values sort below zero
+        assert_eq!(percentile(&[-1.5, 0.0, -0.0, 2.0], 0.0), Ok(-1.5));
+    }
+
+    #[test]
+    fn mode_reports_every_tie() {
+        assert_eq!(mode(&[1, 2, 2, 3, 3, 1, 4]), Ok((vec![1, 2, 3], 2)));
+        assert_eq!(mode(&[5]), Ok((vec![5], 1)));
+        assert_eq!(mode(&[0.5, 0.25, 0.5]), Ok((vec![0.5], 2)));
+    }
+
+    #[test]
+    fn variance_and_std_dev() {
+        let data = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(variance(&data), Ok(4.0));
+        assert_eq!(std_dev(&data), Ok(2.0));
+        assert_close(sample_variance(&data).unwrap(), 32.0 / 7.0);
+        assert_close(sample_std_dev(&data).unwrap(), (32.0f64 / 7.0).sqrt());
+        // A large offset doesn't cost precision
+        let shifted: Vec<f64> = data.iter().map(|&x| x as f64 + 1e9).collect();
+        assert!((variance(&shifted).unwrap() - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn accumulator_matches_the_slice_functions() {
+        let data: Vec<i64> = (0..1_000).map(|i| (i * 7919) % 1000 - 500).collect();
+        let acc: Accumulator<i64> = data.iter().copied().collect();
+        assert_eq!(acc.count(), data.len());
+        assert_eq!(acc.sum(), sum(&data));
+        assert_close(acc.mean().unwrap(), mean(&data).unwrap());
+        assert_close(acc.variance().unwrap(), variance(&data).unwrap());
+        assert_close(
+            acc.sample_std_dev().unwrap(),
+            sample_std_dev(&data).unwrap(),
+        );
+        assert_eq!(acc.min(), Ok(-500));
+        assert_eq!(acc.max(), Ok(499));
+    }
+
+    #[test]
+    fn merge_matches_a_single_pass() {
+        let data: Vec<f64> = (0..100).map(|i| (i as f64 * 0.37).sin() * 50.0).collect();
+        let whole: Accumulator<f64> = data.iter().copied().collect();
+        for split in [0, 1, 37, 99, 100] {
+            let mut left: Accumulator<f64> = data[..split].iter().copied().collect();
+            let right: Accumulator<f64> = data[split..].iter().copied().collect();
+            left.merge(&right);
+            assert_eq!(left.count(), whole.count());
+            assert_close(left.mean().unwrap(), whole.mean().unwrap());
+            assert_close(left.variance().unwrap(), whole.variance().unwrap());
+            assert_eq!(left.min(), whole.min());
+            assert_eq!(left.max(), whole.max());
+        }
+    }
+
+    #[test]
+    fn accumulator_errors() {
+        let mut acc = Accumulator::<f32>::new();
+        assert_eq!(acc.mean(), Err(StatsError::Empty));
+        acc.push(1.0);
+        assert_eq!(
+            acc.sample_variance(),
+            Err(StatsError::TooFewValues { needed: 2, got: 1 })
+        );
+
+        let mut other = Accumulator::new();
+        other.extend([2.0, f32::NAN]);
+        acc.merge(&other);
+        // Index 2 overall: one value in `acc`, then the NaN is second in `other`
+        assert_eq!(acc.max(), Err(StatsError::NotANumber { index: 2 }));
+    }
+
+    // What windowed.rs relies on: integer sums can run a total up and back
+    // down again exactly, float sums can't
+    #[test]
+    fn exact_sums_subtract_back() {
+        fn round_trip<T: Sample>(values: &[T]) -> T::Sum {
+            let total = values
+                .iter()
+                .fold(T::Sum::default(), |sum, x| sum + x.widen());
+            values[1..].iter().fold(total, |sum, x| sum - x.widen())
+        }
+        assert_eq!([i64::EXACT_SUM, u8::EXACT_SUM, usize::EXACT_SUM], [true; 3]);
+        assert_eq!([f32::EXACT_SUM, f64::EXACT_SUM], [false; 2]);
+
+        assert_eq!(
+            round_trip(&[i64::MAX, i64::MAX, i64::MIN]),
+            i64::MAX as i128
+        );
+        assert_eq!(round_trip(&[7u64, u64::MAX, u64::MAX]), 7);
+        assert_eq!(round_trip(&[1.0, 1e20, -1e20]), 0.0);
+    }
+
+    #[test]
+    fn sums_compare_in_the_wide_type() {
+        let big = sum(&[i64::MAX, 1]);
+        assert!(big > i64::MAX.widen());
+        assert!(sum(&[-1i8, -1]) < 0);
+        assert!(sum::<u8>(&[]) == 0);
+        assert_eq!(sum(&[255u8, 255]), 510);
+        // f32 values add up as f64
+        assert_eq!(sum(&[f32::MAX, f32::MAX]), 2.0 * f32::MAX as f64);
+        assert!(sum(&[1.0, f64::NAN]).partial_cmp(&0.0).is_none());
+    }
+}
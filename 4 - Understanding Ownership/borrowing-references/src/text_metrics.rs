@@ -0,0 +1,777 @@
+// Text lengths beyond bytes: chars, grapheme clusters and display columns.
+//
+// `calculate_length(&String)` returns `s.len()`, the number of UTF-8 bytes.
+// For "नमस्ते" that is 18, although it is 6 chars, 3 user-perceived
+// characters (grapheme clusters: न, म, स्ते) and 4 terminal columns. This
+// module measures all four, and slices or truncates text by clusters or
+// columns without ever cutting a cluster in half.
+//
+// Segmentation follows the extended grapheme cluster rules of UAX #29
+// (including emoji ZWJ sequences, flags and Indic conjuncts), but the
+// character tables below are a hand-picked approximation of the Unicode
+// data, covering Latin, Greek, Cyrillic, Hebrew, Arabic, the major Indic
+// scripts, Thai, Hangul, CJK and emoji. Rare scripts may segment per char.
+// The tests run Unicode's GraphemeBreakTest.txt (data/, version 15.1), which
+// has one sample character per property value, so it checks the rules
+// rather than the tables.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+// ============================================================================
+// Character tables
+// ============================================================================
+
+// Sorted, non-overlapping inclusive ranges
+type Table = &'static [(u32, u32)];
+
+fn in_table(table: Table, c: char) -> bool {
+    let c = c as u32;
+    table
+        .binary_search_by(|&(lo, hi)| {
+            if hi < c {
+                std::cmp::Ordering::Less
+            } else if lo > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+// Combining marks, variation selectors, emoji modifiers, tags
+const EXTEND: Table = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x05BF, 0x05BF),
+    (0x05C1, 0x05C2),
+    (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x0670, 0x0670),
+    (0x06D6, 0x06DC),
+    (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8),
+    (0x06EA, 0x06ED),
+    (0x0900, 0x0902),
+    (0x093A, 0x093A),
+    (0x093C, 0x093C),
+    (0x0941, 0x0948),
+    (0x094D, 0x094D),
+    (0x0951, 0x0957),
+    (0x0962, 0x0963),
+    (0x0981, 0x0981),
+    (0x09BC, 0x09BC),
+    (0x09BE, 0x09BE),
+    (0x09C1, 0x09C4),
+    (0x09CD, 0x09CD),
+    (0x09D7, 0x09D7),
+    (0x09E2, 0x09E3),
+    (0x0A01, 0x0A02),
+    (0x0A3C, 0x0A3C),
+    (0x0A41, 0x0A51),
+    (0x0A70, 0x0A71),
+    (0x0A75, 0x0A75),
+    (0x0A81, 0x0A82),
+    (0x0ABC, 0x0ABC),
+    (0x0AC1, 0x0AC8),
+    (0x0ACD, 0x0ACD),
+    (0x0AE2, 0x0AE3),
+    (0x0B01, 0x0B01),
+    (0x0B3C, 0x0B3C),
+    (0x0B3E, 0x0B3F),
+    (0x0B41, 0x0B44),
+    (0x0B4D, 0x0B4D),
+    (0x0B55, 0x0B57),
+    (0x0B62, 0x0B63),
+    (0x0BBE, 0x0BBE),
+    (0x0BC0, 0x0BC0),
+    (0x0BCD, 0x0BCD),
+    (0x0BD7, 0x0BD7),
+    (0x0C00, 0x0C00),
+    (0x0C3E, 0x0C40),
+    (0x0C46, 0x0C56),
+    (0x0C62, 0x0C63),
+    (0x0CBC, 0x0CBC),
+    (0x0CBF, 0x0CBF),
+    (0x0CC6, 0x0CC6),
+    (0x0CCC, 0x0CCD),
+    (0x0CE2, 0x0CE3),
+    (0x0D00, 0x0D01),
+    (0x0D3E, 0x0D3E),
+    (0x0D41, 0x0D44),
+    (0x0D4D, 0x0D4D),
+    (0x0D57, 0x0D57),
+    (0x0E31, 0x0E31),
+    (0x0E34, 0x0E3A),
+    (0x0E47, 0x0E4E),
+    (0x0EB1, 0x0EB1),
+    (0x0EB4, 0x0EBC),
+    (0x0EC8, 0x0ECE),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200C, 0x200C),
+    (0x20D0, 0x20F0),
+    (0x302A, 0x302F),
+    (0x3099, 0x309A),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0x1F3FB, 0x1F3FF),
+    (0xE0020, 0xE007F),
+    (0xE0100, 0xE01EF),
+];
+
+// Vowel signs that take up space but still belong to the consonant
+const SPACING_MARK: Table = &[
+    (0x0903, 0x0903),
+    (0x093B, 0x093B),
+    (0x093E, 0x0940),
+    (0x0949, 0x094C),
+    (0x094E, 0x094F),
+    (0x0982, 0x0983),
+    (0x09BF, 0x09C0),
+    (0x09C7, 0x09CC),
+    (0x0A03, 0x0A03),
+    (0x0A3E, 0x0A40),
+    (0x0A83, 0x0A83),
+    (0x0ABE, 0x0AC0),
+    (0x0AC9, 0x0AC9),
+    (0x0ACB, 0x0ACC),
+    (0x0B02, 0x0B03),
+    (0x0B40, 0x0B40),
+    (0x0B47, 0x0B4C),
+    (0x0BBF, 0x0BBF),
+    (0x0BC1, 0x0BCC),
+    (0x0C01, 0x0C03),
+    (0x0C41, 0x0C44),
+    (0x0C82, 0x0C83),
+    (0x0CBE, 0x0CBE),
+    (0x0CC0, 0x0CC4),
+    (0x0D02, 0x0D03),
+    (0x0D3F, 0x0D40),
+    (0x0D46, 0x0D4C),
+    (0x0E33, 0x0E33),
+    (0x0EB3, 0x0EB3),
+];
+
+// Signs written before the letter they attach to (GB9b), complete as of
+// Unicode 15.1
+const PREPEND: Table = &[
+    (0x0600, 0x0605),
+    (0x06DD, 0x06DD),
+    (0x070F, 0x070F),
+    (0x0890, 0x0891),
+    (0x08E2, 0x08E2),
+    (0x0D4E, 0x0D4E),
+    (0x110BD, 0x110BD),
+    (0x110CD, 0x110CD),
+    (0x111C2, 0x111C3),
+    (0x1193F, 0x1193F),
+    (0x11941, 0x11941),
+    (0x11A3A, 0x11A3A),
+    (0x11A84, 0x11A89),
+    (0x11D46, 0x11D46),
+    (0x11F02, 0x11F02),
+];
+
+// Controls and format characters that always stand alone
+const CONTROL: Table = &[
+    (0x0000, 0x0009),
+    (0x000B, 0x000C),
+    (0x000E, 0x001F),
+    (0x007F, 0x009F),
+    (0x00AD, 0x00AD),
+    (0x061C, 0x061C),
+    (0x200B, 0x200B),
+    (0x200E, 0x200F),
+    (0x2028, 0x202E),
+    (0x2060, 0x206F),
+    (0xFEFF, 0xFEFF),
+];
+
+// Emoji and other pictographs that can be joined with ZWJ
+const PICTOGRAPHIC: Table = &[
+    (0x00A9, 0x00A9),
+    (0x00AE, 0x00AE),
+    (0x203C, 0x203C),
+    (0x2049, 0x2049),
+    (0x2122, 0x2122),
+    (0x2139, 0x2139),
+    (0x2194, 0x2199),
+    (0x21A9, 0x21AA),
+    (0x231A, 0x231B),
+    (0x2328, 0x2328),
+    (0x23CF, 0x23CF),
+    (0x23E9, 0x23F3),
+    (0x23F8, 0x23FA),
+    (0x24C2, 0x24C2),
+    (0x25AA, 0x25AB),
+    (0x25B6, 0x25B6),
+    (0x25C0, 0x25C0),
+    (0x25FB, 0x25FE),
+    (0x2600, 0x27BF),
+    (0x2934, 0x2935),
+    (0x2B05, 0x2B07),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x3030, 0x3030),
+    (0x303D, 0x303D),
+    (0x3297, 0x3297),
+    (0x3299, 0x3299),
+    (0x1F000, 0x1F0FF),
+    (0x1F10D, 0x1F10F),
+    (0x1F12F, 0x1F12F),
+    (0x1F16C, 0x1F171),
+    (0x1F17E, 0x1F17F),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F1AD, 0x1F1E5),
+    (0x1F201, 0x1F20F),
+    (0x1F21A, 0x1F21A),
+    (0x1F22F, 0x1F22F),
+    (0x1F232, 0x1F23A),
+    (0x1F23C, 0x1F23F),
+    (0x1F249, 0x1F3FA),
+    (0x1F400, 0x1F53D),
+    (0x1F546, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F774, 0x1F77F),
+    (0x1F7D5, 0x1F7FF),
+    (0x1F80C, 0x1F80F),
+    (0x1F848, 0x1F84F),
+    (0x1F85A, 0x1F85F),
+    (0x1F888, 0x1F88F),
+    (0x1F8AE, 0x1F8FF),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1FAFF),
+    (0x1FC00, 0x1FFFD),
+];
+
+// Consonants that join into conjuncts through a virama (GB9c)
+const CONJUNCT_CONSONANT: Table = &[
+    (0x0915, 0x0939),
+    (0x0958, 0x095F),
+    (0x0978, 0x097F),
+    (0x0995, 0x09A8),
+    (0x09AA, 0x09B0),
+    (0x09B2, 0x09B2),
+    (0x09B6, 0x09B9),
+    (0x09DC, 0x09DD),
+    (0x09DF, 0x09DF),
+    (0x09F0, 0x09F1),
+    (0x0A95, 0x0AA8),
+    (0x0AAA, 0x0AB0),
+    (0x0AB2, 0x0AB3),
+    (0x0AB5, 0x0AB9),
+    (0x0AF9, 0x0AF9),
+    (0x0B15, 0x0B28),
+    (0x0B2A, 0x0B30),
+    (0x0B32, 0x0B33),
+    (0x0B35, 0x0B39),
+    (0x0B5C, 0x0B5D),
+    (0x0B5F, 0x0B5F),
+    (0x0B71, 0x0B71),
+    (0x0C15, 0x0C28),
+    (0x0C2A, 0x0C39),
+    (0x0C58, 0x0C5A),
+    (0x0D15, 0x0D3A),
+];
+
+// Viramas of the scripts above
+const CONJUNCT_LINKER: Table = &[
+    (0x094D, 0x094D),
+    (0x09CD, 0x09CD),
+    (0x0ACD, 0x0ACD),
+    (0x0B4D, 0x0B4D),
+    (0x0C4D, 0x0C4D),
+    (0x0D4D, 0x0D4D),
+];
+
+// East Asian wide and fullwidth characters, and emoji shown as emoji
+const WIDE: Table = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F1E6, 0x1F1FF),
+    (0x1F200, 0x1F202),
+    (0x1F210, 0x1F23B),
+    (0x1F240, 0x1F248),
+    (0x1F250, 0x1F251),
+    (0x1F300, 0x1F320),
+    (0x1F32D, 0x1F335),
+    (0x1F337, 0x1F37C),
+    (0x1F37E, 0x1F393),
+    (0x1F3A0, 0x1F3CA),
+    (0x1F3CF, 0x1F3D3),
+    (0x1F3E0, 0x1F3F0),
+    (0x1F3F4, 0x1F3F4),
+    (0x1F3F8, 0x1F43E),
+    (0x1F440, 0x1F440),
+    (0x1F442, 0x1F4FC),
+    (0x1F4FF, 0x1F53D),
+    (0x1F54B, 0x1F54E),
+    (0x1F550, 0x1F567),
+    (0x1F57A, 0x1F57A),
+    (0x1F595, 0x1F596),
+    (0x1F5A4, 0x1F5A4),
+    (0x1F5FB, 0x1F64F),
+    (0x1F680, 0x1F6C5),
+    (0x1F6CC, 0x1F6CC),
+    (0x1F6D0, 0x1F6D2),
+    (0x1F6D5, 0x1F6D7),
+    (0x1F6EB, 0x1F6EC),
+    (0x1F6F4, 0x1F6FC),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F90C, 0x1F93A),
+    (0x1F93C, 0x1F945),
+    (0x1F947, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+const ZWJ: char = '\u{200D}';
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+// Grapheme_Cluster_Break property values we distinguish
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Break {
+    Cr,
+    Lf,
+    Control,
+    Extend,
+    Zwj,
+    RegionalIndicator,
+    SpacingMark,
+    Prepend,
+    L,
+    V,
+    T,
+    Lv,
+    Lvt,
+    Other,
+}
+
+fn break_property(c: char) -> Break {
+    let cp = c as u32;
+    match c {
+        '\r' => Break::Cr,
+        '\n' => Break::Lf,
+        ZWJ => Break::Zwj,
+        _ if in_table(CONTROL, c) => Break::Control,
+        _ if in_table(EXTEND, c) => Break::Extend,
+        _ if in_table(SPACING_MARK, c) => Break::SpacingMark,
+        _ if in_table(PREPEND, c) => Break::Prepend,
+        '\u{1F1E6}'..='\u{1F1FF}' => Break::RegionalIndicator,
+        '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => Break::L,
+        '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => Break::V,
+        '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => Break::T,
+        '\u{AC00}'..='\u{D7A3}' if (cp - 0xAC00).is_multiple_of(28) => Break::Lv,
+        '\u{AC00}'..='\u{D7A3}' => Break::Lvt,
+        _ => Break::Other,
+    }
+}
+
+// ============================================================================
+// Grapheme clusters
+// ============================================================================
+
+/// Iterator over the grapheme clusters of a string, as subslices
+#[derive(Debug, Clone)]
+pub struct Graphemes<'a> {
+    rest: &'a str,
+}
+
+/// Splits `text` into user-perceived characters
+pub fn graphemes(text: &str) -> Graphemes<'_> {
+    Graphemes { rest: text }
+}
+
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let len = cluster_len(self.rest);
+        if len == 0 {
+            return None;
+        }
+        let (cluster, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(cluster)
+    }
+}
+
+// Byte length of the first cluster of `text`
+fn cluster_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    let Some((_, first)) = chars.next() else {
+        return 0;
+    };
+
+    let mut prev = break_property(first);
+    // GB11: pictograph Extend* ZWJ, waiting for another pictograph
+    let mut pictographic = in_table(PICTOGRAPHIC, first);
+    let mut after_zwj = false;
+    // GB12/13: regional indicators pair up
+    let mut regional = usize::from(prev == Break::RegionalIndicator);
+    // GB9c: consonant [Extend Linker]* Linker [Extend Linker]*
+    let mut consonant = in_table(CONJUNCT_CONSONANT, first);
+    let mut linked = false;
+
+    for (i, c) in chars {
+        let next = break_property(c);
+        let joins = match (prev, next) {
+            (Break::Cr, Break::Lf) => true,
+            (Break::Cr | Break::Lf | Break::Control, _) => false,
+            (_, Break::Cr | Break::Lf | Break::Control) => false,
+            (Break::L, Break::L | Break::V | Break::Lv | Break::Lvt) => true,
+            (Break::Lv | Break::V, Break::V | Break::T) => true,
+            (Break::Lvt | Break::T, Break::T) => true,
+            (_, Break::Extend | Break::Zwj | Break::SpacingMark) => true,
+            (Break::Prepend, _) => true,
+            (Break::RegionalIndicator, Break::RegionalIndicator) => regional % 2 == 1,
+            _ if after_zwj && in_table(PICTOGRAPHIC, c) => true,
+            _ if consonant && linked && in_table(CONJUNCT_CONSONANT, c) => true,
+            _ => false,
+        };
+        if !joins {
+            return i;
+        }
+
+        after_zwj = pictographic && next == Break::Zwj;
+        pictographic = (pictographic && next == Break::Extend) || in_table(PICTOGRAPHIC, c);
+        if next == Break::RegionalIndicator {
+            regional += 1;
+        }
+        if in_table(CONJUNCT_CONSONANT, c) {
+            consonant = true;
+            linked = false;
+        } else if in_table(CONJUNCT_LINKER, c) {
+            linked = consonant;
+        } else if next != Break::Extend && next != Break::Zwj {
+            consonant = false;
+            linked = false;
+        }
+        prev = next;
+    }
+    text.len()
+}
+
+// ============================================================================
+// Display width
+// ============================================================================
+
+fn char_width(c: char) -> usize {
+    match break_property(c) {
+        Break::Control | Break::Cr | Break::Lf | Break::Extend | Break::Zwj => 0,
+        Break::V | Break::T => 0,
+        _ if in_table(WIDE, c) => 2,
+        _ => 1,
+    }
+}
+
+/// Terminal columns taken by one grapheme cluster
+fn cluster_width(cluster: &str) -> usize {
+    let Some(first) = cluster.chars().next() else {
+        return 0;
+    };
+    if in_table(PICTOGRAPHIC, first) || break_property(first) == Break::RegionalIndicator {
+        // Emoji sequences render as one wide glyph; a text-style symbol
+        // becomes wide when followed by the emoji variation selector
+        if cluster.contains(EMOJI_PRESENTATION) || cluster.chars().count() > 1 {
+            return 2;
+        }
+        return char_width(first);
+    }
+    cluster.chars().map(char_width).sum()
+}
+
+/// Terminal columns needed to show `text` (East Asian wide characters and
+/// emoji take two)
+pub fn width(text: &str) -> usize {
+    graphemes(text).map(cluster_width).sum()
+}
+
+// ============================================================================
+// Metrics
+// ============================================================================
+
+/// The four ways to measure the length of a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    /// UTF-8 bytes: `s.len()`
+    pub bytes: usize,
+    /// Unicode scalar values: `s.chars().count()`
+    pub chars: usize,
+    /// User-perceived characters
+    pub graphemes: usize,
+    /// Terminal columns
+    pub columns: usize,
+}
+
+pub fn metrics(text: &str) -> Metrics {
+    let mut graphemes_count = 0;
+    let mut columns = 0;
+    for cluster in graphemes(text) {
+        graphemes_count += 1;
+        columns += cluster_width(cluster);
+    }
+    Metrics {
+        bytes: text.len(),
+        chars: text.chars().count(),
+        graphemes: graphemes_count,
+        columns,
+    }
+}
+
+// ============================================================================
+// Slicing and truncating
+// ============================================================================
+
+/// Clusters `range` (counted in grapheme clusters) as a subslice. The range
+/// is clamped to the text, so this never panics.
+pub fn slice_graphemes(text: &str, range: Range<usize>) -> &str {
+    let mut start = text.len();
+    let mut end = text.len();
+    let mut offset = 0;
+    for (i, cluster) in graphemes(text).enumerate() {
+        if i == range.start {
+            start = offset;
+        }
+        if i == range.end {
+            end = offset;
+            break;
+        }
+        offset += cluster.len();
+    }
+    &text[start..end.max(start)]
+}
+
+/// The clusters that lie entirely within display columns `range`. A wide
+/// cluster straddling either edge is left out rather than cut.
+pub fn slice_columns(text: &str, range: Range<usize>) -> &str {
+    let mut start = None;
+    let mut end = text.len();
+    let (mut column, mut offset) = (0, 0);
+    for cluster in graphemes(text) {
+        let next_column = column + cluster_width(cluster);
+        if start.is_none() && column >= range.start {
+            start = Some(offset);
+        }
+        if next_column > range.end {
+            end = offset;
+            break;
+        }
+        column = next_column;
+        offset += cluster.len();
+    }
+    let start = start.unwrap_or(offset);
+    &text[start..end.max(start)]
+}
+
+/// Keeps at most `max` grapheme clusters. If anything is cut, `ellipsis`
+/// is appended and counts towards `max`; an ellipsis longer than `max` is
+/// cut to its first `max` clusters.
+pub fn truncate_graphemes<'a>(text: &'a str, max: usize, ellipsis: Option<&str>) -> Cow<'a, str> {
+    if graphemes(text).nth(max).is_none() {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = slice_graphemes(ellipsis.unwrap_or(""), 0..max);
+    let room = max.saturating_sub(graphemes(ellipsis).count());
+    let kept: usize = graphemes(text).take(room).map(str::len).sum();
+    join(&text[..kept], ellipsis)
+}
+
+/// Keeps as much as fits in `max` display columns. If anything is cut,
+/// `ellipsis` is appended and its width counts towards `max`; an ellipsis
+/// wider than `max` is cut like the text.
+pub fn truncate_columns<'a>(text: &'a str, max: usize, ellipsis: Option<&str>) -> Cow<'a, str> {
+    if width(text) <= max {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = slice_columns(ellipsis.unwrap_or(""), 0..max);
+    let room = max.saturating_sub(width(ellipsis));
+    let kept = slice_columns(text, 0..room);
+    join(kept, ellipsis)
+}
+
+fn join<'a>(kept: &'a str, ellipsis: &str) -> Cow<'a, str> {
+    if ellipsis.is_empty() {
+        Cow::Borrowed(kept)
+    } else {
+        Cow::Owned(format!("{kept}{ellipsis}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each line of GraphemeBreakTest.txt is a list of hex code points with
+    // `÷` (break) or `×` (no break) between them
+    fn parse_case(case: &str) -> Vec<String> {
+        let mut clusters = vec![String::new()];
+        for token in case.split_whitespace() {
+            match token {
+                "÷" => clusters.push(String::new()),
+                "×" => {}
+                code => {
+                    let c = u32::from_str_radix(code, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .unwrap_or_else(|| panic!("bad code point {code:?}"));
+                    clusters.last_mut().unwrap().push(c);
+                }
+            }
+        }
+        clusters.retain(|cluster| !cluster.is_empty());
+        clusters
+    }
+
+    #[test]
+    fn grapheme_break_test() {
+        let data = include_str!("../data/GraphemeBreakTest.txt");
+        assert!(data.starts_with("# GraphemeBreakTest-15.1.0.txt"));
+        let mut cases = 0;
+        let mut failures = Vec::new();
+        for (number, line) in data.lines().enumerate() {
+            let case = line.split('#').next().unwrap().trim();
+            if case.is_empty() {
+                continue;
+            }
+            cases += 1;
+            let expected = parse_case(case);
+            let text = expected.concat();
+            let got: Vec<&str> = graphemes(&text).collect();
+            if got != expected {
+                failures.push(format!("line {}: {line}", number + 1));
+            }
+        }
+        assert!(cases > 1000, "only {cases} cases");
+        assert!(
+            failures.is_empty(),
+            "{} of {cases} cases failed:\n{}",
+            failures.len(),
+            failures[..failures.len().min(10)].join("\n")
+        );
+    }
+
+    #[test]
+    fn the_four_lengths() {
+        let m = metrics("नमस्ते");
+        assert_eq!((m.bytes, m.chars, m.graphemes, m.columns), (18, 6, 3, 4));
+        assert_eq!(width("日本"), 4);
+        assert_eq!(width("👨‍👩‍👧"), 2);
+        assert_eq!(width("🇫🇷"), 2);
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!(metrics("").graphemes, 0);
+    }
+
+    #[test]
+    fn slicing_keeps_clusters_whole() {
+        assert_eq!(slice_graphemes("नमस्ते", 2..3), "स्ते");
+        assert_eq!(slice_graphemes("abc", 1..10), "bc");
+        assert_eq!(slice_graphemes("abc", 5..10), "");
+        // 日 takes columns 0-1, 本 2-3
+        assert_eq!(slice_columns("日本", 0..3), "日");
+        assert_eq!(slice_columns("日本", 1..4), "本");
+        assert_eq!(slice_columns("日本", 1..3), "");
+    }
+
+    #[test]
+    fn truncate_graphemes_counts_the_ellipsis() {
+        let cases: &[(&str, usize, Option<&str>, &str)] = &[
+            ("hello world", 11, Some("..."), "hello world"),
+            ("hello world", 8, Some("..."), "hello..."),
+            ("hello world", 5, None, "hello"),
+            ("hello world", 3, Some("..."), "..."),
+            ("hello world", 2, Some("..."), ".."),
+            ("hello world", 0, Some("..."), ""),
+            ("नमस्ते", 2, Some("…"), "न…"),
+            ("👨‍👩‍👧👨‍👩‍👧", 1, None, "👨‍👩‍👧"),
+        ];
+        for &(text, max, ellipsis, expected) in cases {
+            let got = truncate_graphemes(text, max, ellipsis);
+            assert_eq!(got, expected, "{text:?} to {max}");
+            assert!(graphemes(&got).count() <= max);
+        }
+        assert!(matches!(
+            truncate_graphemes("short", 5, Some("...")),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn truncate_columns_counts_the_ellipsis() {
+        let cases: &[(&str, usize, Option<&str>, &str)] = &[
+            ("hello world", 11, Some("…"), "hello world"),
+            ("hello world", 6, Some("…"), "hello…"),
+            ("hello world", 1, Some("……"), "…"),
+            ("hello world", 0, Some("…"), ""),
+            // A wide char that doesn't fit is dropped, not cut
+            ("日本語", 4, Some("…"), "日…"),
+            ("日本語", 3, None, "日"),
+            // An ellipsis that doesn't fit at all leaves the room to the text
+            ("abc", 1, Some("日"), "a"),
+            ("日本語", 1, None, ""),
+        ];
+        for &(text, max, ellipsis, expected) in cases {
+            let got = truncate_columns(text, max, ellipsis);
+            assert_eq!(got, expected, "{text:?} to {max}");
+            assert!(width(&got) <= max);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+// A global allocator that counts heap traffic.
+//
+// Install it in a binary with
+//
+//     #[global_allocator]
+//     static GLOBAL: CountingAllocator = CountingAllocator;
+//
+// and then use `measure`, `Probe` or `Sections` to see what each piece of
+// code allocated. All counters are process-wide. Measurements nest: an inner
+// `measure` starts its own peak window and, when it ends, folds its peak back
+// into the enclosing one.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static DEALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static BYTES_FREED: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Forwards to the system allocator and counts every call
+pub struct CountingAllocator;
+
+fn grew(bytes: usize) {
+    BYTES_ALLOCATED.fetch_add(bytes, Ordering::Relaxed);
+    let live = LIVE_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+fn shrank(bytes: usize) {
+    BYTES_FREED.fetch_add(bytes, Ordering::Relaxed);
+    LIVE_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: same contract as the caller's
+        unsafe { System.dealloc(ptr, layout) };
+        DEALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: same contract as the caller's
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            REALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                grew(new_size - layout.size());
+            } else {
+                shrank(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+// ============================================================================
+// Snapshots
+// ============================================================================
+
+/// Counter values at one point in time, or the difference between two
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub deallocations: usize,
+    pub reallocations: usize,
+    pub bytes_allocated: usize,
+    pub bytes_freed: usize,
+    /// Bytes currently allocated (for a difference: at the end)
+    pub live_bytes: usize,
+    /// Highest `live_bytes` seen (for a difference: above the starting level)
+    pub peak_bytes: usize,
+}
+
+/// Current counter values
+pub fn stats() -> Stats {
+    Stats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        deallocations: DEALLOCATIONS.load(Ordering::Relaxed),
+        reallocations: REALLOCATIONS.load(Ordering::Relaxed),
+        bytes_allocated: BYTES_ALLOCATED.load(Ordering::Relaxed),
+        bytes_freed: BYTES_FREED.load(Ordering::Relaxed),
+        live_bytes: LIVE_BYTES.load(Ordering::Relaxed),
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Starts a new peak window at the current live size
+pub fn reset_peak() {
+    PEAK_BYTES.store(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+impl Stats {
+    /// What happened between `start` and `self`. Only meaningful if
+    /// `reset_peak` was called when `start` was taken.
+    pub fn since(&self, start: &Stats) -> Stats {
+        Stats {
+            allocations: self.allocations - start.allocations,
+            deallocations: self.deallocations - start.deallocations,
+            reallocations: self.reallocations - start.reallocations,
+            bytes_allocated: self.bytes_allocated - start.bytes_allocated,
+            bytes_freed: self.bytes_freed - start.bytes_freed,
+            live_bytes: self.live_bytes,
+            peak_bytes: self.peak_bytes.saturating_sub(start.live_bytes),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.allocations == 0 && self.deallocations == 0 && self.reallocations == 0
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} frees, {} reallocs, {} bytes allocated, peak +{} bytes",
+            self.allocations,
+            self.deallocations,
+            self.reallocations,
+            self.bytes_allocated,
+            self.peak_bytes
+        )
+    }
+}
+
+/// Measures the statements between `Probe::start()` and `probe.finish()`,
+/// so a lesson can keep its plain `let s2 = s1;` in between
+pub struct Probe {
+    start: Stats,
+    // The enclosing window's peak, restored by `finish`
+    outer_peak: usize,
+}
+
+impl Probe {
+    pub fn start() -> Probe {
+        let outer_peak = PEAK_BYTES.load(Ordering::Relaxed);
+        reset_peak();
+        Probe {
+            start: stats(),
+            outer_peak,
+        }
+    }
+
+    /// What happened since `start`. The enclosing window's peak is put back,
+    /// raised to this window's peak if that was higher.
+    pub fn finish(self) -> Stats {
+        let end = stats();
+        PEAK_BYTES.fetch_max(self.outer_peak, Ordering::Relaxed);
+        end.since(&self.start)
+    }
+}
+
+/// Runs `f` and returns what it allocated
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Stats) {
+    let probe = Probe::start();
+    let result = f();
+    (result, probe.finish())
+}
+
+// ============================================================================
+// Per-section report
+// ============================================================================
+
+/// Collects `Stats` for consecutive named sections of a program
+pub struct Sections {
+    entries: Vec<(&'static str, Stats)>,
+    current: Option<(&'static str, Probe)>,
+}
+
+impl Sections {
+    /// `capacity` should cover every section, so that recording one never
+    /// reallocates and shows up in the next section's numbers.
+    pub fn with_capacity(capacity: usize) -> Sections {
+        Sections {
+            entries: Vec::with_capacity(capacity),
+            current: None,
+        }
+    }
+
+    /// Ends the current section (if any) and starts a new one
+    pub fn begin(&mut self, name: &'static str) {
+        self.end();
+        self.current = Some((name, Probe::start()));
+    }
+
+    /// Ends the current section and returns its stats
+    pub fn end(&mut self) -> Option<Stats> {
+        let (name, probe) = self.current.take()?;
+        let delta = probe.finish();
+        self.entries.push((name, delta));
+        Some(delta)
+    }
+
+    pub fn entries(&self) -> &[(&'static str, Stats)] {
+        &self.entries
+    }
+}
+
+impl fmt::Display for Sections {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .entries
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0)
+            .max("SECTION".len());
+        writeln!(
+            f,
+            "{:<width$}  {:>6}  {:>6}  {:>8}  {:>9}  {:>10}",
+            "SECTION", "ALLOCS", "FREES", "REALLOCS", "BYTES", "PEAK BYTES"
+        )?;
+        for (name, s) in &self.entries {
+            writeln!(
+                f,
+                "{name:<width$}  {:>6}  {:>6}  {:>8}  {:>9}  {:>10}",
+                s.allocations, s.deallocations, s.reallocations, s.bytes_allocated, s.peak_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    // Counters are process-wide and other tests allocate concurrently, so
+    // these only make claims that a little extra traffic can't break.
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    const BIG: usize = 1 << 20;
+
+    #[test]
+    fn measure_counts_what_the_closure_allocates() {
+        let (v, heap) = measure(|| black_box(Vec::<u8>::with_capacity(BIG)));
+        assert!(heap.allocations >= 1);
+        assert!(heap.bytes_allocated >= BIG);
+        assert!(heap.peak_bytes >= BIG);
+        drop(v);
+
+        let (_, heap) = measure(|| drop(black_box(vec![0u8; BIG])));
+        assert!(heap.deallocations >= 1);
+        assert!(heap.bytes_freed >= BIG);
+    }
+
+    #[test]
+    fn nested_measure_keeps_the_outer_peak() {
+        let outer = Probe::start();
+        drop(black_box(vec![0u8; BIG]));
+        let (_, inner) = measure(|| black_box(String::from("small")));
+        assert!(inner.peak_bytes < BIG);
+        let outer = outer.finish();
+        assert!(outer.peak_bytes >= BIG, "outer peak {}", outer.peak_bytes);
+    }
+
+    #[test]
+    fn nested_peak_raises_the_outer_peak() {
+        let outer = Probe::start();
+        let (_, inner) = measure(|| drop(black_box(vec![0u8; BIG])));
+        assert!(inner.peak_bytes >= BIG);
+        assert!(outer.finish().peak_bytes >= BIG);
+    }
+
+    #[test]
+    fn sections_survive_a_measure_inside_them() {
+        let mut sections = Sections::with_capacity(2);
+        sections.begin("big");
+        drop(black_box(vec![0u8; BIG]));
+        let _ = measure(|| black_box(1));
+        sections.begin("empty");
+        sections.end();
+
+        let [(first, big), (second, _)] = sections.entries() else {
+            panic!("expected two sections");
+        };
+        assert_eq!((*first, *second), ("big", "empty"));
+        assert!(big.peak_bytes >= BIG);
+        assert!(sections.to_string().starts_with("SECTION"));
+    }
+
+    #[test]
+    fn since_subtracts_counts_and_keeps_live_bytes() {
+        let start = Stats {
+            allocations: 2,
+            deallocations: 1,
+            live_bytes: 100,
+            ..Stats::default()
+        };
+        let end = Stats {
+            allocations: 5,
+            deallocations: 1,
+            live_bytes: 40,
+            peak_bytes: 150,
+            ..Stats::default()
+        };
+        let delta = end.since(&start);
+        assert_eq!(delta.allocations, 3);
+        assert_eq!(delta.deallocations, 0);
+        assert_eq!(delta.live_bytes, 40);
+        assert_eq!(delta.peak_bytes, 50);
+        assert!(!delta.is_zero());
+        assert!(Stats::default().is_zero());
+    }
+}
@@ -0,0 +1,325 @@
+// Looks inside a `String` or `Vec`: where the handle lives on the stack,
+// where its buffer lives on the heap, and how long and big that buffer is.
+//
+// Section 2 of main.rs says a String is a "pointer on stack, data on heap";
+// this module prints the actual addresses so that claim can be watched
+// across push_str, clear, shrink_to_fit, clone and moves.
+//
+// Taking a snapshot and printing a diagram don't allocate, so main.rs can
+// show them inside a section without changing its heap numbers.
+
+use std::fmt::{self, Write};
+use std::mem;
+
+/// A snapshot of a String/Vec handle and its buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Anatomy {
+    /// Address of the (ptr, len, capacity) handle itself
+    pub handle: usize,
+    /// Size of the handle in bytes (3 words)
+    pub handle_size: usize,
+    /// Address of the heap buffer; dangling when `heap_bytes` is 0
+    pub buffer: usize,
+    pub len: usize,
+    pub capacity: usize,
+    /// Size of the heap buffer: capacity times the element size, so 0 for a
+    /// new String and for every Vec of zero-sized elements
+    pub heap_bytes: usize,
+    /// Short rendering of the contents
+    pub contents: Preview,
+}
+
+/// Types whose layout is a (ptr, len, capacity) handle
+pub trait Inspect {
+    fn anatomy(&self) -> Anatomy;
+}
+
+impl Inspect for String {
+    fn anatomy(&self) -> Anatomy {
+        Anatomy {
+            handle: self as *const String as usize,
+            handle_size: mem::size_of::<String>(),
+            buffer: self.as_ptr() as usize,
+            len: self.len(),
+            capacity: self.capacity(),
+            heap_bytes: self.capacity(),
+            contents: Preview::of(self),
+        }
+    }
+}
+
+impl<T: fmt::Debug> Inspect for Vec<T> {
+    fn anatomy(&self) -> Anatomy {
+        Anatomy {
+            handle: self as *const Vec<T> as usize,
+            handle_size: mem::size_of::<Vec<T>>(),
+            buffer: self.as_ptr() as usize,
+            len: self.len(),
+            capacity: self.capacity(),
+            // Vec<()> reports a capacity of usize::MAX without allocating
+            heap_bytes: self.capacity() * mem::size_of::<T>(),
+            contents: Preview::of(self),
+        }
+    }
+}
+
+const PREVIEW_CHARS: usize = 32;
+
+/// The start of a value's `Debug` output, kept inline so that taking a
+/// snapshot doesn't allocate
+#[derive(Clone, PartialEq, Eq)]
+pub struct Preview {
+    bytes: [u8; PREVIEW_CHARS * 4],
+    len: usize,
+    // Characters written, including the ones that didn't fit
+    chars: usize,
+}
+
+impl Preview {
+    fn of(value: &impl fmt::Debug) -> Preview {
+        let mut preview = Preview {
+            bytes: [0; PREVIEW_CHARS * 4],
+            len: 0,
+            chars: 0,
+        };
+        // Preview::write_str never fails
+        let _ = write!(preview, "{value:?}");
+        preview
+    }
+
+    fn kept(&self) -> &str {
+        // Only whole chars are copied in
+        std::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl Write for Preview {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for c in text.chars() {
+            self.chars += 1;
+            if self.chars <= PREVIEW_CHARS {
+                self.len += c.encode_utf8(&mut self.bytes[self.len..]).len();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kept = self.kept();
+        if self.chars <= PREVIEW_CHARS {
+            return f.write_str(kept);
+        }
+        let cut = kept
+            .char_indices()
+            .nth(PREVIEW_CHARS - 3)
+            .map_or(kept.len(), |(i, _)| i);
+        write!(f, "{}...", &kept[..cut])
+    }
+}
+
+impl fmt::Debug for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Preview({self})")
+    }
+}
+
+/// One difference between two snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    HandleMoved { from: usize, to: usize },
+    BufferMoved,
+    BufferAllocated,
+    BufferFreed,
+    Len { from: usize, to: usize },
+    Capacity { from: usize, to: usize },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::HandleMoved { from, to } => {
+                write!(f, "handle moved on the stack ({from:#x} -> {to:#x})")
+            }
+            Change::BufferMoved => write!(f, "heap buffer is at a new address"),
+            Change::BufferAllocated => write!(f, "heap buffer allocated"),
+            Change::BufferFreed => write!(f, "heap buffer freed"),
+            Change::Len { from, to } => write!(f, "len {from} -> {to}"),
+            Change::Capacity { from, to } => write!(f, "capacity {from} -> {to}"),
+        }
+    }
+}
+
+impl Anatomy {
+    pub fn has_buffer(&self) -> bool {
+        self.heap_bytes > 0
+    }
+
+    /// What differs between `self` and `after`
+    pub fn changes(&self, after: &Anatomy) -> impl Iterator<Item = Change> {
+        let handle = (self.handle != after.handle).then_some(Change::HandleMoved {
+            from: self.handle,
+            to: after.handle,
+        });
+        let buffer = match (self.has_buffer(), after.has_buffer()) {
+            (true, true) if self.buffer != after.buffer => Some(Change::BufferMoved),
+            (false, true) => Some(Change::BufferAllocated),
+            (true, false) => Some(Change::BufferFreed),
+            _ => None,
+        };
+        let len = (self.len != after.len).then_some(Change::Len {
+            from: self.len,
+            to: after.len,
+        });
+        let capacity = (self.capacity != after.capacity).then_some(Change::Capacity {
+            from: self.capacity,
+            to: after.capacity,
+        });
+        [handle, buffer, len, capacity].into_iter().flatten()
+    }
+}
+
+impl fmt::Display for Anatomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stack {:#014x} [ptr | len {} | cap {}]",
+            self.handle, self.len, self.capacity
+        )?;
+        if self.has_buffer() {
+            write!(f, " --> heap {:#014x} {}", self.buffer, self.contents)
+        } else {
+            write!(f, " --> (no heap buffer)")
+        }
+    }
+}
+
+/// Before/after picture of one operation, ready to print
+pub struct Diagram<'a> {
+    operation: &'a str,
+    before: &'a Anatomy,
+    after: &'a Anatomy,
+}
+
+pub fn diagram<'a>(operation: &'a str, before: &'a Anatomy, after: &'a Anatomy) -> Diagram<'a> {
+    Diagram {
+        operation,
+        before,
+        after,
+    }
+}
+
+impl fmt::Display for Diagram<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "   {}", self.operation)?;
+        writeln!(f, "      before: {}", self.before)?;
+        writeln!(f, "      after:  {}", self.after)?;
+        write!(f, "      changed: ")?;
+        let mut changes = self.before.changes(self.after).peekable();
+        if changes.peek().is_none() {
+            write!(f, "nothing")?;
+        }
+        for (i, change) in changes.enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{change}")?;
+        }
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_and_zero_sized_vecs_have_no_buffer() {
+        assert!(!String::new().anatomy().has_buffer());
+        assert!(!Vec::<u64>::new().anatomy().has_buffer());
+
+        let units = vec![(); 10];
+        let anatomy = units.anatomy();
+        assert_eq!(anatomy.capacity, usize::MAX);
+        assert_eq!(anatomy.heap_bytes, 0);
+        assert!(!anatomy.has_buffer());
+        assert!(anatomy.to_string().ends_with("(no heap buffer)"));
+    }
+
+    #[test]
+    fn heap_bytes_scale_with_the_element_size() {
+        let v: Vec<u32> = Vec::with_capacity(4);
+        let anatomy = v.anatomy();
+        assert_eq!(anatomy.heap_bytes, anatomy.capacity * 4);
+        assert_eq!(anatomy.handle_size, 3 * mem::size_of::<usize>());
+        assert!(anatomy.has_buffer());
+    }
+
+    #[test]
+    fn move_keeps_the_buffer() {
+        let s1 = String::from("hello");
+        let before = s1.anatomy();
+        let s2 = std::hint::black_box(s1);
+        let after = s2.anatomy();
+        assert_eq!(before.buffer, after.buffer);
+        assert!(
+            before
+                .changes(&after)
+                .all(|c| matches!(c, Change::HandleMoved { .. }))
+        );
+    }
+
+    #[test]
+    fn clone_gets_its_own_buffer() {
+        let s3 = String::from("hello");
+        let s4 = s3.clone();
+        let changes: Vec<Change> = s3.anatomy().changes(&s4.anatomy()).collect();
+        assert!(changes.contains(&Change::BufferMoved));
+    }
+
+    #[test]
+    fn allocation_and_freeing() {
+        let mut s = String::new();
+        let before = s.anatomy();
+        s.push('a');
+        let after = s.anatomy();
+        let changes: Vec<Change> = before.changes(&after).collect();
+        assert_eq!(changes[0], Change::BufferAllocated);
+        assert!(changes.contains(&Change::Len { from: 0, to: 1 }));
+
+        s.clear();
+        s.shrink_to_fit();
+        let freed: Vec<Change> = after.changes(&s.anatomy()).collect();
+        assert_eq!(freed[0], Change::BufferFreed);
+    }
+
+    #[test]
+    fn preview_is_truncated_on_a_char_boundary() {
+        let short = String::from("héllo");
+        assert_eq!(short.anatomy().contents.to_string(), "\"héllo\"");
+
+        let long = "é".repeat(40);
+        let preview = long.anatomy().contents.to_string();
+        assert_eq!(preview, format!("\"{}...", "é".repeat(28)));
+        assert_eq!(preview.chars().count(), PREVIEW_CHARS);
+
+        let exact = "x".repeat(PREVIEW_CHARS - 2);
+        assert_eq!(exact.anatomy().contents.to_string(), format!("\"{exact}\""));
+        assert_eq!(vec![1, 2].anatomy().contents.to_string(), "[1, 2]");
+    }
+
+    #[test]
+    fn diagram_lists_every_change() {
+        let before = String::from("ab").anatomy();
+        let diagram = diagram("nothing", &before, &before).to_string();
+        assert!(diagram.starts_with("   nothing\n      before: "));
+        assert!(diagram.ends_with("      changed: nothing\n"));
+
+        let mut after = before.clone();
+        after.len = 1;
+        after.capacity = 8;
+        let diagram = super::diagram("edit", &before, &after).to_string();
+        assert!(diagram.ends_with("changed: len 2 -> 1, capacity 2 -> 8\n"));
+    }
+}
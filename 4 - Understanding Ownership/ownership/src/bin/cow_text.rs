@@ -0,0 +1,135 @@
+// Borrow, and only allocate when the text actually changes.
+//
+// Every function in ownership::text takes &str and returns Cow<str>. This
+// demo checks that already-normalized input comes back as Cow::Borrowed and
+// that the counting allocator sees no heap traffic for it.
+
+use std::borrow::Cow;
+
+use ownership::alloc_counter::{self, CountingAllocator};
+use ownership::text;
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() {
+    println!("=== COPY-ON-WRITE TEXT ===\n");
+
+    // ========================================================================
+    // 1. THE OLD WAY: MOVE IN, MOVE BACK OUT
+    // ========================================================================
+    println!("1. MOVING OWNERSHIP BACK AND FORTH:");
+
+    let s = String::from("hello");
+    let (s, len) = calculate_length(s);
+    let s = takes_and_gives_back(s);
+    println!("   calculate_length + takes_and_gives_back: '{s}' ({len} bytes)");
+    println!("   Works, but the caller has to hand its String over and take it back\n");
+
+    // ========================================================================
+    // 2. THE COW WAY: BORROW, ALLOCATE ONLY ON CHANGE
+    // ========================================================================
+    println!("2. BORROWED WHEN NOTHING CHANGES:");
+
+    type Step = fn(&str) -> Cow<'_, str>;
+    let steps: [(&str, Step, &str, &str); 6] = [
+        ("trim", text::trim, "hello", "  hello  "),
+        ("lowercase", text::lowercase, "hello", "HeLLo"),
+        (
+            "collapse_whitespace",
+            text::collapse_whitespace,
+            "hello world",
+            "hello \t\n world",
+        ),
+        (
+            "escape_html",
+            text::escape_html,
+            "fish and chips",
+            "fish & <chips>",
+        ),
+        (
+            "escape_json",
+            text::escape_json,
+            "plain text",
+            "say \"hi\"\n",
+        ),
+        (
+            "normalize",
+            text::normalize,
+            "already normal",
+            "  Not\tSo   NORMAL ",
+        ),
+    ];
+
+    for (name, step, clean, dirty) in steps {
+        let (clean_result, clean_heap) = alloc_counter::measure(|| step(clean));
+        let (dirty_result, dirty_heap) = alloc_counter::measure(|| step(dirty));
+
+        println!("   {name}:");
+        println!(
+            "      {clean:?} -> {:?} ({}, {} allocations)",
+            clean_result,
+            kind(matches!(clean_result, Cow::Borrowed(_))),
+            clean_heap.allocations
+        );
+        println!(
+            "      {dirty:?} -> {:?} ({}, {} allocations)",
+            dirty_result,
+            kind(matches!(dirty_result, Cow::Borrowed(_))),
+            dirty_heap.allocations
+        );
+
+        // Unchanged input must come back borrowed, without touching the heap
+        assert!(
+            matches!(clean_result, Cow::Borrowed(_)),
+            "{name}({clean:?}) allocated"
+        );
+        assert_eq!(clean_heap.allocations, 0, "{name}({clean:?}) allocated");
+    }
+
+    // trim only ever slices, even when it removes something
+    assert!(matches!(text::trim("  padded  "), Cow::Borrowed("padded")));
+    // collapse_whitespace can borrow the trimmed middle of the input
+    assert!(matches!(
+        text::collapse_whitespace("  two words  "),
+        Cow::Borrowed("two words")
+    ));
+    // Changed input is owned and has the expected contents
+    assert!(matches!(text::lowercase("ABC"), Cow::Owned(ref s) if s == "abc"));
+    assert_eq!(
+        text::escape_html("<a href='x'>"),
+        "&lt;a href=&#39;x&#39;&gt;"
+    );
+    assert_eq!(text::normalize("  Hello \n  WORLD "), "hello world");
+    println!("\n   All unchanged inputs came back as Cow::Borrowed with 0 allocations");
+
+    // ========================================================================
+    // 3. CHAINING STEPS
+    // ========================================================================
+    println!("\n3. CHAINING STEPS WITH and_then:");
+
+    let input = "  Fish & Chips  ";
+    let (result, heap) = alloc_counter::measure(|| {
+        let step = text::collapse_whitespace(input);
+        let step = text::and_then(step, text::lowercase);
+        text::and_then(step, text::escape_html)
+    });
+    println!(
+        "   {input:?} -> {result:?} ({}, {} allocations)",
+        kind(matches!(result, Cow::Borrowed(_))),
+        heap.allocations
+    );
+}
+
+fn kind(borrowed: bool) -> &'static str {
+    if borrowed { "borrowed" } else { "owned" }
+}
+
+fn takes_and_gives_back(a_string: String) -> String {
+    a_string // Ownership is transferred back to caller
+}
+
+fn calculate_length(s: String) -> (String, usize) {
+    let length = s.len();
+    (s, length) // Return both the String and its length
+}
@@ -1,9 +1,18 @@
+use ownership::alloc_counter::{CountingAllocator, Probe, Sections};
+use ownership::anatomy::{self, Inspect};
+
+// Counts every heap allocation so each section can report its heap traffic
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
 fn main() {
     println!("=== RUST OWNERSHIP CONCEPTS ===\n");
+    let mut sections = Sections::with_capacity(16);
     
     // ========================================================================
     // 1. WHAT IS OWNERSHIP?
     // ========================================================================
+    sections.begin("1. What is ownership");
     println!("1. WHAT IS OWNERSHIP?");
     println!("   - Rust's memory management system (no GC, no manual malloc/free)");
     println!("   - Set of rules enforced at compile time");
@@ -12,6 +21,7 @@ fn main() {
     // ========================================================================
     // 2. THE STACK AND THE HEAP
     // ========================================================================
+    sections.begin("2. Stack vs heap");
     println!("2. STACK vs HEAP:");
     
     // Stack example - fixed size, fast
@@ -22,6 +32,7 @@ fn main() {
     // Heap example - dynamic size, slower
     let s = String::from("hello");  // String, dynamic size, data on heap
     println!("   Heap value: s = '{}' (pointer on stack, data on heap)", s);
+    println!("      {}", s.anatomy());
     
     println!("   Key differences:");
     println!("   - Stack: LIFO, fixed size, fast, automatic allocation");
@@ -30,6 +41,7 @@ fn main() {
     // ========================================================================
     // 3. OWNERSHIP RULES
     // ========================================================================
+    sections.begin("3. Ownership rules");
     println!("3. THREE OWNERSHIP RULES:");
     println!("   1. Each value has an owner");
     println!("   2. Only one owner at a time");
@@ -38,6 +50,7 @@ fn main() {
     // ========================================================================
     // 4. VARIABLE SCOPE
     // ========================================================================
+    sections.begin("4. Variable scope");
     println!("4. VARIABLE SCOPE:");
     {
         let inner_var = "I exist only in this block";
@@ -49,6 +62,7 @@ fn main() {
     // ========================================================================
     // 5. STRING TYPE - HEAP ALLOCATED
     // ========================================================================
+    sections.begin("5. String type");
     println!("\n5. STRING TYPE (Heap allocated):");
     
     // String literal (immutable, in binary)
@@ -57,24 +71,32 @@ fn main() {
     
     // String type (mutable, heap allocated)
     let mut string = String::from("hello");
+    let before = string.anatomy();
     string.push_str(", world!");
     println!("   String type: '{}' (mutable, heap allocated)", string);
+    print!("{}", anatomy::diagram("string.push_str(\", world!\")", &before, &string.anatomy()));
     
     // ========================================================================
     // 6. MOVE SEMANTICS
     // ========================================================================
+    sections.begin("6. Move semantics");
     println!("\n6. MOVE SEMANTICS (Key Concept!):");
     
     let s1 = String::from("hello");
     println!("   Created s1 = '{}'", s1);
+    let before = s1.anatomy();
     
     // This MOVES ownership from s1 to s2
+    let probe = Probe::start();
     let s2 = s1;  // s1 is INVALIDATED after this line
+    let move_heap = probe.finish();
     
     println!("   After 'let s2 = s1':");
     println!("   - s2 = '{}' (valid)", s2);
     // println!("   - s1 = '{}' (ERROR: borrow of moved value)", s1);
     println!("   - s1 is INVALID (ownership moved to s2)");
+    println!("   - Heap traffic of the move: {}", move_heap);
+    print!("{}", anatomy::diagram("let s2 = s1", &before, &s2.anatomy()));
     
     // Why? To prevent double-free!
     // Both s1 and s2 would try to free the same memory when going out of scope
@@ -82,20 +104,26 @@ fn main() {
     // ========================================================================
     // 7. CLONING (Deep Copy)
     // ========================================================================
+    sections.begin("7. Cloning");
     println!("\n7. CLONING (Explicit deep copy):");
     
     let s3 = String::from("hello");
+    let probe = Probe::start();
     let s4 = s3.clone();  // Explicit deep copy - both heap data AND metadata
+    let clone_heap = probe.finish();
     
     println!("   After cloning:");
     println!("   - s3 = '{}' (still valid)", s3);
     println!("   - s4 = '{}' (copy of s3)", s4);
     println!("   - Both are valid independent strings");
-    println!("   - Expensive operation - copies heap data\n");
+    println!("   - Expensive operation - copies heap data");
+    println!("   - Heap traffic of the clone: {}", clone_heap);
+    println!("{}", anatomy::diagram("let s4 = s3.clone()", &s3.anatomy(), &s4.anatomy()));
     
     // ========================================================================
     // 8. COPY TRAIT (Stack-only types)
     // ========================================================================
+    sections.begin("8. Copy trait");
     println!("8. COPY TRAIT (Stack-only types):");
     
     let x1 = 5;
@@ -126,6 +154,7 @@ fn main() {
     // ========================================================================
     // 9. OWNERSHIP AND FUNCTIONS
     // ========================================================================
+    sections.begin("9. Ownership and functions");
     println!("\n9. OWNERSHIP AND FUNCTIONS:");
     
     let s_func = String::from("hello");
@@ -141,6 +170,7 @@ fn main() {
     // ========================================================================
     // 10. RETURN VALUES AND SCOPE
     // ========================================================================
+    sections.begin("10. Return values and scope");
     println!("\n10. RETURN VALUES AND SCOPE:");
     
     let s_returned = gives_ownership();  // Takes ownership of return value
@@ -153,6 +183,7 @@ fn main() {
     // ========================================================================
     // 11. TUPLES FOR MULTIPLE RETURNS
     // ========================================================================
+    sections.begin("11. Tuples for multiple returns");
     println!("\n11. TUPLES FOR MULTIPLE RETURN VALUES:");
     
     let s_tuple = String::from("hello");
@@ -162,15 +193,22 @@ fn main() {
     // ========================================================================
     // 12. REASSIGNMENT AND DROP
     // ========================================================================
+    sections.begin("12. Reassignment and drop");
     println!("\n12. REASSIGNMENT AND DROP:");
     
     let mut s_reassign = String::from("hello");
     println!("   Created: '{}'", s_reassign);
+    let before = s_reassign.anatomy();
     
     s_reassign = String::from("ahoy");  // Original "hello" is DROPPED here!
     println!("   Reassigned: '{}'", s_reassign);
     println!("   Original 'hello' was automatically freed (drop called)");
+    print!("{}", anatomy::diagram("s_reassign = String::from(\"ahoy\")", &before, &s_reassign.anatomy()));
     
+    sections.end();
+    println!("\n=== HEAP TRAFFIC PER SECTION ===");
+    print!("{}", sections);
+
     println!("\n=== SUMMARY ===");
     println!("1. Ownership prevents memory errors at compile time");
     println!("2. Move semantics prevent double-free errors");
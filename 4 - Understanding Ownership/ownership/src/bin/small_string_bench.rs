@@ -0,0 +1,134 @@
+// Compares SmallString with String: heap allocations and clone speed.
+//
+// Run with optimizations for meaningful timings:
+//   cargo run --release --bin small_string_bench
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ownership::alloc_counter::{self, CountingAllocator};
+use ownership::small_string::{INLINE_CAPACITY, SmallString};
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Strings from the lessons, plus one that is too long to stay inline
+const SAMPLES: [&str; 6] = [
+    "hello",
+    "yours",
+    "someusername123",
+    "someone@example.com",
+    "hello, world!",
+    "I can be modified through function",
+];
+
+const ROUNDS: usize = 100_000;
+
+fn main() {
+    println!("=== SmallString vs String ===\n");
+    println!(
+        "size_of::<String>() = {}, size_of::<SmallString>() = {}, inline capacity = {} bytes\n",
+        std::mem::size_of::<String>(),
+        std::mem::size_of::<SmallString>(),
+        INLINE_CAPACITY
+    );
+
+    // ========================================================================
+    // 1. ALLOCATIONS
+    // ========================================================================
+    println!("1. HEAP ALLOCATIONS ({ROUNDS} creates + clones per sample):");
+    println!("   {:<48} {:>12} {:>12}", "sample", "String", "SmallString");
+    for sample in SAMPLES {
+        let (_, string_heap) = alloc_counter::measure(|| {
+            for _ in 0..ROUNDS {
+                let s = String::from(black_box(sample));
+                black_box(s.clone());
+            }
+        });
+        let (_, small_heap) = alloc_counter::measure(|| {
+            for _ in 0..ROUNDS {
+                let s = SmallString::from(black_box(sample));
+                black_box(s.clone());
+            }
+        });
+        println!(
+            "   {:<48} {:>12} {:>12}",
+            format!("{sample:?} ({} bytes)", sample.len()),
+            string_heap.allocations,
+            small_heap.allocations
+        );
+    }
+
+    // ========================================================================
+    // 2. CLONE SPEED
+    // ========================================================================
+    println!("\n2. CLONE SPEED ({ROUNDS} clones per sample):");
+    println!(
+        "   {:<48} {:>12} {:>12} {:>8}",
+        "sample", "String", "SmallString", "speedup"
+    );
+    for sample in SAMPLES {
+        let string = String::from(sample);
+        let small = SmallString::from(sample);
+        let string_time = time(|| {
+            black_box(black_box(&string).clone());
+        });
+        let small_time = time(|| {
+            black_box(black_box(&small).clone());
+        });
+        println!(
+            "   {:<48} {:>12} {:>12} {:>7.1}x",
+            format!("{sample:?}"),
+            format!("{:.1?}", string_time),
+            format!("{:.1?}", small_time),
+            string_time.as_secs_f64() / small_time.as_secs_f64()
+        );
+    }
+
+    // ========================================================================
+    // 3. THE DEMO API STILL WORKS
+    // ========================================================================
+    println!("\n3. SAME API AS THE DEMOS:");
+    let mut s = SmallString::from("hello");
+    println!(
+        "   from(\"hello\"): {s:?}, len {}, inline {}",
+        s.len(),
+        s.is_inline()
+    );
+    s.push_str(", world!");
+    println!(
+        "   push_str: {s:?}, len {}, inline {}",
+        s.len(),
+        s.is_inline()
+    );
+    s.push_str(" and then some more");
+    println!(
+        "   push_str: {s:?}, len {}, inline {}",
+        s.len(),
+        s.is_inline()
+    );
+    println!(
+        "   Deref to str: starts_with(\"hello\") = {}",
+        s.starts_with("hello")
+    );
+    s.clear();
+    println!(
+        "   clear: {s:?}, len {}, capacity {}",
+        s.len(),
+        s.capacity()
+    );
+}
+
+// Best of 5 runs of ROUNDS calls
+fn time(mut f: impl FnMut()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                f();
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
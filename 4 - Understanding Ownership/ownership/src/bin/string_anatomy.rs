@@ -0,0 +1,121 @@
+// Before/after diagrams of a String's stack handle and heap buffer for each
+// operation used in the ownership demo.
+
+use ownership::anatomy::{self, Inspect};
+
+fn main() {
+    println!("=== STRING ANATOMY ===\n");
+    println!(
+        "A String is a {}-byte handle (pointer, length, capacity) on the stack",
+        std::mem::size_of::<String>()
+    );
+    println!("pointing at a buffer on the heap.\n");
+
+    // ========================================================================
+    // 1. STRING
+    // ========================================================================
+    println!("1. STRING:");
+
+    let empty = String::new();
+    println!("   String::new()\n      {}\n", empty.anatomy());
+
+    let mut s = String::from("hello");
+    println!("   String::from(\"hello\")\n      {}\n", s.anatomy());
+
+    let before = s.anatomy();
+    s.push_str(", world!");
+    println!(
+        "{}",
+        anatomy::diagram("s.push_str(\", world!\")", &before, &s.anatomy())
+    );
+
+    let before = s.anatomy();
+    s.reserve(100);
+    println!(
+        "{}",
+        anatomy::diagram("s.reserve(100)", &before, &s.anatomy())
+    );
+
+    let before = s.anatomy();
+    s.clear();
+    println!("{}", anatomy::diagram("s.clear()", &before, &s.anatomy()));
+
+    let before = s.anatomy();
+    s.push_str("hi");
+    s.shrink_to_fit();
+    println!(
+        "{}",
+        anatomy::diagram(
+            "s.push_str(\"hi\"); s.shrink_to_fit()",
+            &before,
+            &s.anatomy()
+        )
+    );
+
+    // Clone: a second handle AND a second buffer
+    let s3 = String::from("hello");
+    let s4 = s3.clone();
+    println!(
+        "{}",
+        anatomy::diagram("let s4 = s3.clone()", &s3.anatomy(), &s4.anatomy())
+    );
+
+    // Move: a second handle pointing at the SAME buffer; s1 is unusable after
+    let s1 = String::from("hello");
+    let before = s1.anatomy();
+    let s2 = s1;
+    println!(
+        "{}",
+        anatomy::diagram("let s2 = s1 (move)", &before, &s2.anatomy())
+    );
+
+    // Reassignment: the old buffer is freed, a new one takes its place
+    let mut s_reassign = String::from("hello");
+    let before = s_reassign.anatomy();
+    s_reassign = String::from("ahoy");
+    println!(
+        "{}",
+        anatomy::diagram(
+            "s_reassign = String::from(\"ahoy\")",
+            &before,
+            &s_reassign.anatomy()
+        )
+    );
+
+    // ========================================================================
+    // 2. VEC
+    // ========================================================================
+    println!("2. VEC:");
+
+    let mut v = vec![85, 92, 78];
+    println!("   vec![85, 92, 78]\n      {}\n", v.anatomy());
+
+    let before = v.anatomy();
+    v.push(100);
+    println!("{}", anatomy::diagram("v.push(100)", &before, &v.anatomy()));
+
+    let before = v.anatomy();
+    v.clear();
+    println!("{}", anatomy::diagram("v.clear()", &before, &v.anatomy()));
+
+    let before = v.anatomy();
+    v.shrink_to_fit();
+    println!(
+        "{}",
+        anatomy::diagram("v.shrink_to_fit()", &before, &v.anatomy())
+    );
+
+    let v1 = vec![1, 2, 3];
+    let v2 = v1.clone();
+    println!(
+        "{}",
+        anatomy::diagram("let v2 = v1.clone()", &v1.anatomy(), &v2.anatomy())
+    );
+
+    let before = v2.anatomy();
+    let v3 = v2;
+    println!(
+        "{}",
+        anatomy::diagram("let v3 = v2 (move)", &before, &v3.anatomy())
+    );
+}
@@ -0,0 +1,228 @@
+// Compares TextBuffer (a rope) with String for edits in the middle of a
+// large text, after checking that both end up with the same contents.
+//
+// Run with optimizations for meaningful timings:
+//   cargo run --release --bin text_buffer_bench [FILE]
+//
+// Without FILE a ~4 MB text is generated.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ownership::lcg::Lcg;
+use ownership::text_buffer::TextBuffer;
+
+const EDITS: usize = 1_000;
+
+fn main() {
+    println!("=== TextBuffer vs String ===\n");
+
+    let text = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("cannot read {path}: {e}");
+            std::process::exit(1);
+        }),
+        None => sample_text(4 * 1024 * 1024),
+    };
+    println!(
+        "Text: {} bytes, {} chars, {} lines\n",
+        text.len(),
+        text.chars().count(),
+        text.lines().count()
+    );
+
+    // ========================================================================
+    // 1. SAME RESULT AS STRING
+    // ========================================================================
+    println!("1. CHECKING AGAINST STRING ({EDITS} random edits):");
+
+    let mut buffer = TextBuffer::from(text.as_str());
+    let mut string = text.clone();
+    let mut rng = Lcg::new(42);
+    for i in 0..EDITS {
+        let len = buffer.len_chars();
+        let at = rng.below(len + 1);
+        if i % 3 == 2 && at < len {
+            let end = (at + 1 + rng.below(20)).min(len);
+            let removed = buffer.delete(at..end);
+            let (start_byte, end_byte) = (char_to_byte(&string, at), char_to_byte(&string, end));
+            assert_eq!(removed, &string[start_byte..end_byte]);
+            string.replace_range(start_byte..end_byte, "");
+        } else {
+            let insert = ["héllo ", "wörld\n", "ownership ", "नमस्ते ", "🦀"][i % 5];
+            buffer.insert(at, insert);
+            string.insert_str(char_to_byte(&string, at), insert);
+        }
+    }
+    assert_eq!(buffer.to_string(), string);
+    println!("   Contents match after every edit was applied");
+
+    for (i, expected) in string.split_inclusive('\n').enumerate().step_by(997) {
+        assert_eq!(buffer.line(i).to_string(), expected, "line {i}");
+        assert_eq!(buffer.rope().char_to_line(buffer.rope().line_to_char(i)), i);
+    }
+    println!("   line(), line_to_char() and char_to_line() agree with split_inclusive('\\n')");
+
+    let mut undone = 0;
+    while buffer.undo() {
+        undone += 1;
+    }
+    assert_eq!(buffer.to_string(), text);
+    println!("   Undid {undone} edits: back to the original text");
+
+    let mut redone = 0;
+    while buffer.redo() {
+        redone += 1;
+    }
+    assert_eq!(buffer.to_string(), string);
+    println!("   Redid {redone} edits: back to the edited text");
+    println!("   {:?}", buffer.rope());
+
+    // ========================================================================
+    // 2. INSERT AND DELETE IN THE MIDDLE
+    // ========================================================================
+    println!("\n2. TIMINGS ({EDITS} edits at random char positions):");
+    println!(
+        "   {:<36} {:>12} {:>12}",
+        "operation", "String", "TextBuffer"
+    );
+
+    let mut string = text.clone();
+    let string_time = time(|| {
+        let mut rng = Lcg::new(7);
+        for _ in 0..EDITS {
+            let at = rng.below(string.len() / 2);
+            string.insert_str(char_to_byte(&string, at), "hello ");
+        }
+    });
+    black_box(string);
+    let mut buffer = TextBuffer::from(text.as_str());
+    let buffer_time = time(|| {
+        let mut rng = Lcg::new(7);
+        for _ in 0..EDITS {
+            let at = rng.below(buffer.len_chars() / 2);
+            buffer.insert(at, "hello ");
+        }
+    });
+    black_box(buffer);
+    row("insert at char position", string_time, Some(buffer_time));
+
+    let mut string = text.clone();
+    let string_time = time(|| {
+        let mut rng = Lcg::new(7);
+        for _ in 0..EDITS {
+            let at = rng.below(string.len() / 2);
+            let start = char_to_byte(&string, at);
+            let end = char_to_byte(&string, at + 5);
+            string.replace_range(start..end, "");
+        }
+    });
+    black_box(string);
+    let mut buffer = TextBuffer::from(text.as_str());
+    let buffer_time = time(|| {
+        let mut rng = Lcg::new(7);
+        for _ in 0..EDITS {
+            let at = rng.below(buffer.len_chars() / 2);
+            buffer.delete(at..at + 5);
+        }
+    });
+    black_box(buffer);
+    row(
+        "delete 5 chars at char position",
+        string_time,
+        Some(buffer_time),
+    );
+
+    // Byte positions are the best case for String: no char scan needed
+    let mut string = text.clone();
+    let string_time = time(|| {
+        let mut rng = Lcg::new(7);
+        for _ in 0..EDITS {
+            let mut at = rng.below(string.len());
+            while !string.is_char_boundary(at) {
+                at -= 1;
+            }
+            string.insert_str(at, "hello ");
+        }
+    });
+    black_box(string);
+    row("insert at byte position (String)", string_time, None);
+
+    let string_time = time(|| {
+        let lines = text.lines().count();
+        for i in 0..EDITS {
+            black_box(text.lines().nth(i * 7919 % lines));
+        }
+    });
+    let buffer = TextBuffer::from(text.as_str());
+    let buffer_time = time(|| {
+        let lines = buffer.len_lines();
+        for i in 0..EDITS {
+            black_box(buffer.line(i * 7919 % lines).count());
+        }
+    });
+    row("look up a line", string_time, Some(buffer_time));
+
+    // Building is timed on its own; the edit timings above start from
+    // copies made beforehand
+    let string_time = time(|| {
+        black_box(text.clone());
+    });
+    let buffer_time = time(|| {
+        black_box(TextBuffer::from(black_box(text.as_str())));
+    });
+    row("build from the text", string_time, Some(buffer_time));
+}
+
+// Byte offset of char `at`, the way a String-based editor would find it
+fn char_to_byte(string: &str, at: usize) -> usize {
+    string
+        .char_indices()
+        .nth(at)
+        .map_or(string.len(), |(i, _)| i)
+}
+
+fn row(name: &str, string_time: Duration, buffer_time: Option<Duration>) {
+    match buffer_time {
+        Some(buffer_time) => {
+            let ratio = string_time.as_secs_f64() / buffer_time.as_secs_f64();
+            let precision = if ratio < 10.0 { 1 } else { 0 };
+            println!(
+                "   {:<36} {:>12} {:>12}   ({ratio:.precision$}x)",
+                name,
+                format!("{string_time:.1?}"),
+                format!("{buffer_time:.1?}"),
+            )
+        }
+        None => println!(
+            "   {:<36} {:>12} {:>12}",
+            name,
+            format!("{string_time:.1?}"),
+            "-"
+        ),
+    }
+}
+
+// Single run: String edits on large texts are too slow to repeat
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+// Lines of mixed ASCII and multibyte text, about `bytes` long
+fn sample_text(bytes: usize) -> String {
+    let lines = [
+        "Each value in Rust has an owner.\n",
+        "There can only be one owner at a time.\n",
+        "When the owner goes out of scope, the value will be dropped.\n",
+        "Grüße, Jürgen — ça va? नमस्ते दुनिया 🦀\n",
+    ];
+    let mut text = String::with_capacity(bytes + 64);
+    let mut i = 0;
+    while text.len() < bytes {
+        text.push_str(lines[i % lines.len()]);
+        i += 1;
+    }
+    text
+}
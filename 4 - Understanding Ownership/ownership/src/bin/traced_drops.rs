@@ -0,0 +1,105 @@
+// Replays ownrship.rs and returnvaluesandscope.rs with Traced<T> values and
+// checks that the real drop order matches what their comments claim.
+
+use ownership::traced::{self, Event, Traced};
+
+fn main() {
+    println!("=== DROP ORDER, TRACED ===\n");
+
+    // ========================================================================
+    // 1. ownrship.rs
+    // ========================================================================
+    println!("1. ownrship.rs:");
+    ownership_example();
+    let events = traced::take_events();
+    print_events(&events);
+
+    // takes_ownership drops some_string when it returns; makes_copy drops
+    // its copy; at the end of main "x goes out of scope, then s. However,
+    // because s's value was moved, nothing special happens."
+    check(&events, &["some_string", "some_integer", "x"]);
+
+    // ========================================================================
+    // 2. returnvaluesandscope.rs
+    // ========================================================================
+    println!("\n2. returnvaluesandscope.rs:");
+    return_values_example();
+    let events = traced::take_events();
+    print_events(&events);
+
+    // "s3 goes out of scope and is dropped. s2 was moved, so nothing
+    // happens. s1 goes out of scope and is dropped."
+    check(&events, &["s3", "s1"]);
+
+    // ========================================================================
+    // 3. CLONE
+    // ========================================================================
+    println!("\n3. clone.rs:");
+    {
+        let s1 = Traced::new("s1", String::from("hello"));
+        let s2 = s1.clone_as("s2");
+        println!("   s1 = {s1}, s2 = {s2}");
+    }
+    let events = traced::take_events();
+    print_events(&events);
+
+    // Locals are dropped in reverse order of declaration
+    check(&events, &["s2", "s1"]);
+}
+
+fn ownership_example() {
+    let s = Traced::new("s", String::from("hello")); // s comes into scope
+
+    takes_ownership(s.move_into("takes_ownership", "some_string")); // s's value moves into the function...
+
+    let x = Traced::new("x", 5); // x comes into scope
+
+    // i32 is Copy, so the function gets its own copy and x stays usable
+    makes_copy(x.clone_as("some_integer"));
+    println!("   x is still usable: {x}");
+} // Here, x goes out of scope, then s. s's value was moved: nothing happens.
+
+fn takes_ownership(some_string: Traced<String>) {
+    println!("   {some_string}");
+} // Here, some_string goes out of scope and `drop` is called.
+
+fn makes_copy(some_integer: Traced<i32>) {
+    println!("   {some_integer}");
+} // Here, some_integer goes out of scope.
+
+fn return_values_example() {
+    let s1 = gives_ownership().return_from("gives_ownership", "s1");
+    let s2 = Traced::new("s2", String::from("hello"));
+    let s3 = takes_and_gives_back(s2.move_into("takes_and_gives_back", "a_string"))
+        .return_from("takes_and_gives_back", "s3");
+    println!("   s1 = {s1}, s3 = {s3}");
+} // Here, s3 goes out of scope and is dropped. s2 was moved, so nothing
+// happens. s1 goes out of scope and is dropped.
+
+fn gives_ownership() -> Traced<String> {
+    Traced::new("some_string", String::from("yours")) // moves out to the caller
+}
+
+fn takes_and_gives_back(a_string: Traced<String>) -> Traced<String> {
+    a_string // moves out to the calling function
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn print_events(events: &[Event]) {
+    println!("   Events:");
+    for event in events {
+        println!("   {event}");
+    }
+}
+
+fn check(events: &[Event], expected: &[&str]) {
+    let actual = traced::drop_order(events);
+    assert_eq!(
+        actual, expected,
+        "drop order doesn't match the comments in the lesson"
+    );
+    println!("   Drop order {actual:?} matches the comments");
+}
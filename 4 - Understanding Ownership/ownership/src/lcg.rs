@@ -0,0 +1,51 @@
+// A small deterministic random number generator for benchmarks and stress
+// tests, so runs are repeatable without pulling in the `rand` crate.
+//
+// It is Knuth's MMIX linear congruential generator. The low bits of an LCG
+// are poor, so only the top 31 bits are used.
+
+#[derive(Debug, Clone)]
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg(seed)
+    }
+
+    /// A number in `0..n` (0 when `n` is 0)
+    pub fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Lcg::new(42);
+        let mut b = Lcg::new(42);
+        let first: Vec<usize> = (0..100).map(|_| a.below(1000)).collect();
+        assert!(first.iter().all(|&x| x < 1000));
+        assert_eq!(first, (0..100).map(|_| b.below(1000)).collect::<Vec<_>>());
+        let mut c = Lcg::new(7);
+        assert_ne!(first, (0..100).map(|_| c.below(1000)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn covers_small_ranges() {
+        let mut rng = Lcg::new(1);
+        let mut seen = [false; 6];
+        for _ in 0..200 {
+            seen[rng.below(6)] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert_eq!(rng.below(0), 0);
+        assert_eq!(rng.below(1), 0);
+    }
+}
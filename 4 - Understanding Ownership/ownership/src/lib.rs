@@ -0,0 +1,8 @@
+pub mod alloc_counter;
+pub mod anatomy;
+pub mod lcg;
+pub mod rope;
+pub mod small_string;
+pub mod text;
+pub mod text_buffer;
+pub mod traced;
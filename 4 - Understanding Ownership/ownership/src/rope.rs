@@ -0,0 +1,675 @@
+// A rope: text stored as a tree of small `String` chunks.
+//
+// `String::insert_str` in the middle of a large text shifts every byte after
+// the insertion point. A rope only rewrites one small leaf (and the sizes on
+// the path down to it), so inserts and deletes anywhere cost O(log n).
+//
+// All positions are in chars, not bytes, so multibyte text such as "नमस्ते"
+// can never be split in the middle of a character.
+
+use std::fmt;
+use std::ops::Range;
+
+// Leaves hold at most this many bytes
+const MAX_LEAF: usize = 1024;
+// Internal nodes hold at most this many children
+const MAX_CHILDREN: usize = 8;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Summary {
+    bytes: usize,
+    chars: usize,
+    newlines: usize,
+}
+
+impl Summary {
+    fn of(text: &str) -> Summary {
+        Summary {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&b| b == b'\n').count(),
+        }
+    }
+
+    fn add(&mut self, other: Summary) {
+        self.bytes += other.bytes;
+        self.chars += other.chars;
+        self.newlines += other.newlines;
+    }
+}
+
+#[derive(Clone)]
+struct Node {
+    summary: Summary,
+    kind: Kind,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Leaf(String),
+    Internal(Vec<Node>),
+}
+
+impl Node {
+    fn leaf(text: String) -> Node {
+        Node {
+            summary: Summary::of(&text),
+            kind: Kind::Leaf(text),
+        }
+    }
+
+    fn internal(children: Vec<Node>) -> Node {
+        let mut summary = Summary::default();
+        for child in &children {
+            summary.add(child.summary);
+        }
+        Node {
+            summary,
+            kind: Kind::Internal(children),
+        }
+    }
+
+    fn recompute(&mut self) {
+        self.summary = match &self.kind {
+            Kind::Leaf(text) => Summary::of(text),
+            Kind::Internal(children) => {
+                let mut summary = Summary::default();
+                for child in children {
+                    summary.add(child.summary);
+                }
+                summary
+            }
+        };
+    }
+
+    // Inserts `text` at char `at`. Returns new siblings that must be placed
+    // right after this node when it grew too big.
+    fn insert(&mut self, at: usize, text: &str) -> Vec<Node> {
+        let extra = match &mut self.kind {
+            Kind::Leaf(leaf) => {
+                leaf.insert_str(char_to_byte(leaf, at), text);
+                if leaf.len() <= MAX_LEAF {
+                    Vec::new()
+                } else {
+                    // Even halves (or thirds, ...): a full leaf plus a few
+                    // bytes would leave a trail of tiny leaves behind
+                    let size = leaf.len().div_ceil(leaf.len().div_ceil(MAX_LEAF));
+                    let mut pieces = split_leaves(leaf, size).into_iter();
+                    *leaf = pieces.next().unwrap_or_default();
+                    pieces.map(Node::leaf).collect()
+                }
+            }
+            Kind::Internal(children) => {
+                let mut offset = 0;
+                let mut index = children.len() - 1;
+                for (i, child) in children.iter().enumerate() {
+                    if at <= offset + child.summary.chars {
+                        index = i;
+                        break;
+                    }
+                    offset += child.summary.chars;
+                }
+                let siblings = children[index].insert(at - offset, text);
+                children.splice(index + 1..index + 1, siblings);
+                if children.len() <= MAX_CHILDREN {
+                    Vec::new()
+                } else {
+                    // Keep half, so neither side starts out nearly empty
+                    let rest = children.split_off(MAX_CHILDREN / 2);
+                    group(rest)
+                }
+            }
+        };
+        self.recompute();
+        extra
+    }
+
+    // Removes chars `range` (relative to this node)
+    fn remove(&mut self, range: Range<usize>) {
+        match &mut self.kind {
+            Kind::Leaf(leaf) => {
+                let start = char_to_byte(leaf, range.start);
+                let end = start + char_to_byte(&leaf[start..], range.end - range.start);
+                leaf.replace_range(start..end, "");
+            }
+            Kind::Internal(children) => {
+                let mut offset = 0;
+                for child in children.iter_mut() {
+                    let chars = child.summary.chars;
+                    let start = range.start.max(offset);
+                    let end = range.end.min(offset + chars);
+                    if start < end {
+                        child.remove(start - offset..end - offset);
+                    }
+                    offset += chars;
+                }
+                children.retain(|c| c.summary.bytes > 0);
+                merge_small_leaves(children);
+            }
+        }
+        self.recompute();
+    }
+
+    fn char_to_byte(&self, at: usize) -> usize {
+        match &self.kind {
+            Kind::Leaf(leaf) => char_to_byte(leaf, at),
+            Kind::Internal(children) => {
+                let (mut chars, mut bytes) = (0, 0);
+                for child in children {
+                    if at <= chars + child.summary.chars {
+                        return bytes + child.char_to_byte(at - chars);
+                    }
+                    chars += child.summary.chars;
+                    bytes += child.summary.bytes;
+                }
+                bytes
+            }
+        }
+    }
+
+    // Number of '\n' before char `at`
+    fn newlines_before(&self, at: usize) -> usize {
+        match &self.kind {
+            Kind::Leaf(leaf) => leaf.chars().take(at).filter(|&c| c == '\n').count(),
+            Kind::Internal(children) => {
+                let (mut chars, mut newlines) = (0, 0);
+                for child in children {
+                    if at <= chars + child.summary.chars {
+                        return newlines + child.newlines_before(at - chars);
+                    }
+                    chars += child.summary.chars;
+                    newlines += child.summary.newlines;
+                }
+                newlines
+            }
+        }
+    }
+
+    // Char index just after the `n`th '\n' (1-based)
+    fn after_newline(&self, n: usize) -> usize {
+        match &self.kind {
+            Kind::Leaf(leaf) => {
+                let mut seen = 0;
+                for (i, c) in leaf.chars().enumerate() {
+                    if c == '\n' {
+                        seen += 1;
+                        if seen == n {
+                            return i + 1;
+                        }
+                    }
+                }
+                self.summary.chars
+            }
+            Kind::Internal(children) => {
+                let mut remaining = n;
+                let mut chars = 0;
+                for child in children {
+                    if remaining <= child.summary.newlines {
+                        return chars + child.after_newline(remaining);
+                    }
+                    remaining -= child.summary.newlines;
+                    chars += child.summary.chars;
+                }
+                chars
+            }
+        }
+    }
+
+    fn collect_chunks<'a>(&'a self, range: Range<usize>, out: &mut Vec<&'a str>) {
+        match &self.kind {
+            Kind::Leaf(leaf) => {
+                let start = char_to_byte(leaf, range.start);
+                let end = start + char_to_byte(&leaf[start..], range.end - range.start);
+                if start < end {
+                    out.push(&leaf[start..end]);
+                }
+            }
+            Kind::Internal(children) => {
+                let mut offset = 0;
+                for child in children {
+                    let chars = child.summary.chars;
+                    let start = range.start.max(offset);
+                    let end = range.end.min(offset + chars);
+                    if start < end {
+                        child.collect_chunks(start - offset..end - offset, out);
+                    }
+                    offset += chars;
+                    if offset >= range.end {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match &self.kind {
+            Kind::Leaf(_) => 1,
+            Kind::Internal(children) => 1 + children.iter().map(Node::depth).max().unwrap_or(0),
+        }
+    }
+}
+
+// Byte offset of char `at` in `text` (or text.len() past the end)
+fn char_to_byte(text: &str, at: usize) -> usize {
+    text.char_indices().nth(at).map_or(text.len(), |(i, _)| i)
+}
+
+// Cuts text into pieces of at most `size` bytes on char boundaries
+fn split_leaves(text: &str, size: usize) -> Vec<String> {
+    let mut pieces = Vec::with_capacity(text.len() / size + 1);
+    let mut rest = text;
+    while rest.len() > size {
+        let mut cut = size;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        pieces.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+    pieces.push(rest.to_string());
+    pieces
+}
+
+// Packs nodes into parents of at most MAX_CHILDREN, one level up
+fn group(mut nodes: Vec<Node>) -> Vec<Node> {
+    let mut parents = Vec::with_capacity(nodes.len() / MAX_CHILDREN + 1);
+    while nodes.len() > MAX_CHILDREN {
+        let rest = nodes.split_off(MAX_CHILDREN);
+        parents.push(Node::internal(nodes));
+        nodes = rest;
+    }
+    if !nodes.is_empty() {
+        parents.push(Node::internal(nodes));
+    }
+    parents
+}
+
+// After deletes, neighbouring leaves can end up tiny; join them back up
+fn merge_small_leaves(children: &mut Vec<Node>) {
+    let mut i = 0;
+    while i + 1 < children.len() {
+        let fits = children[i].summary.bytes + children[i + 1].summary.bytes <= MAX_LEAF;
+        if let (true, Kind::Leaf(_), Kind::Leaf(_)) =
+            (fits, &children[i].kind, &children[i + 1].kind)
+        {
+            let next = children.remove(i + 1);
+            if let (Kind::Leaf(left), Kind::Leaf(right)) = (&mut children[i].kind, next.kind) {
+                left.push_str(&right);
+            }
+            children[i].recompute();
+        } else {
+            i += 1;
+        }
+    }
+}
+
+// ============================================================================
+// Rope
+// ============================================================================
+
+#[derive(Clone)]
+pub struct Rope {
+    root: Node,
+}
+
+impl Rope {
+    pub fn new() -> Rope {
+        Rope {
+            root: Node::leaf(String::new()),
+        }
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.root.summary.bytes
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.root.summary.chars
+    }
+
+    /// Number of lines; a trailing '\n' starts a final empty line
+    pub fn len_lines(&self) -> usize {
+        self.root.summary.newlines + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// Height of the tree (1 for a single leaf)
+    pub fn depth(&self) -> usize {
+        self.root.depth()
+    }
+
+    /// Inserts `text` before char `at`. Panics if `at > len_chars()`.
+    pub fn insert(&mut self, at: usize, text: &str) {
+        assert!(
+            at <= self.len_chars(),
+            "insert position {at} out of bounds (len {})",
+            self.len_chars()
+        );
+        if text.is_empty() {
+            return;
+        }
+        let siblings = self.root.insert(at, text);
+        if !siblings.is_empty() {
+            let old_root = std::mem::replace(&mut self.root, Node::leaf(String::new()));
+            let mut level = vec![old_root];
+            level.extend(siblings);
+            while level.len() > 1 {
+                level = group(level);
+            }
+            self.root = level.pop().unwrap();
+        }
+    }
+
+    /// Removes the chars in `range`. Panics if the range is out of bounds.
+    pub fn remove(&mut self, range: Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len_chars(),
+            "remove range {range:?} out of bounds (len {})",
+            self.len_chars()
+        );
+        if range.is_empty() {
+            return;
+        }
+        self.root.remove(range);
+        // Drop levels that only have one child
+        loop {
+            match &mut self.root.kind {
+                Kind::Internal(children) if children.len() == 1 => {
+                    self.root = children.pop().unwrap();
+                }
+                Kind::Internal(children) if children.is_empty() => {
+                    self.root = Node::leaf(String::new());
+                }
+                _ => break,
+            }
+        }
+    }
+
+    pub fn char_to_byte(&self, at: usize) -> usize {
+        assert!(at <= self.len_chars(), "char index {at} out of bounds");
+        self.root.char_to_byte(at)
+    }
+
+    /// Line (0-based) that contains char `at`
+    pub fn char_to_line(&self, at: usize) -> usize {
+        assert!(at <= self.len_chars(), "char index {at} out of bounds");
+        self.root.newlines_before(at)
+    }
+
+    /// Char index where `line` (0-based) starts
+    pub fn line_to_char(&self, line: usize) -> usize {
+        assert!(line < self.len_lines(), "line {line} out of bounds");
+        if line == 0 {
+            0
+        } else {
+            self.root.after_newline(line)
+        }
+    }
+
+    /// The chars of `line`, including its trailing '\n', as chunks
+    pub fn line(&self, line: usize) -> Chunks<'_> {
+        let start = self.line_to_char(line);
+        let end = if line + 1 < self.len_lines() {
+            self.line_to_char(line + 1)
+        } else {
+            self.len_chars()
+        };
+        self.slice(start..end)
+    }
+
+    /// The chars in `range` as borrowed `&str` chunks, without copying
+    pub fn slice(&self, range: Range<usize>) -> Chunks<'_> {
+        assert!(
+            range.start <= range.end && range.end <= self.len_chars(),
+            "slice range {range:?} out of bounds (len {})",
+            self.len_chars()
+        );
+        let mut chunks = Vec::new();
+        self.root.collect_chunks(range, &mut chunks);
+        Chunks {
+            inner: chunks.into_iter(),
+        }
+    }
+
+    /// All the text as chunks
+    pub fn chunks(&self) -> Chunks<'_> {
+        self.slice(0..self.len_chars())
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Rope {
+        Rope::new()
+    }
+}
+
+impl From<&str> for Rope {
+    /// Builds a balanced tree bottom-up
+    fn from(text: &str) -> Rope {
+        let mut level: Vec<Node> = split_leaves(text, MAX_LEAF)
+            .into_iter()
+            .map(Node::leaf)
+            .collect();
+        while level.len() > 1 {
+            level = group(level);
+        }
+        Rope {
+            root: level.pop().unwrap_or_else(|| Node::leaf(String::new())),
+        }
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rope")
+            .field("chars", &self.len_chars())
+            .field("lines", &self.len_lines())
+            .field("depth", &self.depth())
+            .finish()
+    }
+}
+
+/// Borrowed pieces of a rope, in order
+pub struct Chunks<'a> {
+    inner: std::vec::IntoIter<&'a str>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next()
+    }
+}
+
+impl fmt::Display for Chunks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.inner.as_slice() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Checks every node's summary and size limits, and that all leaves are
+    // at the same depth. Returns the depth.
+    fn check(node: &Node, is_root: bool) -> usize {
+        match &node.kind {
+            Kind::Leaf(text) => {
+                assert_eq!(node.summary, Summary::of(text));
+                assert!(text.len() <= MAX_LEAF, "leaf of {} bytes", text.len());
+                assert!(is_root || !text.is_empty(), "empty leaf");
+                1
+            }
+            Kind::Internal(children) => {
+                assert!(!children.is_empty() && children.len() <= MAX_CHILDREN);
+                assert!(!is_root || children.len() > 1, "root with one child");
+                let mut summary = Summary::default();
+                let depths: Vec<usize> = children
+                    .iter()
+                    .map(|child| {
+                        summary.add(child.summary);
+                        check(child, false)
+                    })
+                    .collect();
+                assert_eq!(node.summary, summary);
+                assert!(depths.iter().all(|&d| d == depths[0]), "unbalanced");
+                depths[0] + 1
+            }
+        }
+    }
+
+    fn assert_rope(rope: &Rope, expected: &str) {
+        check(&rope.root, true);
+        assert_eq!(rope.to_string(), expected);
+        assert_eq!(rope.len_bytes(), expected.len());
+        assert_eq!(rope.len_chars(), expected.chars().count());
+        assert_eq!(rope.len_lines(), expected.split('\n').count());
+    }
+
+    fn byte(text: &str, at: usize) -> usize {
+        char_to_byte(text, at)
+    }
+
+    #[test]
+    fn empty_rope() {
+        let rope = Rope::new();
+        assert_rope(&rope, "");
+        assert!(rope.is_empty());
+        assert_eq!(rope.depth(), 1);
+        assert_eq!(rope.line(0).count(), 0);
+        assert_rope(&Rope::from(""), "");
+    }
+
+    #[test]
+    fn from_builds_a_balanced_tree() {
+        let text = "x".repeat(MAX_LEAF * MAX_CHILDREN * 3 + 5);
+        let rope = Rope::from(text.as_str());
+        assert_rope(&rope, &text);
+        assert_eq!(rope.depth(), 3);
+    }
+
+    #[test]
+    fn leaves_split_on_char_boundaries() {
+        // 3-byte chars don't line up with MAX_LEAF, 4-byte ones are offset by
+        // the leading ASCII byte
+        for text in ["€".repeat(2000), format!("a{}", "🦀".repeat(1000))] {
+            let rope = Rope::from(text.as_str());
+            assert_rope(&rope, &text);
+            assert!(rope.chunks().count() > 1);
+            for at in [0, 1, 340, 341, 342, 1000, rope.len_chars()] {
+                assert_eq!(rope.char_to_byte(at), byte(&text, at));
+            }
+
+            let mut rope = rope;
+            let mut text = text;
+            let at = 341;
+            rope.insert(at, "ü");
+            text.insert(byte(&text, at), 'ü');
+            assert_rope(&rope, &text);
+            assert_eq!(
+                rope.slice(340..343).to_string(),
+                text.chars().skip(340).take(3).collect::<String>()
+            );
+        }
+    }
+
+    #[test]
+    fn inserts_grow_the_tree_and_keep_it_balanced() {
+        let mut rope = Rope::new();
+        let mut text = String::new();
+        let pieces = ["héllo ", "wörld\n", "नमस्ते ", "🦀", "ownership "];
+        for i in 0..4_000 {
+            let at = i * 7919 % (rope.len_chars() + 1);
+            let piece = pieces[i % pieces.len()];
+            rope.insert(at, piece);
+            text.insert_str(byte(&text, at), piece);
+            if i % 500 == 0 {
+                assert_rope(&rope, &text);
+            }
+        }
+        assert_rope(&rope, &text);
+        // ~30 KB in leaves at least half full, at least 4 children per node
+        assert!(rope.depth() <= 4, "depth {}", rope.depth());
+    }
+
+    #[test]
+    fn removes_merge_leaves_and_shrink_the_tree() {
+        let mut text = "Grüße, नमस्ते 🦀\n".repeat(2_000);
+        let mut rope = Rope::from(text.as_str());
+        let depth = rope.depth();
+        let mut i = 0;
+        while !text.is_empty() {
+            let len = text.chars().count();
+            let start = i * 7919 % len;
+            let end = (start + 1 + i * 31 % 700).min(len);
+            rope.remove(start..end);
+            text.replace_range(byte(&text, start)..byte(&text, end), "");
+            if i % 50 == 0 {
+                assert_rope(&rope, &text);
+            }
+            i += 1;
+        }
+        assert_rope(&rope, "");
+        assert_eq!(rope.depth(), 1);
+        assert!(depth > 1);
+    }
+
+    #[test]
+    fn remove_across_many_leaves() {
+        let text = "abcdefghij".repeat(1_000);
+        let mut rope = Rope::from(text.as_str());
+        rope.remove(5..9_995);
+        assert_rope(&rope, "abcdefghij");
+        rope.remove(0..10);
+        assert_rope(&rope, "");
+    }
+
+    #[test]
+    fn lines() {
+        let text = "one\ntwö\n\nfour\n".repeat(300);
+        let rope = Rope::from(text.as_str());
+        assert_rope(&rope, &text);
+        let lines: Vec<&str> = text.split_inclusive('\n').collect();
+        assert_eq!(rope.len_lines(), lines.len() + 1);
+        let mut start = 0;
+        for (i, line) in lines.iter().enumerate() {
+            assert_eq!(rope.line_to_char(i), start);
+            assert_eq!(rope.char_to_line(start), i);
+            assert_eq!(rope.line(i).to_string(), *line);
+            start += line.chars().count();
+        }
+        // The empty line after the final '\n'
+        assert_eq!(rope.line_to_char(lines.len()), rope.len_chars());
+        assert_eq!(rope.line(lines.len()).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn insert_past_the_end_panics() {
+        Rope::from("abc").insert(4, "x");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn remove_past_the_end_panics() {
+        Rope::from("abc").remove(1..4);
+    }
+}
@@ -0,0 +1,332 @@
+// `SmallString`: a string that keeps short contents inline.
+//
+// Most strings in the lessons are short ("hello", "yours", usernames). A
+// `String` always puts its bytes on the heap; a `SmallString` stores up to
+// `INLINE_CAPACITY` bytes right inside the value and only spills to a heap
+// `String` when it grows past that. Creating or cloning a short one never
+// allocates.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+/// Longest string (in bytes) stored without a heap allocation
+pub const INLINE_CAPACITY: usize = 23;
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, buf: [u8; INLINE_CAPACITY] },
+    Heap(String),
+}
+
+#[derive(Clone)]
+pub struct SmallString {
+    repr: Repr,
+}
+
+impl SmallString {
+    pub const fn new() -> SmallString {
+        SmallString {
+            repr: Repr::Inline {
+                len: 0,
+                buf: [0; INLINE_CAPACITY],
+            },
+        }
+    }
+
+    /// True while the contents live inside the value, not on the heap
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline { .. })
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline { len, .. } => *len as usize,
+            Repr::Heap(s) => s.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.repr {
+            Repr::Inline { .. } => INLINE_CAPACITY,
+            Repr::Heap(s) => s.capacity(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            Repr::Inline { len, buf } => {
+                let bytes = &buf[..*len as usize];
+                debug_assert!(std::str::from_utf8(bytes).is_ok());
+                // SAFETY: `buf[..len]` is only ever written from whole `&str`s
+                // (see `push_str`), so it is always valid UTF-8.
+                unsafe { std::str::from_utf8_unchecked(bytes) }
+            }
+            Repr::Heap(s) => s.as_str(),
+        }
+    }
+
+    pub fn push_str(&mut self, string: &str) {
+        match &mut self.repr {
+            Repr::Inline { len, buf } => {
+                let old_len = *len as usize;
+                let new_len = old_len + string.len();
+                if new_len <= INLINE_CAPACITY {
+                    buf[old_len..new_len].copy_from_slice(string.as_bytes());
+                    *len = new_len as u8;
+                } else {
+                    // Spill: move what we have plus the new text to the heap
+                    let mut heap = String::with_capacity(new_len);
+                    heap.push_str(self.as_str());
+                    heap.push_str(string);
+                    self.repr = Repr::Heap(heap);
+                }
+            }
+            Repr::Heap(s) => s.push_str(string),
+        }
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Empties the string. Like `String::clear`, a heap buffer is kept for
+    /// reuse rather than freed.
+    pub fn clear(&mut self) {
+        match &mut self.repr {
+            Repr::Inline { len, .. } => *len = 0,
+            Repr::Heap(s) => s.clear(),
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        match self.repr {
+            Repr::Inline { .. } => self.as_str().to_string(),
+            Repr::Heap(s) => s,
+        }
+    }
+}
+
+impl Default for SmallString {
+    fn default() -> SmallString {
+        SmallString::new()
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(string: &str) -> SmallString {
+        let mut small = SmallString::new();
+        small.push_str(string);
+        small
+    }
+}
+
+impl From<String> for SmallString {
+    /// Short strings are copied inline (freeing the String's buffer); long
+    /// ones keep the String's buffer without copying.
+    fn from(string: String) -> SmallString {
+        if string.len() <= INLINE_CAPACITY {
+            SmallString::from(string.as_str())
+        } else {
+            SmallString {
+                repr: Repr::Heap(string),
+            }
+        }
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SmallString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Debug for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other: &SmallString) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialEq<str> for SmallString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SmallString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SmallString {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other: &SmallString) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other: &SmallString) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+// Must hash like `str` so that `Borrow<str>` lookups in maps work
+impl Hash for SmallString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn spills_one_byte_past_the_inline_capacity() {
+        let full = "x".repeat(INLINE_CAPACITY);
+        let mut s = SmallString::from(full.as_str());
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_CAPACITY);
+        assert_eq!(s.capacity(), INLINE_CAPACITY);
+
+        s.push('y');
+        assert!(!s.is_inline());
+        assert_eq!(s, format!("{full}y"));
+        assert!(s.capacity() > INLINE_CAPACITY);
+
+        // A heap buffer stays on the heap after clear, like String's
+        s.clear();
+        assert!(s.is_empty());
+        assert!(!s.is_inline());
+    }
+
+    #[test]
+    fn multi_byte_chars_at_the_boundary() {
+        // 21 bytes + a 2-byte char fills the buffer exactly
+        let mut s = SmallString::from("a".repeat(INLINE_CAPACITY - 2).as_str());
+        s.push('é');
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_CAPACITY);
+        assert!(s.ends_with('é'));
+
+        // 22 bytes + a 4-byte char doesn't fit, so nothing is split
+        let mut s = SmallString::from("a".repeat(INLINE_CAPACITY - 1).as_str());
+        s.push('🦀');
+        assert!(!s.is_inline());
+        assert_eq!(s.len(), INLINE_CAPACITY + 3);
+        assert_eq!(s.chars().last(), Some('🦀'));
+
+        // Built from chars alone: every prefix is valid UTF-8
+        let mut s = SmallString::new();
+        let mut expected = String::new();
+        for c in "日本語のテキスト".chars() {
+            s.push(c);
+            expected.push(c);
+            assert_eq!(s, expected);
+            assert_eq!(s.is_inline(), expected.len() <= INLINE_CAPACITY);
+        }
+    }
+
+    #[test]
+    fn push_str_matches_string() {
+        let pieces = ["", "hello", ", ", "wörld", "", "!", " and some more text"];
+        let mut s = SmallString::new();
+        let mut expected = String::new();
+        for piece in pieces {
+            s.push_str(piece);
+            expected.push_str(piece);
+            assert_eq!(s.as_str(), expected);
+            assert_eq!(s.len(), expected.len());
+        }
+        assert_eq!(s.into_string(), expected);
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let short = SmallString::from("hello");
+        let mut copy = short.clone();
+        copy.push_str(", world");
+        assert_eq!(short, "hello");
+        assert_eq!(copy, "hello, world");
+        assert!(copy.is_inline());
+
+        let long = SmallString::from("a string that is too long to be inline");
+        let mut copy = long.clone();
+        assert!(!copy.is_inline());
+        copy.clear();
+        assert_eq!(long.len(), 38);
+        assert!(copy.is_empty());
+    }
+
+    #[test]
+    fn from_string_keeps_long_buffers() {
+        let long = String::from("a string that is too long to be inline");
+        let ptr = long.as_ptr();
+        let small = SmallString::from(long);
+        assert!(!small.is_inline());
+        assert_eq!(small.as_ptr(), ptr);
+
+        let small = SmallString::from(String::from("short"));
+        assert!(small.is_inline());
+        assert_eq!(small.into_string(), "short");
+    }
+
+    #[test]
+    fn hashes_and_orders_like_str() {
+        let mut map = HashMap::new();
+        map.insert(SmallString::from("alice"), 1);
+        map.insert(SmallString::from("b".repeat(30)), 2);
+        assert_eq!(map.get("alice"), Some(&1));
+        assert_eq!(map.get("b".repeat(30).as_str()), Some(&2));
+
+        let mut names = [
+            SmallString::from("b"),
+            SmallString::from("a"),
+            SmallString::new(),
+        ];
+        names.sort();
+        assert_eq!(names, [SmallString::new(), "a".into(), "b".into()]);
+        assert_eq!(format!("{:?} {}", names[1], names[2]), "\"a\" b");
+    }
+}
@@ -0,0 +1,245 @@
+// Copy-on-write text normalization.
+//
+// `takes_and_gives_back(a_string: String) -> String` moves a String in and
+// out just to look at it. These functions borrow instead: they take `&str`
+// and return `Cow<str>`, which is `Cow::Borrowed` (no allocation) when the
+// text is already normalized and `Cow::Owned` only when something had to
+// change.
+
+use std::borrow::Cow;
+
+/// Removes leading and trailing whitespace. Never allocates: the result is
+/// always a slice of the input.
+pub fn trim(text: &str) -> Cow<'_, str> {
+    Cow::Borrowed(text.trim())
+}
+
+/// Lowercases the text with `char::to_lowercase`. This is not full case
+/// folding: "straße" stays as it is rather than matching "STRASSE".
+pub fn lowercase(text: &str) -> Cow<'_, str> {
+    let unchanged = |c: char| {
+        let mut lower = c.to_lowercase();
+        lower.next() == Some(c) && lower.next().is_none()
+    };
+    match text.find(|c: char| !unchanged(c)) {
+        None => Cow::Borrowed(text),
+        Some(first) => {
+            let mut lower = String::with_capacity(text.len());
+            lower.push_str(&text[..first]);
+            lower.extend(text[first..].chars().flat_map(char::to_lowercase));
+            Cow::Owned(lower)
+        }
+    }
+}
+
+/// Trims the text and turns every interior run of whitespace (spaces, tabs,
+/// newlines, any Unicode whitespace) into a single space
+pub fn collapse_whitespace(text: &str) -> Cow<'_, str> {
+    let trimmed = text.trim();
+
+    // Already clean if the only whitespace is single ' ' characters
+    let mut previous_space = false;
+    let clean = trimmed.chars().all(|c| {
+        let ok = !c.is_whitespace() || (c == ' ' && !previous_space);
+        previous_space = c.is_whitespace();
+        ok
+    });
+    if clean {
+        return Cow::Borrowed(trimmed);
+    }
+
+    let mut collapsed = String::with_capacity(trimmed.len());
+    for (i, word) in trimmed.split_whitespace().enumerate() {
+        if i > 0 {
+            collapsed.push(' ');
+        }
+        collapsed.push_str(word);
+    }
+    Cow::Owned(collapsed)
+}
+
+/// Escapes `& < > " '` for use in HTML text or attribute values
+pub fn escape_html(text: &str) -> Cow<'_, str> {
+    escape(text, |c| match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    })
+}
+
+/// Escapes the text for the inside of a JSON string literal
+pub fn escape_json(text: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char| c == '"' || c == '\\' || c.is_control();
+    if !text.contains(needs_escape) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+// Replaces every char for which `replacement` returns Some
+fn escape(text: &str, replacement: impl Fn(char) -> Option<&'static str>) -> Cow<'_, str> {
+    let Some(first) = text.find(|c: char| replacement(c).is_some()) else {
+        return Cow::Borrowed(text);
+    };
+    let mut escaped = String::with_capacity(text.len() + 16);
+    escaped.push_str(&text[..first]);
+    for c in text[first..].chars() {
+        match replacement(c) {
+            Some(entity) => escaped.push_str(entity),
+            None => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Trims, collapses whitespace and lowercases. Stays borrowed unless one of
+/// the steps changes the text.
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    let collapsed = collapse_whitespace(text);
+    and_then(collapsed, lowercase)
+}
+
+/// Applies another step to a `Cow`, keeping it borrowed while every step
+/// returns borrowed text
+pub fn and_then<'a>(text: Cow<'a, str>, step: impl FnOnce(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match text {
+        Cow::Borrowed(borrowed) => step(borrowed),
+        Cow::Owned(owned) => match step(&owned) {
+            // The step kept the whole string: reuse the existing allocation.
+            // Same length isn't enough, the step may have borrowed other text.
+            Cow::Borrowed(result)
+                if result.as_ptr() == owned.as_ptr() && result.len() == owned.len() =>
+            {
+                Cow::Owned(owned)
+            }
+            result => Cow::Owned(result.into_owned()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_borrowed(text: Cow<'_, str>) -> bool {
+        matches!(text, Cow::Borrowed(_))
+    }
+
+    #[test]
+    fn trim_always_borrows() {
+        assert!(matches!(trim("clean"), Cow::Borrowed("clean")));
+        assert!(matches!(trim("  padded\n"), Cow::Borrowed("padded")));
+        assert!(matches!(trim(""), Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn lowercase() {
+        assert!(matches!(
+            super::lowercase("already lower"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(super::lowercase("ümlaut 123"), Cow::Borrowed(_)));
+        assert!(matches!(super::lowercase("ABC"), Cow::Owned(ref s) if s == "abc"));
+        assert_eq!(super::lowercase("ÄÖÜ straße"), "äöü straße");
+        assert_ne!(super::lowercase("STRASSE"), super::lowercase("straße"));
+        // 'İ' lowercases to two chars
+        assert_eq!(super::lowercase("İ"), "i\u{307}");
+    }
+
+    #[test]
+    fn collapse_whitespace() {
+        assert!(is_borrowed(super::collapse_whitespace("two words")));
+        assert!(matches!(
+            super::collapse_whitespace("  two words  "),
+            Cow::Borrowed("two words")
+        ));
+        assert!(matches!(
+            super::collapse_whitespace(" a\t b\n\nc  "),
+            Cow::Owned(ref s) if s == "a b c"
+        ));
+        // A single non-space whitespace char still has to become ' '
+        assert_eq!(super::collapse_whitespace("a\u{3000}b"), "a b");
+        assert!(!is_borrowed(super::collapse_whitespace("a\tb")));
+    }
+
+    #[test]
+    fn escape_html() {
+        assert!(is_borrowed(super::escape_html("plain text")));
+        assert_eq!(
+            super::escape_html("<a href='x'>&\"</a>"),
+            "&lt;a href=&#39;x&#39;&gt;&amp;&quot;&lt;/a&gt;"
+        );
+        assert!(!is_borrowed(super::escape_html("&")));
+    }
+
+    #[test]
+    fn escape_json() {
+        assert!(is_borrowed(super::escape_json("no escapes, ünïcode ok")));
+        assert_eq!(
+            super::escape_json("say \"hi\"\\\n\t\u{1}"),
+            "say \\\"hi\\\"\\\\\\n\\t\\u0001"
+        );
+    }
+
+    #[test]
+    fn normalize() {
+        assert!(is_borrowed(super::normalize("hello world")));
+        assert!(matches!(
+            super::normalize("  hello world "),
+            Cow::Borrowed("hello world")
+        ));
+        assert_eq!(super::normalize("  Hello \n  WORLD "), "hello world");
+        assert!(!is_borrowed(super::normalize("Hello")));
+    }
+
+    #[test]
+    fn and_then_stays_borrowed_while_steps_borrow() {
+        let text = and_then(Cow::Borrowed("  clean  "), trim);
+        assert!(matches!(text, Cow::Borrowed("clean")));
+        let text = and_then(text, super::lowercase);
+        assert!(matches!(text, Cow::Borrowed("clean")));
+    }
+
+    #[test]
+    fn and_then_reuses_the_owned_string_when_a_step_keeps_it() {
+        let owned = super::lowercase("ABC");
+        let ptr = owned.as_ptr();
+        let text = and_then(owned, super::escape_html);
+        assert!(matches!(text, Cow::Owned(ref s) if s == "abc"));
+        assert_eq!(text.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn and_then_keeps_changes_of_the_same_length() {
+        // Owned input, owned result of the same length
+        let text = and_then(Cow::Owned(String::from("ABC")), super::lowercase);
+        assert_eq!(text, "abc");
+
+        // Owned input, borrowed result of the same length that isn't the input
+        let text = and_then(Cow::Owned(String::from("abc")), |_| Cow::Borrowed("xyz"));
+        assert!(matches!(text, Cow::Owned(ref s) if s == "xyz"));
+
+        // A shorter slice of the owned input
+        let text = and_then(Cow::Owned(String::from(" abc ")), trim);
+        assert!(matches!(text, Cow::Owned(ref s) if s == "abc"));
+
+        // Borrowed input, changed by the step
+        let text = and_then(Cow::Borrowed("ABC"), super::lowercase);
+        assert!(matches!(text, Cow::Owned(ref s) if s == "abc"));
+    }
+}
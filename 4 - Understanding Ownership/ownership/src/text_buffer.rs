@@ -0,0 +1,257 @@
+// An editable text buffer with undo and redo, built on `Rope`.
+//
+// Every edit is recorded as an `Edit` that owns the text it inserted or
+// removed, so it can be reversed later. Undoing moves the edit to the redo
+// stack; making a new edit clears the redo stack, like in any editor.
+
+use std::ops::Range;
+
+use crate::rope::{Chunks, Rope};
+
+/// One recorded change, in char positions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Insert { at: usize, text: String },
+    Delete { at: usize, text: String },
+}
+
+impl Edit {
+    /// The edit that undoes this one
+    fn inverse(&self) -> Edit {
+        match self {
+            Edit::Insert { at, text } => Edit::Delete {
+                at: *at,
+                text: text.clone(),
+            },
+            Edit::Delete { at, text } => Edit::Insert {
+                at: *at,
+                text: text.clone(),
+            },
+        }
+    }
+
+    fn apply(&self, rope: &mut Rope) {
+        match self {
+            Edit::Insert { at, text } => rope.insert(*at, text),
+            Edit::Delete { at, text } => rope.remove(*at..*at + text.chars().count()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    rope: Rope,
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl TextBuffer {
+    pub fn new() -> TextBuffer {
+        TextBuffer::default()
+    }
+
+    /// The current text, for the read-only `Rope` API
+    pub fn rope(&self) -> &Rope {
+        &self.rope
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.rope.len_chars()
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    pub fn line(&self, line: usize) -> Chunks<'_> {
+        self.rope.line(line)
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> Chunks<'_> {
+        self.rope.slice(range)
+    }
+
+    /// Inserts `text` before char `at`
+    pub fn insert(&mut self, at: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.rope.insert(at, text);
+        self.record(Edit::Insert {
+            at,
+            text: text.to_string(),
+        });
+    }
+
+    /// Deletes the chars in `range` and returns them
+    pub fn delete(&mut self, range: Range<usize>) -> String {
+        let removed = self.rope.slice(range.clone()).to_string();
+        if !removed.is_empty() {
+            self.rope.remove(range.clone());
+            self.record(Edit::Delete {
+                at: range.start,
+                text: removed.clone(),
+            });
+        }
+        removed
+    }
+
+    /// Replaces the chars in `range` with `text` (two undo steps)
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        let at = range.start;
+        self.delete(range);
+        self.insert(at, text);
+    }
+
+    /// Reverts the last edit. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+        edit.inverse().apply(&mut self.rope);
+        self.redo.push(edit);
+        true
+    }
+
+    /// Re-applies the last undone edit. Returns false if there was nothing
+    /// to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+        edit.apply(&mut self.rope);
+        self.undo.push(edit);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Edits that `undo` would revert, oldest first
+    pub fn history(&self) -> &[Edit] {
+        &self.undo
+    }
+
+    fn record(&mut self, edit: Edit) {
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+}
+
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> TextBuffer {
+        TextBuffer {
+            rope: Rope::from(text),
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for TextBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.rope, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_walk_the_history() {
+        let mut buffer = TextBuffer::from("hello world");
+        buffer.insert(5, ",");
+        buffer.delete(7..12);
+        buffer.insert(7, "wörld 🦀");
+        let states = ["hello world", "hello, world", "hello, ", "hello, wörld 🦀"];
+        assert_eq!(buffer.to_string(), states[3]);
+        assert_eq!(buffer.history().len(), 3);
+
+        for expected in states[..3].iter().rev() {
+            assert!(buffer.undo());
+            assert_eq!(buffer.to_string(), *expected);
+        }
+        assert!(!buffer.undo());
+        assert!(!buffer.can_undo());
+
+        for expected in &states[1..] {
+            assert!(buffer.redo());
+            assert_eq!(buffer.to_string(), *expected);
+        }
+        assert!(!buffer.redo());
+        assert!(!buffer.can_redo());
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let mut buffer = TextBuffer::new();
+        buffer.insert(0, "abc");
+        buffer.undo();
+        assert!(buffer.can_redo());
+        buffer.insert(0, "x");
+        assert!(!buffer.can_redo());
+        assert_eq!(buffer.to_string(), "x");
+        assert_eq!(
+            buffer.history(),
+            [Edit::Insert {
+                at: 0,
+                text: String::from("x")
+            }]
+        );
+    }
+
+    #[test]
+    fn delete_returns_the_text_in_char_positions() {
+        let mut buffer = TextBuffer::from("नमस्ते दुनिया");
+        assert_eq!(buffer.delete(0..7), "नमस्ते ");
+        assert_eq!(buffer.to_string(), "दुनिया");
+        buffer.undo();
+        assert_eq!(buffer.to_string(), "नमस्ते दुनिया");
+    }
+
+    #[test]
+    fn empty_edits_are_not_recorded() {
+        let mut buffer = TextBuffer::from("abc");
+        buffer.insert(1, "");
+        assert_eq!(buffer.delete(2..2), "");
+        assert!(!buffer.can_undo());
+    }
+
+    #[test]
+    fn replace_is_two_undo_steps() {
+        let mut buffer = TextBuffer::from("one two three");
+        buffer.replace(4..7, "2");
+        assert_eq!(buffer.to_string(), "one 2 three");
+        buffer.undo();
+        assert_eq!(buffer.to_string(), "one  three");
+        buffer.undo();
+        assert_eq!(buffer.to_string(), "one two three");
+    }
+
+    #[test]
+    fn undo_across_leaf_boundaries() {
+        let text = "Grüße 🦀\n".repeat(1_000);
+        let mut buffer = TextBuffer::from(text.as_str());
+        let len = buffer.len_chars();
+        for i in 0..200 {
+            let at = i * 7919 % buffer.len_chars();
+            if i % 2 == 0 {
+                buffer.insert(at, "नमस्ते ");
+            } else {
+                buffer.delete(at..(at + 1_500).min(buffer.len_chars()));
+            }
+        }
+        let edited = buffer.to_string();
+        while buffer.undo() {}
+        assert_eq!(buffer.to_string(), text);
+        assert_eq!(buffer.len_chars(), len);
+        while buffer.redo() {}
+        assert_eq!(buffer.to_string(), edited);
+    }
+}
@@ -0,0 +1,276 @@
+// `Traced<T>`: a wrapper that logs what happens to a value.
+//
+// Creation, clones and drops are recorded automatically. Moves can't be
+// observed by a library (a move is just a memcpy), so they are recorded
+// through explicit hooks:
+//
+//     let s = Traced::new("s", String::from("hello"));
+//     takes_ownership(s.move_into("takes_ownership", "some_string"));
+//
+// Events go into a per-thread log that `take_events` drains.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    Created,
+    /// Cloned from the value with this name
+    Cloned {
+        from: String,
+    },
+    /// Moved into `function`, where it is bound to the event's name
+    MovedInto {
+        function: &'static str,
+        from: String,
+    },
+    /// Returned from a function and bound to the event's name
+    ReturnedFrom {
+        function: &'static str,
+        from: String,
+    },
+    Dropped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub seq: usize,
+    /// Time since the first event on this thread
+    pub at: Duration,
+    /// Name of the binding that holds the value after the event
+    pub name: String,
+    pub kind: EventKind,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let micros = self.at.as_secs_f64() * 1e6;
+        write!(f, "#{:<3} {micros:>9.1}µs  ", self.seq)?;
+        match &self.kind {
+            EventKind::Created => write!(f, "{} created", self.name),
+            EventKind::Cloned { from } => write!(f, "{} cloned from {from}", self.name),
+            EventKind::MovedInto { function, from } => {
+                write!(f, "{from} moved into {function}() as {}", self.name)
+            }
+            EventKind::ReturnedFrom { function, from } => {
+                write!(f, "{from} returned from {function}() into {}", self.name)
+            }
+            EventKind::Dropped => write!(f, "{} dropped", self.name),
+        }
+    }
+}
+
+struct Log {
+    start: Option<Instant>,
+    events: Vec<Event>,
+}
+
+thread_local! {
+    static LOG: RefCell<Log> = const {
+        RefCell::new(Log {
+            start: None,
+            events: Vec::new(),
+        })
+    };
+}
+
+fn record(name: &str, kind: EventKind) {
+    LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        let start = *log.start.get_or_insert_with(Instant::now);
+        let seq = log.events.len() + 1;
+        log.events.push(Event {
+            seq,
+            at: start.elapsed(),
+            name: name.to_string(),
+            kind,
+        });
+    });
+}
+
+/// Drains this thread's event log
+pub fn take_events() -> Vec<Event> {
+    LOG.with(|log| {
+        let mut log = log.borrow_mut();
+        log.start = None;
+        std::mem::take(&mut log.events)
+    })
+}
+
+/// Names of the dropped values, in drop order
+pub fn drop_order(events: &[Event]) -> Vec<&str> {
+    events
+        .iter()
+        .filter(|e| e.kind == EventKind::Dropped)
+        .map(|e| e.name.as_str())
+        .collect()
+}
+
+// ============================================================================
+// Traced<T>
+// ============================================================================
+
+pub struct Traced<T> {
+    name: String,
+    value: T,
+}
+
+impl<T> Traced<T> {
+    pub fn new(name: &str, value: T) -> Traced<T> {
+        record(name, EventKind::Created);
+        Traced {
+            name: name.to_string(),
+            value,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Records a move into `function`, where the value is bound to `param`
+    pub fn move_into(mut self, function: &'static str, param: &str) -> Traced<T> {
+        let from = std::mem::replace(&mut self.name, param.to_string());
+        record(param, EventKind::MovedInto { function, from });
+        self
+    }
+
+    /// Records the value leaving `function` and being bound to `binding`
+    pub fn return_from(mut self, function: &'static str, binding: &str) -> Traced<T> {
+        let from = std::mem::replace(&mut self.name, binding.to_string());
+        record(binding, EventKind::ReturnedFrom { function, from });
+        self
+    }
+
+    /// Clones under an explicit name (`Clone::clone` appends "'")
+    pub fn clone_as(&self, name: &str) -> Traced<T>
+    where
+        T: Clone,
+    {
+        record(
+            name,
+            EventKind::Cloned {
+                from: self.name.clone(),
+            },
+        );
+        Traced {
+            name: name.to_string(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: Clone> Clone for Traced<T> {
+    fn clone(&self) -> Traced<T> {
+        self.clone_as(&format!("{}'", self.name))
+    }
+}
+
+impl<T> Drop for Traced<T> {
+    fn drop(&mut self) {
+        record(&self.name, EventKind::Dropped);
+    }
+}
+
+impl<T> Deref for Traced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Traced<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Traced<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Traced")
+            .field("name", &self.name)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn takes_ownership(some_string: Traced<String>) {
+        assert_eq!(*some_string, "hello");
+    }
+
+    fn makes_copy(some_integer: Traced<i32>) {
+        assert_eq!(*some_integer, 5);
+    }
+
+    fn takes_and_gives_back(a_string: Traced<String>) -> Traced<String> {
+        a_string
+    }
+
+    // Each test runs on its own thread, so each starts with an empty log
+
+    #[test]
+    fn ownership_drop_order() {
+        {
+            let s = Traced::new("s", String::from("hello"));
+            takes_ownership(s.move_into("takes_ownership", "some_string"));
+            let x = Traced::new("x", 5);
+            makes_copy(x.clone_as("some_integer"));
+        }
+        let events = take_events();
+        assert_eq!(drop_order(&events), ["some_string", "some_integer", "x"]);
+    }
+
+    #[test]
+    fn return_values_drop_order() {
+        {
+            let _s1 = Traced::new("some_string", String::from("yours"))
+                .return_from("gives_ownership", "s1");
+            let s2 = Traced::new("s2", String::from("hello"));
+            let _s3 = takes_and_gives_back(s2.move_into("takes_and_gives_back", "a_string"))
+                .return_from("takes_and_gives_back", "s3");
+        }
+        let events = take_events();
+        assert_eq!(drop_order(&events), ["s3", "s1"]);
+    }
+
+    #[test]
+    fn clones_drop_in_reverse_declaration_order() {
+        {
+            let s1 = Traced::new("s1", String::from("hello"));
+            let _s2 = s1.clone_as("s2");
+            let _s3 = s1.clone();
+        }
+        let events = take_events();
+        assert_eq!(drop_order(&events), ["s1'", "s2", "s1"]);
+        assert_eq!(
+            events[2].kind,
+            EventKind::Cloned {
+                from: String::from("s1")
+            }
+        );
+    }
+
+    #[test]
+    fn take_events_drains_the_log() {
+        drop(Traced::new("a", 1));
+        assert_eq!(take_events().len(), 2);
+        assert!(take_events().is_empty());
+        drop(Traced::new("b", 2));
+        let events = take_events();
+        assert_eq!(events[0].seq, 1);
+        assert_eq!(events[0].name, "b");
+    }
+}
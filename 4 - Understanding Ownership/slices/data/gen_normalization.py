@@ -0,0 +1,174 @@
+#!/usr/bin/env python3
+# Generates the Unicode normalization tables in src/normalize/tables.rs from
+# Python's unicodedata module (whose Unicode version is written into the
+# tables), and downloads the official conformance data for that version.
+#
+#   python3 data/gen_normalization.py        # run from the slices crate
+#
+# data/NormalizationTest.txt is Unicode's conformance data, fetched from
+# https://www.unicode.org/Public/<version>/ucd/ and read by the tests in
+# src/normalize/tests.rs. It is checked in; it is only downloaded again when
+# it is for a different version than the tables.
+
+import sys
+import unicodedata
+import urllib.request
+from pathlib import Path
+
+VERSION = unicodedata.unidata_version
+ROOT = Path(__file__).resolve().parent.parent
+HANGUL = range(0xAC00, 0xAC00 + 11172)
+SURROGATES = range(0xD800, 0xE000)
+
+
+def chars():
+    for cp in range(0x110000):
+        if cp not in SURROGATES:
+            yield cp
+
+
+def nf(form, text):
+    return unicodedata.normalize(form, text)
+
+
+def rust_str(text):
+    out = []
+    for ch in text:
+        cp = ord(ch)
+        out.append(ch if 0x20 <= cp < 0x7F and ch not in '"\\' else f"\\u{{{cp:X}}}")
+    return '"' + "".join(out) + '"'
+
+
+def ranges(cps):
+    cps = sorted(cps)
+    out = []
+    for cp in cps:
+        if out and out[-1][1] == cp - 1:
+            out[-1][1] = cp
+        else:
+            out.append([cp, cp])
+    return out
+
+
+def rows(items, per_line):
+    lines = []
+    for i in range(0, len(items), per_line):
+        lines.append("    " + " ".join(items[i : i + per_line]))
+    return "\n".join(lines)
+
+
+def tables():
+    ccc = {}
+    canonical = []
+    compatibility = []
+    nfc_no = []
+    nfkc_no = []
+    for cp in chars():
+        ch = chr(cp)
+        if unicodedata.combining(ch):
+            ccc[cp] = unicodedata.combining(ch)
+        if cp in HANGUL:
+            continue  # decomposed and composed algorithmically
+        nfd, nfkd = nf("NFD", ch), nf("NFKD", ch)
+        if nfd != ch:
+            canonical.append((cp, nfd))
+        if nfkd != nfd:
+            compatibility.append((cp, nfkd))
+        if nf("NFC", ch) != ch:
+            nfc_no.append(cp)
+        if nf("NFKC", ch) != ch:
+            nfkc_no.append(cp)
+
+    # Primary composites: canonical pairs that NFC puts back together
+    compositions = []
+    for cp, _ in canonical:
+        mapping = unicodedata.decomposition(chr(cp)).split()
+        if len(mapping) == 2 and not mapping[0].startswith("<"):
+            first, second = (chr(int(m, 16)) for m in mapping)
+            if nf("NFC", first + second) == chr(cp):
+                compositions.append((first, second, chr(cp)))
+    compositions.sort()
+    # Hangul vowels and trailing consonants also compose with what precedes
+    seconds = {ord(s) for _, s, _ in compositions}
+    seconds |= set(range(0x1161, 0x1176)) | set(range(0x11A8, 0x11C3))
+
+    ccc_ranges = []
+    for cp in sorted(ccc):
+        if ccc_ranges and ccc_ranges[-1][1] == cp - 1 and ccc_ranges[-1][2] == ccc[cp]:
+            ccc_ranges[-1][1] = cp
+        else:
+            ccc_ranges.append([cp, cp, ccc[cp]])
+
+    def range_list(name, doc, cps):
+        items = [f"(0x{a:X}, 0x{b:X})," for a, b in ranges(cps)]
+        return f"/// {doc}\npub(super) const {name}: &[(u32, u32)] = &[\n{rows(items, 6)}\n];\n"
+
+    out = [
+        f"// Generated by data/gen_normalization.py from Unicode {VERSION}. Do not edit.",
+        "",
+        f'pub const UNICODE_VERSION: &str = "{VERSION}";',
+        "",
+        "/// (first, last, canonical combining class) for every non-zero class",
+        "pub(super) const COMBINING_CLASS: &[(u32, u32, u8)] = &[",
+        rows([f"(0x{a:X}, 0x{b:X}, {c})," for a, b, c in ccc_ranges], 4),
+        "];",
+        "",
+        "/// Full canonical decompositions (NFD of the char), Hangul excluded",
+        "pub(super) const CANONICAL: &[(char, &str)] = &[",
+        rows([f"('\\u{{{cp:X}}}', {rust_str(d)})," for cp, d in canonical], 3),
+        "];",
+        "",
+        "/// Full compatibility decompositions where they differ from CANONICAL",
+        "pub(super) const COMPATIBILITY: &[(char, &str)] = &[",
+        rows([f"('\\u{{{cp:X}}}', {rust_str(d)})," for cp, d in compatibility], 3),
+        "];",
+        "",
+        "/// (first, second, composite), sorted, Hangul excluded",
+        "pub(super) const COMPOSITIONS: &[(char, char, char)] = &[",
+        rows(
+            [
+                f"('\\u{{{ord(a):X}}}', '\\u{{{ord(b):X}}}', '\\u{{{ord(c):X}}}'),"
+                for a, b, c in compositions
+            ],
+            2,
+        ),
+        "];",
+        "",
+        range_list("NFC_QC_NO", "Chars that never appear in NFC", nfc_no),
+        range_list("NFKC_QC_NO", "Chars that never appear in NFKC", nfkc_no),
+        range_list(
+            "COMPOSES_WITH_PREVIOUS",
+            "Chars that may combine with what comes before them (quick check Maybe)",
+            seconds,
+        ),
+    ]
+    path = ROOT / "src" / "normalize" / "tables.rs"
+    path.parent.mkdir(exist_ok=True)
+    path.write_text("\n".join(out))
+    print(f"{path}: {len(canonical)} canonical, {len(compatibility)} compatibility, "
+          f"{len(compositions)} compositions, {len(ccc_ranges)} class ranges")
+
+
+def official():
+    url = f"https://www.unicode.org/Public/{VERSION}/ucd/NormalizationTest.txt"
+    path = ROOT / "data" / "NormalizationTest.txt"
+    if path.exists() and f"NormalizationTest-{VERSION}.txt" in path.read_text().split("\n", 1)[0]:
+        print(f"{path}: already for {VERSION}")
+        return
+    try:
+        with urllib.request.urlopen(url, timeout=60) as response:
+            data = response.read()
+    except OSError as err:
+        sys.exit(f"cannot fetch {url}: {err}")
+    first = data.split(b"\n", 1)[0].decode()
+    if f"NormalizationTest-{VERSION}.txt" not in first:
+        sys.exit(f"{url} starts with {first!r}, expected version {VERSION}")
+    path.write_bytes(data)
+    print(f"{path}: {len(data.splitlines())} lines")
+
+
+if __name__ == "__main__":
+    if sys.version_info < (3, 8):
+        sys.exit("needs Python 3.8+")
+    tables()
+    official()
@@ -1,3 +1,7 @@
+use slices::timestamp::Timestamp;
+use slices::transliterate;
+use slices::words::{nth_word, words};
+
 fn main() {
     println!("=== RUST SLICES ===\n");
     
@@ -100,6 +104,8 @@ fn main() {
     
     println!("   Cannot call s5.clear() while word exists");
     println!("   Compiler prevents use-after-invalidation!");
+    println!("   To keep word positions across edits, hold span handles instead:");
+    println!("   cargo run --bin text_arena");
     
     // After word is no longer used, we can modify
     println!("   (Dropping word reference...)");
@@ -161,18 +167,19 @@ fn main() {
     println!("\n10. PRACTICAL EXAMPLES:");
     
     // Example 1: Extracting parts of data
+    // &data[0..10] and &data[11..19] only fit this exact layout, so parse
+    // instead (see src/bin/timestamp.rs)
     let data = "2023-12-04T15:30:00";
-    let date = &data[0..10];
-    let time = &data[11..19];
+    let timestamp: Timestamp = data.parse().expect("valid timestamp");
     println!("   Timestamp: {}", data);
-    println!("   Date: {}, Time: {}", date, time);
+    println!("   Date: {}, Time: {}", timestamp.date(), timestamp.time());
     
     // Example 2: Multiple word extraction
     let sentence = "The quick brown fox jumps";
-    let words = extract_words(sentence);
+    let words: [&str; 3] = extract_words(sentence);
     println!("   Sentence: '{}'", sentence);
     println!("   Word 1: '{}', Word 2: '{}', Word 3: '{}'", 
-             words.0, words.1, words.2);
+             words[0], words[1], words[2]);
     
     // Example 3: Array manipulation with slices
     let numbers = [10, 20, 30, 40, 50, 60, 70, 80, 90];
@@ -204,6 +211,8 @@ fn main() {
     if let Some(slice) = safe_slice(unicode, 0, 3) {
         println!("   Safe slice [0..3]: {}", slice);
     }
+    let latin = transliterate::iast(unicode);
+    println!("   Transliterated: {} (cargo run --bin transliterate)", latin);
     
     println!("\n=== SLICE SUMMARY ===");
     println!("1. Slices are references to contiguous data");
@@ -221,15 +230,8 @@ fn main() {
 /// Returns the index of the end of the first word
 /// PROBLEM: Index can become invalid if string changes!
 fn first_word_index(s: &String) -> usize {
-    let bytes = s.as_bytes();
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return i;  // Return index where space found
-        }
-    }
-    
-    s.len()  // No space found, entire string is one word
+    // End of the first word's span; any whitespace counts, not just b' '
+    words(s).next().map_or(s.len(), |word| word.end)
 }
 
 // ============================================================================
@@ -239,17 +241,8 @@ fn first_word_index(s: &String) -> usize {
 /// Returns a slice of the first word in a string
 /// BETTER: Returns reference that's tied to original data
 fn first_word(s: &String) -> &str {
-    let bytes = s.as_bytes();
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            // Return slice from start to space
-            return &s[0..i];
-        }
-    }
-    
-    // No space found, return entire string slice
-    &s[..]
+    // The slice borrows from s, so s can't change while it's in use
+    nth_word(s, 0).unwrap_or("")
 }
 
 // ============================================================================
@@ -259,68 +252,21 @@ fn first_word(s: &String) -> &str {
 /// Most flexible version: accepts any string slice
 /// Can be called with String, &str, or string slices
 fn flexible_first_word(s: &str) -> &str {
-    let bytes = s.as_bytes();
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            return &s[0..i];
-        }
-    }
-    
-    &s[..]
+    nth_word(s, 0).unwrap_or("")
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
-/// Extracts first three words from a string
+/// Extracts the first N words from a string ("" for missing words)
 /// Demonstrates multiple slice returns
-fn extract_words(s: &str) -> (&str, &str, &str) {
-    let bytes = s.as_bytes();
-    let mut word_starts = Vec::new();
-    let mut word_ends = Vec::new();
-    
-    // Find word boundaries
-    let mut in_word = false;
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            if in_word {
-                word_ends.push(i);
-                in_word = false;
-            }
-        } else {
-            if !in_word {
-                word_starts.push(i);
-                in_word = true;
-            }
-        }
-    }
-    
-    if in_word {
-        word_ends.push(s.len());
+fn extract_words<const N: usize>(s: &str) -> [&str; N] {
+    let mut found = [""; N];
+    for (slot, word) in found.iter_mut().zip(words(s).texts()) {
+        *slot = word;
     }
-    
-    // Extract up to 3 words
-    let word1 = if word_starts.len() > 0 {
-        &s[word_starts[0]..word_ends[0]]
-    } else {
-        ""
-    };
-    
-    let word2 = if word_starts.len() > 1 {
-        &s[word_starts[1]..word_ends[1]]
-    } else {
-        ""
-    };
-    
-    let word3 = if word_starts.len() > 2 {
-        &s[word_starts[2]..word_ends[2]]
-    } else {
-        ""
-    };
-    
-    (word1, word2, word3)
+    found
 }
 
 /// Safely slices a string with UTF-8 boundary checking
@@ -365,28 +311,5 @@ fn demonstrate_second_word() {
 
 /// Returns the second word in a string, if it exists
 fn second_word(s: &str) -> Option<&str> {
-    let bytes = s.as_bytes();
-    let mut space_count = 0;
-    let mut start_index = 0;
-    
-    for (i, &item) in bytes.iter().enumerate() {
-        if item == b' ' {
-            space_count += 1;
-            
-            if space_count == 1 {
-                // Found end of first word
-                start_index = i + 1;
-            } else if space_count == 2 {
-                // Found end of second word
-                return Some(&s[start_index..i]);
-            }
-        }
-    }
-    
-    // Handle case where string ends with second word
-    if space_count == 1 && start_index < s.len() {
-        return Some(&s[start_index..]);
-    }
-    
-    None
+    nth_word(s, 1)
 }
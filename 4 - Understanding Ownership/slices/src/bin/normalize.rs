@@ -0,0 +1,169 @@
+// Comparing and slicing text that can be spelled more than one way.
+//
+//   cargo run --bin normalize                    # demo
+//   cargo run --bin normalize TEXT...            # show the four forms
+//
+// The conformance tests against Unicode's NormalizationTest.txt are in
+// src/normalize/tests.rs.
+
+use std::borrow::Cow;
+
+use slices::normalize::{self, Form, IsNormalized};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        for text in &args {
+            println!("{text}   {}", hex(text));
+            for form in Form::ALL {
+                let normalized = normalize::normalize(text, form);
+                println!(
+                    "   {:<5} {normalized}   {}",
+                    format!("{form:?}"),
+                    hex(&normalized)
+                );
+            }
+        }
+        return;
+    }
+
+    println!("=== NORMALIZATION ===\n");
+
+    // ========================================================================
+    // 1. SAME TEXT, DIFFERENT CHARS
+    // ========================================================================
+    println!("1. EQUAL-LOOKING STRINGS:");
+
+    let composed = "caf\u{E9}";
+    let decomposed = "cafe\u{301}";
+    println!(
+        "   {composed:?} is {} bytes: {}",
+        composed.len(),
+        hex(composed)
+    );
+    println!(
+        "   {decomposed:?} is {} bytes: {}",
+        decomposed.len(),
+        hex(decomposed)
+    );
+    println!("   ==: {}", composed == decomposed);
+    println!(
+        "   canonical_eq: {}",
+        normalize::canonical_eq(composed, decomposed)
+    );
+    assert_ne!(composed, decomposed);
+    assert!(normalize::canonical_eq(composed, decomposed));
+    assert_eq!(normalize::nfc(decomposed), composed);
+    assert_eq!(normalize::nfd(composed), decomposed);
+
+    // ========================================================================
+    // 2. SLICING AFTER NORMALIZING
+    // ========================================================================
+    println!("\n2. BYTE OFFSETS DEPEND ON THE FORM:");
+
+    // The first four bytes end inside the accent of one and after it in
+    // the other, so a byte range means nothing until the form is fixed
+    for text in [composed, decomposed] {
+        println!(
+            "   safe_slice({text:?}, 0, 4) = {:?}",
+            safe_slice(text, 0, 4)
+        );
+    }
+    assert_eq!(safe_slice(composed, 0, 4), None);
+    assert_eq!(safe_slice(decomposed, 0, 4), Some("cafe"));
+
+    let unicode = "नमस्ते";
+    let nfd = normalize::nfd(unicode);
+    println!(
+        "   {unicode:?}: NFD borrowed: {}",
+        matches!(nfd, Cow::Borrowed(_))
+    );
+    println!(
+        "   safe_slice(unicode, 0, 3) = {:?}",
+        safe_slice(&nfd, 0, 3)
+    );
+    assert_eq!(nfd, unicode);
+
+    // Devanagari has composed letters too: क़ (U+0958) is क + nukta
+    let qa = "\u{958}";
+    let qa_nfd = normalize::nfd(qa);
+    println!(
+        "   {qa:?} -> NFD {}, NFC {}",
+        hex(&qa_nfd),
+        hex(&normalize::nfc(qa))
+    );
+    assert_eq!(qa_nfd, "\u{915}\u{93C}");
+    // U+0958 is a composition exclusion, so NFC keeps it decomposed
+    assert_eq!(normalize::nfc(qa), "\u{915}\u{93C}");
+
+    // ========================================================================
+    // 3. COMPATIBILITY FORMS
+    // ========================================================================
+    println!("\n3. COMPATIBILITY EQUIVALENCE:");
+
+    for (a, b) in [("\u{FB01}le", "file"), ("\u{2460}", "1"), ("x\u{B2}", "x2")] {
+        println!(
+            "   {a:?} vs {b:?}: canonical_eq {}, compatible_eq {}, NFKC {:?}",
+            normalize::canonical_eq(a, b),
+            normalize::compatible_eq(a, b),
+            normalize::nfkc(a)
+        );
+        assert!(!normalize::canonical_eq(a, b));
+        assert!(normalize::compatible_eq(a, b));
+    }
+
+    // Hangul syllables decompose into jamo and compose back
+    let han = "\u{D55C}";
+    let jamo = normalize::nfd(han);
+    println!("   {han:?} -> NFD {}", hex(&jamo));
+    assert_eq!(jamo, "\u{1112}\u{1161}\u{11AB}");
+    assert_eq!(normalize::nfc(&jamo), han);
+
+    // ========================================================================
+    // 4. QUICK CHECK
+    // ========================================================================
+    println!("\n4. QUICK CHECK AND BORROWING:");
+
+    let cases = [
+        ("plain ASCII", "hello", Form::Nfc, IsNormalized::Yes),
+        ("composed é", composed, Form::Nfd, IsNormalized::No),
+        ("ﬁ", "\u{FB01}", Form::Nfkc, IsNormalized::No),
+        ("e + U+0301", decomposed, Form::Nfc, IsNormalized::Maybe),
+        (
+            "marks out of order",
+            "a\u{301}\u{323}",
+            Form::Nfd,
+            IsNormalized::No,
+        ),
+    ];
+    for (name, text, form, expected) in cases {
+        let result = normalize::quick_check(text, form);
+        println!(
+            "   {name:<20} {:<5} {result:?}, is_normalized {}",
+            format!("{form:?}"),
+            normalize::is_normalized(text, form)
+        );
+        assert_eq!(result, expected);
+    }
+
+    let already = "Grüße, नमस्ते";
+    let normalized = normalize::nfc(already);
+    println!(
+        "   nfc({already:?}) borrowed: {}",
+        matches!(normalized, Cow::Borrowed(_))
+    );
+    assert!(matches!(normalized, Cow::Borrowed(_)));
+    assert!(matches!(normalize::nfc(decomposed), Cow::Owned(_)));
+}
+
+/// Same as `safe_slice` in main.rs
+fn safe_slice(s: &str, start: usize, end: usize) -> Option<&str> {
+    s.get(start..end)
+}
+
+fn hex(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
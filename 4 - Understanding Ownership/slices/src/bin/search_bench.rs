@@ -0,0 +1,161 @@
+// Times the search module against naive loops and against `str::find`.
+// The tests in search.rs check that they all agree.
+//
+// Run with optimizations for meaningful timings:
+//   cargo run --release --bin search_bench [FILE]
+//
+// Without FILE a ~16 MB text is generated.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use slices::search::{self, Finder};
+
+const REPEATS: u32 = 10;
+
+fn main() {
+    println!("=== SEARCH ===\n");
+
+    // ========================================================================
+    // 1. TIMINGS
+    // ========================================================================
+    let mut text = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(&path).unwrap_or_else(|e| {
+            eprintln!("cannot read {path}: {e}");
+            std::process::exit(1);
+        }),
+        None => sample_text(16 * 1024 * 1024),
+    };
+    // Targets that only occur at the very end (or, for rfind_byte, the very
+    // start), so every search scans the whole text
+    let byte = b'#';
+    let needle = "the value will be borrowed instead";
+    assert!(!text.contains(['#', '@']) && !text.contains(needle));
+    text.insert(0, '@');
+    text.push_str(needle);
+    text.push('#');
+    let bytes = text.as_bytes();
+
+    println!(
+        "1. TIMINGS ({} MB, target at the end, best of {REPEATS}):",
+        bytes.len() / 1_000_000
+    );
+    println!("   {:<42} {:>12} {:>10}", "search", "time", "GB/s");
+
+    let expected = bytes.len() - 1;
+    bench(
+        "byte: naive loop (like first_word)",
+        bytes.len(),
+        || naive_find_byte(black_box(bytes), byte),
+        Some(expected),
+    );
+    bench(
+        "byte: iter().position",
+        bytes.len(),
+        || black_box(bytes).iter().position(|&b| b == byte),
+        Some(expected),
+    );
+    bench(
+        "byte: search::find_byte (SWAR)",
+        bytes.len(),
+        || search::find_byte(black_box(bytes), byte),
+        Some(expected),
+    );
+    bench(
+        "byte: str::find(char)",
+        bytes.len(),
+        || black_box(text.as_str()).find('#'),
+        Some(expected),
+    );
+    bench(
+        "byte: search::rfind_byte (first at start)",
+        bytes.len(),
+        || search::rfind_byte(black_box(bytes), b'@'),
+        Some(0),
+    );
+
+    println!();
+    let expected = bytes.len() - 1 - needle.len();
+    bench(
+        "pattern: naive windows()",
+        bytes.len(),
+        || naive_find(black_box(bytes), needle.as_bytes()),
+        Some(expected),
+    );
+    let finder = Finder::new(needle.as_bytes());
+    bench(
+        "pattern: Finder::find (Horspool)",
+        bytes.len(),
+        || finder.find(black_box(bytes)),
+        Some(expected),
+    );
+    bench(
+        "pattern: str::find(&str)",
+        bytes.len(),
+        || black_box(text.as_str()).find(needle),
+        Some(expected),
+    );
+
+    println!();
+    let count = text.matches("owner").count();
+    let owner = Finder::new(b"owner");
+    bench(
+        "count \"owner\": Finder::find_iter",
+        bytes.len(),
+        || Some(owner.find_iter(black_box(bytes)).count()),
+        Some(count),
+    );
+    bench(
+        "count \"owner\": str::matches",
+        bytes.len(),
+        || Some(black_box(text.as_str()).matches("owner").count()),
+        Some(count),
+    );
+}
+
+fn naive_find_byte(bytes: &[u8], byte: u8) -> Option<usize> {
+    for (i, &item) in bytes.iter().enumerate() {
+        if item == byte {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn naive_find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    (0..=haystack.len().checked_sub(needle.len())?).find(|&i| haystack[i..].starts_with(needle))
+}
+
+// Best of REPEATS runs, after checking the answer
+fn bench(name: &str, bytes: usize, f: impl Fn() -> Option<usize>, expected: Option<usize>) {
+    let mut best = Duration::MAX;
+    for _ in 0..REPEATS {
+        let start = Instant::now();
+        let result = black_box(f());
+        best = best.min(start.elapsed());
+        assert_eq!(result, expected, "{name}");
+    }
+    println!(
+        "   {:<42} {:>12} {:>10.2}",
+        name,
+        format!("{best:.2?}"),
+        bytes as f64 / best.as_secs_f64() / 1e9
+    );
+}
+
+// Lines of mixed ASCII and multibyte text, about `bytes` long
+fn sample_text(bytes: usize) -> String {
+    let lines = [
+        "Each value in Rust has an owner.\n",
+        "There can only be one owner at a time.\n",
+        "When the owner goes out of scope, the value will be dropped.\n",
+        "Grüße, Jürgen — ça va? नमस्ते दुनिया 🦀\n",
+    ];
+    let mut text = String::with_capacity(bytes + 64);
+    let mut i = 0;
+    while text.len() < bytes {
+        text.push_str(lines[i % lines.len()]);
+        i += 1;
+    }
+    text
+}
@@ -0,0 +1,100 @@
+// Section 6 of main.rs again, with span handles instead of `&str`: the
+// text can be cleared while "word" is still around, and the handle reports
+// that it went stale instead of dangling.
+
+use slices::text_arena::{SpanError, TextArena};
+
+fn main() -> Result<(), SpanError> {
+    println!("=== TEXT ARENA ===\n");
+
+    // ========================================================================
+    // 1. EDITING WHILE HOLDING A WORD
+    // ========================================================================
+    println!("1. s5.clear() WHILE word EXISTS:");
+
+    let mut arena = TextArena::new();
+    let s5 = arena.insert("hello world");
+    let word = arena.word_spans(s5)?[0];
+    println!("   word = {:?} -> '{}'", word.range(), arena.resolve(word)?);
+
+    // With `let word = first_word(&s5)` this is a compile error
+    arena.clear(s5)?;
+    let err = arena.resolve(word).unwrap_err();
+    println!("   after clear: {err}");
+    assert!(matches!(err, SpanError::Stale { version: 0, .. }));
+
+    // ========================================================================
+    // 2. SPANS FOLLOW EDITS AROUND THEM
+    // ========================================================================
+    println!("\n2. EDITS BEFORE, AFTER AND INSIDE SPANS:");
+
+    let doc = arena.insert("the quick brown fox jumps");
+    let spans = arena.word_spans(doc)?;
+    let show = |arena: &TextArena| {
+        println!("   text: '{}'", arena.get(doc).unwrap());
+        for span in &spans {
+            let taken = format!("{:?}", span.range());
+            match arena.rebase(*span) {
+                Ok(now) => {
+                    let now = format!("{:?}", now.range());
+                    println!(
+                        "      {taken:<6} now {now:<6} '{}'",
+                        arena.resolve(*span).unwrap()
+                    );
+                }
+                Err(err) => println!("      {taken:<6} {err}"),
+            }
+        }
+    };
+    show(&arena);
+
+    arena.insert_str(doc, 0, "see ")?;
+    arena.push_str(doc, " over the dog")?;
+    println!("\n   Inserted \"see \" at the start and appended \" over the dog\":");
+    show(&arena);
+    assert_eq!(arena.resolve(spans[2])?, "brown");
+    assert_eq!(arena.rebase(spans[2])?.range(), 14..19);
+
+    // "quick" -> "slow": its span is gone, the rest move left by one
+    let quick = arena.rebase(spans[1])?;
+    arena.replace(doc, quick.range(), "slow")?;
+    println!("\n   Replaced \"quick\" with \"slow\":");
+    show(&arena);
+    assert!(matches!(
+        arena.resolve(spans[1]),
+        Err(SpanError::Stale { .. })
+    ));
+    assert_eq!(arena.resolve(spans[3])?, "fox");
+
+    // Spans taken now start from the current version
+    let fresh = arena.word_spans(doc)?;
+    let dog = fresh.last().unwrap();
+    println!(
+        "\n   New spans at version {}: last word '{}'",
+        dog.version(),
+        arena.resolve(*dog)?
+    );
+    assert_eq!(arena.version(doc), Some(3));
+
+    // ========================================================================
+    // 3. REMOVED TEXTS AND BAD RANGES
+    // ========================================================================
+    println!("\n3. REMOVED TEXTS AND BAD RANGES:");
+
+    let removed = arena.remove(s5);
+    let reused = arena.insert("a new text in the old slot");
+    println!("   Removed s5 ({removed:?}) and inserted another text into its slot");
+    let err = arena.resolve(word).unwrap_err();
+    println!("   old word -> {err}");
+    assert_eq!(err, SpanError::UnknownText(s5));
+    assert_ne!(reused, s5);
+    assert_eq!(arena.len(), 2);
+
+    let accented = arena.insert("café");
+    let err = arena.span(accented, 0..4).unwrap_err();
+    println!("   span(\"café\", 0..4) -> {err}");
+    assert!(matches!(err, SpanError::InvalidRange { .. }));
+    assert_eq!(arena.resolve(arena.span(accented, 0..5)?)?, "café");
+
+    Ok(())
+}
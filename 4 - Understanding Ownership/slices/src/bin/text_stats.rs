@@ -0,0 +1,267 @@
+// wc with a few extras: graphemes, average word length, the longest line
+// and the most frequent words.
+//
+//   cargo run --bin text_stats                          # demo
+//   cargo run --bin text_stats -- [-n N] FILE...        # "-" reads stdin
+//   cat notes.txt | cargo run --bin text_stats -- -
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
+use slices::text_stats::{CHUNK, TextStats, TextStatsError};
+
+const USAGE: &str = "usage: text_stats [-n N] FILE...   (\"-\" reads stdin)";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        demo();
+        return;
+    }
+
+    let mut top = 5;
+    let mut paths = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-n" => {
+                top = match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) => n,
+                    None => exit_with(USAGE),
+                }
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        exit_with(USAGE);
+    }
+
+    print_header();
+    let mut total = TextStats::new();
+    let mut failed = false;
+    for path in &paths {
+        let result = if path == "-" {
+            TextStats::from_reader(io::stdin().lock())
+        } else {
+            File::open(path)
+                .map_err(TextStatsError::from)
+                .and_then(TextStats::from_reader)
+        };
+        match result {
+            Ok(stats) => {
+                print_row(&stats, path);
+                total.merge(&stats);
+            }
+            Err(err) => {
+                eprintln!("text_stats: {path}: {err}");
+                failed = true;
+            }
+        }
+    }
+    if paths.len() > 1 {
+        print_row(&total, "total");
+    }
+    print_top_words(&total, top);
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn demo() {
+    println!("=== TEXT STATS ===\n");
+
+    // ========================================================================
+    // 1. COUNTS
+    // ========================================================================
+    println!("1. COUNTS:");
+
+    let text = "The quick brown fox\tjumps over the lazy dog.\n\
+                नमस्ते दुनिया — the dog 🐕 sleeps.\r\n\
+                \n\
+                The end";
+    for line in text.lines() {
+        println!("   | {line}");
+    }
+    let stats = TextStats::from_text(text);
+    print_header();
+    print_row(&stats, "(sample)");
+    assert_eq!(stats.lines, 3);
+    assert_eq!(stats.words, 9 + 7 + 2);
+    assert_eq!(stats.bytes, text.len() as u64);
+    assert_eq!(stats.chars, text.chars().count());
+    // "नमस्ते दुनिया" is 13 chars but 7 clusters, and "\r\n" is one
+    assert_eq!(stats.graphemes, stats.chars - 6 - 1);
+    let longest = stats.longest_line.unwrap();
+    assert_eq!((longest.number, longest.chars), (1, 44));
+
+    println!();
+    print_top_words(&stats, 3);
+    assert_eq!(stats.top_words(2), [("the", 4), ("dog", 2)]);
+
+    // ========================================================================
+    // 2. STREAMING
+    // ========================================================================
+    println!("\n2. STREAMING IN {} KiB CHUNKS:", CHUNK / 1024);
+
+    // Reads that stop mid-line and mid-character give the same answer
+    let trickle = TextStats::from_reader(Trickle {
+        bytes: text.as_bytes(),
+        step: 3,
+    })
+    .unwrap();
+    println!(
+        "   3-byte reads: {} words, {} graphemes",
+        trickle.words, trickle.graphemes
+    );
+    assert_eq!(
+        (trickle.words, trickle.graphemes),
+        (stats.words, stats.graphemes)
+    );
+
+    // 8 MiB of text that never exists in memory all at once
+    let line = "Slices borrow; they never copy. नमस्ते!\n";
+    let copies = 8 * 1024 * 1024 / line.len();
+    let big = Repeat {
+        line,
+        copies,
+        offset: 0,
+    };
+    let big = TextStats::from_reader(big).unwrap();
+    print_header();
+    print_row(&big, "(generated)");
+    assert_eq!(big.lines, copies);
+    assert_eq!(big.words, copies * 6);
+    assert_eq!(big.vocabulary(), 6);
+
+    // One line longer than a chunk is split between words
+    let long = "word ".repeat(CHUNK / 2);
+    let long = TextStats::from_reader(long.as_bytes()).unwrap();
+    println!("   one {}-byte line: {} words", long.bytes, long.words);
+    assert_eq!(long.words, CHUNK / 2);
+    assert_eq!(long.top_words(1), [("word", CHUNK / 2)]);
+
+    // ========================================================================
+    // 3. INVALID UTF-8
+    // ========================================================================
+    println!("\n3. INVALID UTF-8:");
+
+    let mut bytes = "first line\nsecond ".as_bytes().to_vec();
+    bytes.push(0xFF);
+    bytes.extend_from_slice(b" line\n");
+    let err = TextStats::from_reader(bytes.as_slice()).unwrap_err();
+    println!("   {:?}\n   -> {err}", String::from_utf8_lossy(&bytes));
+    assert!(matches!(
+        err,
+        TextStatsError::InvalidUtf8 {
+            offset: 18,
+            line: 2
+        }
+    ));
+
+    // A truncated character at the very end of the input
+    let truncated = &"done: ✓".as_bytes()[..8];
+    let err = TextStats::from_reader(truncated).unwrap_err();
+    println!("   {:?}\n   -> {err}", String::from_utf8_lossy(truncated));
+    assert!(matches!(
+        err,
+        TextStatsError::InvalidUtf8 { offset: 6, line: 1 }
+    ));
+
+    // Deep in a stream the offset is absolute, not relative to the chunk
+    let mut bytes = "abc\n".repeat(CHUNK).into_bytes();
+    bytes[3 * CHUNK + 1] = 0xC0;
+    let err = TextStats::from_reader(bytes.as_slice()).unwrap_err();
+    println!(
+        "   {} bytes, bad byte at {}\n   -> {err}",
+        bytes.len(),
+        3 * CHUNK + 1
+    );
+    assert!(matches!(
+        err,
+        TextStatsError::InvalidUtf8 { offset, line } if offset == 3 * CHUNK as u64 + 1 && line == 3 * CHUNK / 4 + 1
+    ));
+}
+
+fn print_header() {
+    println!(
+        "   {:>9} {:>9} {:>10} {:>10} {:>10} {:>8} {:>12}  name",
+        "lines", "words", "chars", "bytes", "graphemes", "avg word", "longest"
+    );
+}
+
+fn print_row(stats: &TextStats, name: &str) {
+    let average = match stats.average_word_length() {
+        Some(average) => format!("{average:.2}"),
+        None => "-".to_string(),
+    };
+    let longest = match stats.longest_line {
+        Some(longest) => format!("{}@{}", longest.chars, longest.number),
+        None => "-".to_string(),
+    };
+    println!(
+        "   {:>9} {:>9} {:>10} {:>10} {:>10} {:>8} {:>12}  {name}",
+        stats.lines, stats.words, stats.chars, stats.bytes, stats.graphemes, average, longest
+    );
+}
+
+fn print_top_words(stats: &TextStats, n: usize) {
+    if n == 0 {
+        return;
+    }
+    println!("   top {n} of {} distinct words:", stats.vocabulary());
+    for (word, count) in stats.top_words(n) {
+        println!("   {count:>9}  {word}");
+    }
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    process::exit(2);
+}
+
+// A reader that hands out at most `step` bytes per read
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    step: usize,
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.step.min(buf.len()).min(self.bytes.len());
+        buf[..n].copy_from_slice(&self.bytes[..n]);
+        self.bytes = &self.bytes[n..];
+        Ok(n)
+    }
+}
+
+// `line` repeated `copies` times, produced on demand
+struct Repeat<'a> {
+    line: &'a str,
+    copies: usize,
+    offset: usize,
+}
+
+impl Read for Repeat<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let line = self.line.as_bytes();
+        let mut written = 0;
+        while written < buf.len() && self.copies > 0 {
+            let n = (line.len() - self.offset).min(buf.len() - written);
+            buf[written..written + n].copy_from_slice(&line[self.offset..self.offset + n]);
+            written += n;
+            self.offset += n;
+            if self.offset == line.len() {
+                self.offset = 0;
+                self.copies -= 1;
+            }
+        }
+        Ok(written)
+    }
+}
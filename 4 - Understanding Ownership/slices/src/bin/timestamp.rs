@@ -0,0 +1,136 @@
+// Parsing timestamps instead of slicing them at fixed offsets.
+//
+//   cargo run --bin timestamp                 # demo
+//   cargo run --bin timestamp TIMESTAMP...    # parse your own
+
+use slices::timestamp::{Timestamp, TimestampError};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        for text in &args {
+            match text.parse::<Timestamp>() {
+                Ok(ts) => println!(
+                    "{text}\n   date {}, time {}, {:?}",
+                    ts.date(),
+                    ts.time(),
+                    ts.offset()
+                ),
+                Err(err) => print_error(text, &err),
+            }
+        }
+        return;
+    }
+
+    println!("=== TIMESTAMPS ===\n");
+
+    // ========================================================================
+    // 1. FIXED OFFSETS BREAK
+    // ========================================================================
+    println!("1. &data[0..10] AND &data[11..19] ONLY FIT ONE LAYOUT:");
+
+    for data in ["2023-12-04T15:30:00", "2023-12-04T15:30:00.125+05:30"] {
+        let ts: Timestamp = data.parse().unwrap();
+        println!("   {data}");
+        println!(
+            "      slices:  date {}, time {}",
+            &data[0..10],
+            &data[11..19]
+        );
+        println!(
+            "      parsed:  date {}, time {}, offset {:?}",
+            ts.date(),
+            ts.time(),
+            ts.offset()
+        );
+    }
+    // Slicing also "succeeds" on nonsense
+    let nonsense = "2023-02-30T25:61:00";
+    println!("   {nonsense}");
+    println!(
+        "      slices:  date {}, time {}",
+        &nonsense[0..10],
+        &nonsense[11..19]
+    );
+    let err = nonsense.parse::<Timestamp>().unwrap_err();
+    println!("      parsed:  {err}");
+
+    // ========================================================================
+    // 2. ROUND TRIPS
+    // ========================================================================
+    println!("\n2. FORMATTING BACK OUT:");
+
+    for text in [
+        "2023-12-04T15:30:00",
+        "2023-12-04T15:30:00Z",
+        "2023-12-04T15:30:00.5-08:00",
+        "2024-02-29T23:59:59.123456789+00:00",
+        "2016-12-31T23:59:60Z",
+        "2023-12-04T15:30:00-00:00",
+    ] {
+        let ts: Timestamp = text.parse().unwrap();
+        println!("   {text:<36} -> {ts}");
+    }
+    // Lowercase and space separators are accepted but written canonically
+    let ts: Timestamp = "2023-12-04 15:30:00z".parse().unwrap();
+    println!("   {:<36} -> {ts}", "2023-12-04 15:30:00z");
+
+    // ========================================================================
+    // 3. ERRORS WITH POSITIONS
+    // ========================================================================
+    println!("\n3. VALIDATION:");
+
+    let bad = [
+        "2023-13-01T00:00:00",
+        "2023-02-29T00:00:00",
+        "1900-02-29T00:00:00",
+        "2023-04-31T00:00:00",
+        "2023-12-04T24:00:00",
+        "2016-12-31T12:59:60Z",
+        "2023-12-04X15:30:00",
+        "2023-12-4T15:30:00",
+        "2023-12-04T15:30",
+        "2023-12-04T15:30:00.",
+        "2023-12-04T15:30:00.1234567891Z",
+        "2023-12-04T15:30:00+5:30",
+        "2023-12-04T15:30:00+05:30 UTC",
+        "2023-12-04T15:30:00→",
+    ];
+    for text in bad {
+        print_error(text, &text.parse::<Timestamp>().unwrap_err());
+    }
+
+    // ========================================================================
+    // 4. COMPARING AND SUBTRACTING
+    // ========================================================================
+    println!("\n4. INSTANTS:");
+
+    let utc: Timestamp = "2023-12-04T10:00:00Z".parse().unwrap();
+    let india: Timestamp = "2023-12-04T15:30:00+05:30".parse().unwrap();
+    let new_york: Timestamp = "2023-12-04T06:00:00.250-05:00".parse().unwrap();
+    println!("   {utc} == {india}: {}", utc == india);
+    println!("   {new_york} in UTC is {}", new_york.to_utc().unwrap());
+
+    let elapsed = new_york.duration_since(&india).unwrap();
+    println!("   {new_york} - {india} = {elapsed:?}");
+
+    // Across a leap day and a year boundary
+    let before: Timestamp = "2023-12-31T23:00:00-01:00".parse().unwrap();
+    let after: Timestamp = "2024-03-01T00:00:00Z".parse().unwrap();
+    let days = after.duration_since(&before).unwrap().as_secs() / 86_400;
+    println!("   {before} to {after}: {days} days");
+
+    let mut sorted: Vec<Timestamp> = vec![after, new_york, before, utc];
+    sorted.sort();
+    println!(
+        "   sorted: {:?}",
+        sorted.iter().map(Timestamp::to_string).collect::<Vec<_>>()
+    );
+}
+
+fn print_error(text: &str, err: &TimestampError) {
+    // The caret goes under the offending character; count chars, not bytes
+    let column = text[..err.position()].chars().count();
+    println!("   {text}");
+    println!("   {}^ {err}", " ".repeat(column));
+}
@@ -0,0 +1,133 @@
+// Devanagari in Latin letters, and slices of the Latin traced back to it.
+//
+//   cargo run --bin transliterate              # demo
+//   cargo run --bin transliterate TEXT...      # IAST and ASCII for your own
+
+use slices::transliterate::{self, Scheme, Transliteration};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        for text in &args {
+            println!("{text}");
+            println!("   IAST:  {}", transliterate::iast(text));
+            println!("   ASCII: {}", transliterate::ascii(text));
+        }
+        return;
+    }
+
+    println!("=== TRANSLITERATION ===\n");
+
+    // ========================================================================
+    // 1. LETTERS, VOWEL SIGNS AND CONJUNCTS
+    // ========================================================================
+    println!("1. IAST AND ASCII:");
+
+    let words = [
+        ("नमस्ते", "namaste", "namaste"),
+        ("हिन्दी", "hindī", "hindi"),
+        ("संस्कृतम्", "saṃskṛtam", "sanskritam"),
+        ("संबंध", "saṃbaṃdha", "sambandha"),
+        ("क्षत्रिय", "kṣatriya", "kshatriya"),
+        ("ज्ञान", "jñāna", "jnana"),
+        ("चाँद", "cām̐da", "chanda"),
+        ("दुःख", "duḥkha", "duhkha"),
+        ("ऋषि", "ṛṣi", "rishi"),
+        ("ऐसा", "aisā", "aisa"),
+        ("कमल", "kamala", "kamala"),
+    ];
+    for (devanagari, iast, ascii) in words {
+        let (got_iast, got_ascii) = (
+            transliterate::iast(devanagari),
+            transliterate::ascii(devanagari),
+        );
+        println!("   {devanagari:<12} {got_iast:<12} {got_ascii}");
+        assert_eq!(got_iast.as_str(), iast);
+        assert_eq!(got_ascii.as_str(), ascii);
+        check_segments(&got_iast);
+        check_segments(&got_ascii);
+    }
+
+    // ========================================================================
+    // 2. NUKTA, DIGITS AND MIXED TEXT
+    // ========================================================================
+    println!("\n2. NUKTA, DIGITS AND MIXED TEXT:");
+
+    // The nukta can be a separate mark or part of a precomposed letter
+    let separate = "ज\u{93C}रूर";
+    let precomposed = "\u{95B}रूर";
+    for text in [separate, precomposed] {
+        let latin = transliterate::iast(text);
+        println!("   {:<24} {latin}", format!("{text:?}"));
+        assert_eq!(latin.as_str(), "zarūra");
+    }
+    assert_eq!(transliterate::ascii("फ़िल्म").as_str(), "filma");
+
+    let mixed = "वर्ष २०२४ में, Rust। ॐ";
+    let latin = transliterate::iast(mixed);
+    println!("   {mixed} -> {latin}");
+    assert_eq!(latin.as_str(), "varṣa 2024 meṃ, Rust| oṃ");
+    check_segments(&latin);
+
+    // ========================================================================
+    // 3. TRACING SLICES BACK
+    // ========================================================================
+    println!("\n3. FROM LATIN SLICES TO DEVANAGARI:");
+
+    let unicode = "नमस्ते";
+    let latin = transliterate::transliterate(unicode, Scheme::Iast);
+    println!("   segments of {unicode}:");
+    for segment in latin.segments() {
+        println!(
+            "      {:<8} {:<8} -> {:<4} {:?}",
+            &unicode[segment.source.clone()],
+            format!("{:?}", segment.source),
+            &latin.as_str()[segment.output.clone()],
+            segment.output
+        );
+    }
+
+    let ste = latin.as_str().find("ste").unwrap();
+    let range = ste..ste + 3;
+    println!(
+        "   \"ste\" at {range:?} comes from {:?} = \"{}\"",
+        latin.source_range(range.clone()).unwrap(),
+        latin.source_of(range.clone()).unwrap()
+    );
+    assert_eq!(latin.source_of(range), Some("स्ते"));
+
+    // The vowel sign is its own segment: "t" is त and "e" is े
+    assert_eq!(latin.source_of(5..6), Some("त"));
+    assert_eq!(latin.source_of(5..7), Some("ते"));
+
+    // And the other way: main.rs's safe_slice(unicode, 0, 3) is "न"
+    let na = latin.output_range(0..3).unwrap();
+    println!(
+        "   &unicode[0..3] = \"न\" becomes {na:?} = {:?}",
+        &latin.as_str()[na.clone()]
+    );
+    assert_eq!(&latin.as_str()[na], "na");
+    assert_eq!(latin.output_range(0..1), None);
+
+    // Multibyte output chars can't be split either
+    let sanskrit = transliterate::iast("संस्कृतम्");
+    println!(
+        "   in {sanskrit}, bytes 0..3 split ṃ: {:?}",
+        sanskrit.source_range(0..3)
+    );
+    assert_eq!(sanskrit.source_range(0..3), None);
+    assert_eq!(sanskrit.source_of(0..5), Some("सं"));
+}
+
+// The segments must cover both texts in order, with no gaps
+fn check_segments(latin: &Transliteration) {
+    let (mut source, mut output) = (0, 0);
+    for segment in latin.segments() {
+        assert_eq!(segment.source.start, source);
+        assert_eq!(segment.output.start, output);
+        source = segment.source.end;
+        output = segment.output.end;
+    }
+    assert_eq!(source, latin.source().len());
+    assert_eq!(output, latin.as_str().len());
+}
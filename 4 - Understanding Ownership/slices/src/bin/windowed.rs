@@ -0,0 +1,151 @@
+// Tumbling, sliding and hopping windows over the numbers from section 10
+// of main.rs, plus O(n) moving statistics timed against brute force. The
+// tests in windowed.rs check them against brute force.
+//
+// Run with optimizations for meaningful timings:
+//   cargo run --release --bin windowed
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use ownership::lcg::Lcg;
+use slices::windowed::{self, Windowing};
+
+fn main() {
+    println!("=== WINDOWED ===\n");
+
+    let numbers = [10, 20, 30, 40, 50, 60, 70, 80, 90];
+
+    // ========================================================================
+    // 1. WINDOW SHAPES
+    // ========================================================================
+    println!("1. WINDOWS ARE SUBSLICES:");
+    println!("   numbers: {numbers:?}");
+    println!("   &numbers[3..7]: {:?}", &numbers[3..7]);
+
+    for windowing in [
+        Windowing::Tumbling(4),
+        Windowing::Sliding(4),
+        Windowing::Hopping { size: 4, hop: 3 },
+    ] {
+        let windows: Vec<&[i32]> = windowed::windows(&numbers, windowing).collect();
+        println!("   {:<32} {windows:?}", format!("{windowing:?}"));
+    }
+
+    let pairs: Vec<&[i32; 2]> = windowed::array_windows(&numbers, 2).collect();
+    println!("   array_windows::<2>(hop 2): {pairs:?}");
+
+    // ========================================================================
+    // 2. MOVING STATISTICS
+    // ========================================================================
+    println!("\n2. MOVING SUM, MEAN, MIN AND MAX (Sliding(3)):");
+
+    let readings = [3, -1, 4, 1, -5, 9, 2, -6, 5, 3];
+    let sliding = Windowing::Sliding(3);
+    println!("   readings: {readings:?}");
+    println!("   sum:  {:?}", windowed::moving_sum(&readings, sliding));
+    println!("   mean: {:.2?}", windowed::moving_mean(&readings, sliding));
+    println!("   min:  {:?}", windowed::moving_min(&readings, sliding));
+    println!("   max:  {:?}", windowed::moving_max(&readings, sliding));
+
+    // No overflow: i64 values sum into i128
+    let big = [i64::MAX; 4];
+    let sums = windowed::moving_sum(&big, Windowing::Tumbling(2));
+    println!("   moving_sum([i64::MAX; 4], Tumbling(2)) = {sums:?}");
+
+    // A spike only affects the windows it is in
+    let spiky = [1e20, 1.0, 1.0, f64::INFINITY, 2.0, f64::NAN, 3.0, 4.0];
+    let sums = windowed::moving_sum(&spiky, Windowing::Sliding(2));
+    println!("   moving_sum({spiky:?}, Sliding(2)) = {sums:?}");
+
+    // ========================================================================
+    // 3. WINDOWS THAT MEET A PREDICATE
+    // ========================================================================
+    println!("\n3. SELECTING WINDOWS:");
+
+    let hot = windowed::windows_with_sum_above(&readings, sliding, 5);
+    println!("   windows with sum > 5: {hot:?}");
+
+    let swings: Vec<&[i32]> = windowed::windows_where(&readings, sliding, |w| {
+        w.iter().max().unwrap() - w.iter().min().unwrap() > 10
+    })
+    .collect();
+    println!("   windows swinging by more than 10: {swings:?}");
+
+    let temperatures = [21.5, 22.0, 23.5, 25.0, 24.5, 22.0, 20.5];
+    let warm = windowed::windows_with_sum_above(&temperatures, Windowing::Sliding(2), 47.0);
+    println!("   temperature pairs above 47.0 in total: {warm:?}");
+
+    // ========================================================================
+    // 4. CHUNKED REDUCE
+    // ========================================================================
+    println!("\n4. CHUNKED REDUCE:");
+
+    let totals = windowed::chunked_reduce(&numbers, 4, 0, |sum, &x| sum + x);
+    println!("   totals per 4: {totals:?}");
+    let spans = windowed::chunked_reduce(&readings, 3, (i32::MAX, i32::MIN), |(lo, hi), &x| {
+        (lo.min(x), hi.max(x))
+    });
+    println!("   (min, max) per 3: {spans:?}");
+
+    // ========================================================================
+    // 5. TIMINGS
+    // ========================================================================
+    let mut rng = Lcg::new(42);
+    let data: Vec<f64> = (0..1_000_000)
+        .map(|_| rng.below(10_000) as f64 / 100.0)
+        .collect();
+    let window = Windowing::Sliding(1000);
+    println!("\n5. TIMINGS (1,000,000 values, {window:?}):");
+
+    let start = Instant::now();
+    let brute: Vec<f64> = windowed::windows(&data, window)
+        .map(|w| w.iter().copied().fold(f64::MIN, f64::max))
+        .collect();
+    let brute_time = start.elapsed();
+    let start = Instant::now();
+    let deque = windowed::moving_max(black_box(&data), window);
+    let deque_time = start.elapsed();
+    assert_eq!(brute, deque);
+    println!("   f64 max, rescanning each window: {brute_time:>10.2?}");
+    println!(
+        "   f64 max, monotonic deque:        {deque_time:>10.2?}   ({:.0}x)",
+        brute_time.as_secs_f64() / deque_time.as_secs_f64()
+    );
+
+    let start = Instant::now();
+    let brute: Vec<f64> = windowed::windows(&data, window)
+        .map(|w| w.iter().sum())
+        .collect();
+    let brute_time = start.elapsed();
+    let start = Instant::now();
+    let running = windowed::moving_sum(black_box(&data), window);
+    let running_time = start.elapsed();
+    let worst = brute
+        .iter()
+        .zip(&running)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max);
+    assert!(worst < 1e-6, "running float sum off by {worst}");
+    println!("   f64 sum, rescanning each window: {brute_time:>10.2?}");
+    println!(
+        "   f64 sum, compensated total:      {running_time:>10.2?}   ({:.0}x)",
+        brute_time.as_secs_f64() / running_time.as_secs_f64()
+    );
+
+    let data: Vec<i64> = data.iter().map(|&x| (x * 100.0) as i64).collect();
+    let start = Instant::now();
+    let brute: Vec<i128> = windowed::windows(&data, window)
+        .map(|w| w.iter().map(|&x| x as i128).sum())
+        .collect();
+    let brute_time = start.elapsed();
+    let start = Instant::now();
+    let running = windowed::moving_sum(black_box(&data), window);
+    let running_time = start.elapsed();
+    assert_eq!(brute, running);
+    println!("   i64 sum, rescanning each window: {brute_time:>10.2?}");
+    println!(
+        "   i64 sum, running total:          {running_time:>10.2?}   ({:.0}x)",
+        brute_time.as_secs_f64() / running_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,124 @@
+// Word splitting that the b' ' scanners in main.rs get wrong.
+//
+//   cargo run --bin words             # demo
+//   cargo run --bin words TEXT...     # split your own strings
+
+use slices::words::{self, Punctuation};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        for text in &args {
+            println!("{text:?}");
+            for word in words::words(text) {
+                println!("   {:>3}..{:<3} {:?}", word.start, word.end, word.text);
+            }
+        }
+        return;
+    }
+
+    println!("=== WORDS ===\n");
+
+    // ========================================================================
+    // 1. WHITESPACE
+    // ========================================================================
+    println!("1. ANY UNICODE WHITESPACE SEPARATES WORDS:");
+
+    let samples: [(&str, &[&str]); 6] = [
+        ("hello world", &["hello", "world"]),
+        ("hello\tworld\nagain", &["hello", "world", "again"]),
+        ("  hello    world  ", &["hello", "world"]),
+        ("hello\u{A0}world", &["hello", "world"]),
+        ("日本語\u{3000}テキスト", &["日本語", "テキスト"]),
+        (" \t\r\n ", &[]),
+    ];
+    for (text, expected) in samples {
+        let found: Vec<&str> = words::words(text).texts().collect();
+        println!("   {:<28} -> {found:?}", format!("{text:?}"));
+        assert_eq!(found, expected, "{text:?}");
+    }
+
+    // ========================================================================
+    // 2. SPANS
+    // ========================================================================
+    println!("\n2. BYTE SPANS (ZERO-COPY):");
+
+    let text = "naïve  café\tdéjà vu";
+    println!("   text: {text:?}");
+    for word in words::words(text) {
+        println!("   {:>2}..{:<2} {:?}", word.start, word.end, word.text);
+        // Every word is a slice of the original, not a copy
+        assert_eq!(&text[word.span()], word.text);
+        assert_eq!(word.text.as_ptr(), text[word.start..].as_ptr());
+    }
+
+    // ========================================================================
+    // 3. PUNCTUATION
+    // ========================================================================
+    println!("\n3. PUNCTUATION:");
+
+    let text = "\"Hello,\" she said — don't panic... ¿Qué?";
+    println!("   text: {text:?}");
+    let expected: [(Punctuation, &[&str]); 3] = [
+        (
+            Punctuation::Keep,
+            &[
+                "\"Hello,\"",
+                "she",
+                "said",
+                "—",
+                "don't",
+                "panic...",
+                "¿Qué?",
+            ],
+        ),
+        (
+            Punctuation::Trim,
+            &["Hello", "she", "said", "don't", "panic", "Qué"],
+        ),
+        (
+            Punctuation::Split,
+            &["Hello", "she", "said", "don", "t", "panic", "Qué"],
+        ),
+    ];
+    for (punctuation, expected) in expected {
+        let found: Vec<&str> = words::words(text)
+            .punctuation(punctuation)
+            .texts()
+            .collect();
+        println!("   {:<6} {found:?}", format!("{punctuation:?}"));
+        assert_eq!(found, expected);
+    }
+
+    // Custom set: only hyphens split
+    let found: Vec<&str> = words::words("well-known e-mail, co-op")
+        .punctuation(Punctuation::Split)
+        .punctuation_chars(|c| c == '-')
+        .texts()
+        .collect();
+    println!("   Split on '-' only: {found:?}");
+    assert_eq!(found, ["well", "known", "e", "mail,", "co", "op"]);
+
+    // Trimmed spans point at the word itself, not its punctuation
+    let word = words::words("(quoted)")
+        .punctuation(Punctuation::Trim)
+        .next()
+        .unwrap();
+    assert_eq!((word.text, word.span()), ("quoted", 1..7));
+
+    // ========================================================================
+    // 4. NTH WORD
+    // ========================================================================
+    println!("\n4. nth_word:");
+
+    let text = "first\tsecond\n\nthird";
+    for n in 0..4 {
+        println!(
+            "   nth_word({text:?}, {n}) = {:?}",
+            words::nth_word(text, n)
+        );
+    }
+    assert_eq!(words::nth_word(text, 2), Some("third"));
+    assert_eq!(words::nth_word(text, 3), None);
+    assert_eq!(words::nth_word("", 0), None);
+}
@@ -0,0 +1,8 @@
+pub mod normalize;
+pub mod search;
+pub mod text_arena;
+pub mod text_stats;
+pub mod timestamp;
+pub mod transliterate;
+pub mod windowed;
+pub mod words;
@@ -0,0 +1,286 @@
+// Unicode normalization: NFC, NFD, NFKC and NFKD (UAX #15).
+//
+// "é" can be written as one char (U+00E9) or as "e" followed by a combining
+// acute accent (U+0065 U+0301). Both render the same, but `==` compares
+// bytes, so they are unequal, and `safe_slice(s, 0, 2)` takes the whole of
+// one and half of the other. Normalizing first gives every canonically
+// equivalent string the same chars:
+//
+//     NFD   decompose:              "é" -> "e" + U+0301
+//     NFC   decompose, recompose:   "e" + U+0301 -> "é"
+//     NFKD  also unfold compatibility chars: "ﬁ" -> "fi", "①" -> "1"
+//     NFKC  NFKD, then recompose
+//
+// Decomposition looks chars up in tables generated from the Unicode data by
+// data/gen_normalization.py, then sorts each run of combining marks by
+// combining class. Hangul syllables are decomposed and composed with
+// arithmetic instead of tables.
+
+use std::borrow::Cow;
+
+#[rustfmt::skip]
+mod tables;
+
+pub use tables::UNICODE_VERSION;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl Form {
+    pub const ALL: [Form; 4] = [Form::Nfc, Form::Nfd, Form::Nfkc, Form::Nfkd];
+
+    fn compat(self) -> bool {
+        matches!(self, Form::Nfkc | Form::Nfkd)
+    }
+
+    fn composed(self) -> bool {
+        matches!(self, Form::Nfc | Form::Nfkc)
+    }
+}
+
+/// Result of `quick_check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsNormalized {
+    Yes,
+    No,
+    /// Can't tell without normalizing
+    Maybe,
+}
+
+// ============================================================================
+// Character properties
+// ============================================================================
+
+const S_BASE: u32 = 0xAC00;
+const L_BASE: u32 = 0x1100;
+const V_BASE: u32 = 0x1161;
+const T_BASE: u32 = 0x11A7;
+const L_COUNT: u32 = 19;
+const V_COUNT: u32 = 21;
+const T_COUNT: u32 = 28;
+const N_COUNT: u32 = V_COUNT * T_COUNT;
+const S_COUNT: u32 = L_COUNT * N_COUNT;
+
+/// Canonical combining class; 0 for starters
+pub fn combining_class(c: char) -> u8 {
+    let c = c as u32;
+    tables::COMBINING_CLASS
+        .binary_search_by(|&(first, last, _)| {
+            if last < c {
+                std::cmp::Ordering::Less
+            } else if first > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .map_or(0, |i| tables::COMBINING_CLASS[i].2)
+}
+
+fn in_ranges(ranges: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    let i = ranges.partition_point(|&(_, last)| last < c);
+    ranges.get(i).is_some_and(|&(first, _)| first <= c)
+}
+
+fn lookup(table: &'static [(char, &'static str)], c: char) -> Option<&'static str> {
+    table
+        .binary_search_by_key(&c, |&(key, _)| key)
+        .ok()
+        .map(|i| table[i].1)
+}
+
+fn decompose_char(c: char, compat: bool, out: &mut Vec<char>) {
+    let s = (c as u32).wrapping_sub(S_BASE);
+    if s < S_COUNT {
+        let l = L_BASE + s / N_COUNT;
+        let v = V_BASE + s % N_COUNT / T_COUNT;
+        let t = T_BASE + s % T_COUNT;
+        out.extend([l, v].map(|c| char::from_u32(c).unwrap()));
+        if t != T_BASE {
+            out.push(char::from_u32(t).unwrap());
+        }
+        return;
+    }
+    let full = compat
+        .then(|| lookup(tables::COMPATIBILITY, c))
+        .flatten()
+        .or_else(|| lookup(tables::CANONICAL, c));
+    match full {
+        Some(full) => out.extend(full.chars()),
+        None => out.push(c),
+    }
+}
+
+fn compose_pair(first: char, second: char) -> Option<char> {
+    let (a, b) = (first as u32, second as u32);
+    // Hangul L + V -> LV syllable
+    if (L_BASE..L_BASE + L_COUNT).contains(&a) && (V_BASE..V_BASE + V_COUNT).contains(&b) {
+        let s = S_BASE + ((a - L_BASE) * V_COUNT + (b - V_BASE)) * T_COUNT;
+        return char::from_u32(s);
+    }
+    // Hangul LV + T -> LVT syllable
+    let s = a.wrapping_sub(S_BASE);
+    if s < S_COUNT && s % T_COUNT == 0 && (T_BASE + 1..T_BASE + T_COUNT).contains(&b) {
+        return char::from_u32(a + b - T_BASE);
+    }
+    tables::COMPOSITIONS
+        .binary_search_by_key(&(first, second), |&(a, b, _)| (a, b))
+        .ok()
+        .map(|i| tables::COMPOSITIONS[i].2)
+}
+
+// ============================================================================
+// Normalization
+// ============================================================================
+
+fn decompose(text: &str, compat: bool) -> Vec<char> {
+    let mut chars = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        decompose_char(c, compat, &mut chars);
+    }
+    // Canonical ordering: sort each run of non-starters by class. The sort
+    // is stable, so marks of the same class keep their order.
+    let mut start = 0;
+    while start < chars.len() {
+        if combining_class(chars[start]) == 0 {
+            start += 1;
+            continue;
+        }
+        let run = chars[start..]
+            .iter()
+            .position(|&c| combining_class(c) == 0)
+            .map_or(chars.len(), |n| start + n);
+        chars[start..run].sort_by_key(|&c| combining_class(c));
+        start = run;
+    }
+    chars
+}
+
+// Canonical composition: each char joins the last starter if nothing
+// in between blocks it (a starter, or a mark of the same or higher class)
+fn compose(chars: Vec<char>) -> Vec<char> {
+    let mut out: Vec<char> = Vec::with_capacity(chars.len());
+    let mut starter: Option<usize> = None;
+    let mut last_class = 0;
+    for c in chars {
+        let class = combining_class(c);
+        if let Some(i) = starter {
+            let adjacent = out.len() == i + 1;
+            if (adjacent || (last_class != 0 && last_class < class))
+                && let Some(composite) = compose_pair(out[i], c)
+            {
+                out[i] = composite;
+                continue;
+            }
+        }
+        if class == 0 {
+            starter = Some(out.len());
+        }
+        last_class = class;
+        out.push(c);
+    }
+    out
+}
+
+/// `text` in `form`, borrowed if it already is
+pub fn normalize(text: &str, form: Form) -> Cow<'_, str> {
+    if quick_check(text, form) == IsNormalized::Yes {
+        return Cow::Borrowed(text);
+    }
+    let chars = decompose(text, form.compat());
+    let chars = if form.composed() {
+        compose(chars)
+    } else {
+        chars
+    };
+    Cow::Owned(chars.into_iter().collect())
+}
+
+pub fn nfc(text: &str) -> Cow<'_, str> {
+    normalize(text, Form::Nfc)
+}
+
+pub fn nfd(text: &str) -> Cow<'_, str> {
+    normalize(text, Form::Nfd)
+}
+
+pub fn nfkc(text: &str) -> Cow<'_, str> {
+    normalize(text, Form::Nfkc)
+}
+
+pub fn nfkd(text: &str) -> Cow<'_, str> {
+    normalize(text, Form::Nfkd)
+}
+
+// ============================================================================
+// Checks and comparison
+// ============================================================================
+
+/// The quick check from UAX #15: a single pass over the chars that can say
+/// Yes or No for most text and Maybe for the rest
+pub fn quick_check(text: &str, form: Form) -> IsNormalized {
+    let mut result = IsNormalized::Yes;
+    let mut last_class = 0;
+    for c in text.chars() {
+        // Plain ASCII is normalized in every form
+        if c.is_ascii() {
+            last_class = 0;
+            continue;
+        }
+        let class = combining_class(c);
+        if class != 0 && last_class > class {
+            return IsNormalized::No;
+        }
+        last_class = class;
+        let decomposes = || {
+            let s = (c as u32).wrapping_sub(S_BASE);
+            s < S_COUNT
+                || lookup(tables::CANONICAL, c).is_some()
+                || (form.compat() && lookup(tables::COMPATIBILITY, c).is_some())
+        };
+        match form {
+            Form::Nfd | Form::Nfkd if decomposes() => return IsNormalized::No,
+            Form::Nfd | Form::Nfkd => {}
+            Form::Nfc | Form::Nfkc => {
+                let never = match form {
+                    Form::Nfc => tables::NFC_QC_NO,
+                    _ => tables::NFKC_QC_NO,
+                };
+                if in_ranges(never, c) {
+                    return IsNormalized::No;
+                }
+                if in_ranges(tables::COMPOSES_WITH_PREVIOUS, c) {
+                    result = IsNormalized::Maybe;
+                }
+            }
+        }
+    }
+    result
+}
+
+pub fn is_normalized(text: &str, form: Form) -> bool {
+    match quick_check(text, form) {
+        IsNormalized::Yes => true,
+        IsNormalized::No => false,
+        IsNormalized::Maybe => normalize(text, form) == text,
+    }
+}
+
+/// Equal up to canonical equivalence: "é" == "e\u{301}", but "ﬁ" != "fi"
+pub fn canonical_eq(a: &str, b: &str) -> bool {
+    a == b || nfd(a) == nfd(b)
+}
+
+/// Equal up to compatibility equivalence: also "ﬁ" == "fi" and "①" == "1"
+pub fn compatible_eq(a: &str, b: &str) -> bool {
+    a == b || nfkd(a) == nfkd(b)
+}
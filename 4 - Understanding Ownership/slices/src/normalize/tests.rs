@@ -0,0 +1,136 @@
+// Conformance with Unicode's NormalizationTest.txt.
+//
+// Each line is `c1;c2;c3;c4;c5;` (source, NFC, NFD, NFKC, NFKD) and must
+// satisfy the invariants listed in the header of the official file. Every
+// code point that is not listed in @Part1 must normalize to itself.
+//
+// data/NormalizationTest.txt is the official file for UNICODE_VERSION,
+// fetched by data/gen_normalization.py along with the tables.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::{Form, UNICODE_VERSION, is_normalized, normalize};
+
+const SHOW: usize = 10;
+
+fn read_data(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("data")
+        .join(name);
+    match fs::read_to_string(&path) {
+        Ok(data) => data,
+        Err(err) => panic!("cannot read {}: {err}", path.display()),
+    }
+}
+
+// Checks every line of a NormalizationTest.txt-format file and returns the
+// number of lines and a description of each failed check
+fn check(data: &str) -> (usize, Vec<String>) {
+    let mut failures = Vec::new();
+    let mut part = "";
+    let mut checked = 0;
+    let mut part1 = HashSet::new();
+
+    for (number, line) in data.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('@') {
+            part = name;
+            continue;
+        }
+        let columns: Vec<String> = line.split(';').take(5).map(parse_hex).collect();
+        let [c1, c2, c3, c4, c5] = columns.as_slice() else {
+            panic!("line {}: expected 5 columns", number + 1);
+        };
+        if part == "Part1" {
+            part1.insert(c1.chars().next().unwrap());
+        }
+        checked += 1;
+
+        let mut check = |form: Form, input: &str, expected: &str| {
+            let got = normalize(input, form);
+            let is = is_normalized(input, form);
+            if got != expected || is != (input == expected) {
+                failures.push(format!(
+                    "line {}: {form:?}({}) = {} (is_normalized {is}), expected {}",
+                    number + 1,
+                    hex(input),
+                    hex(&got),
+                    hex(expected)
+                ));
+            }
+        };
+        for input in [c1, c2, c3] {
+            check(Form::Nfc, input, c2);
+            check(Form::Nfd, input, c3);
+        }
+        for input in [c4, c5] {
+            check(Form::Nfc, input, c4);
+            check(Form::Nfd, input, c5);
+        }
+        for input in [c1, c2, c3, c4, c5] {
+            check(Form::Nfkc, input, c4);
+            check(Form::Nfkd, input, c5);
+        }
+    }
+
+    assert!(!part1.is_empty(), "no @Part1 section");
+    let mut buffer = [0; 4];
+    for c in (0..=0x10FFFF).filter_map(char::from_u32) {
+        if part1.contains(&c) {
+            continue;
+        }
+        let text: &str = c.encode_utf8(&mut buffer);
+        for form in Form::ALL {
+            if normalize(text, form) != text || !is_normalized(text, form) {
+                failures.push(format!("{form:?}(U+{:04X}) is not itself", c as u32));
+            }
+        }
+    }
+    (checked, failures)
+}
+
+fn assert_conforms(data: &str) {
+    let (lines, failures) = check(data);
+    assert!(lines > 0, "no test lines");
+    assert!(
+        failures.is_empty(),
+        "{} checks failed over {lines} lines:\n{}",
+        failures.len(),
+        failures[..failures.len().min(SHOW)].join("\n")
+    );
+}
+
+fn parse_hex(column: &str) -> String {
+    column
+        .split_whitespace()
+        .map(|code| {
+            u32::from_str_radix(code, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .unwrap_or_else(|| panic!("bad code point {code:?}"))
+        })
+        .collect()
+}
+
+fn hex(text: &str) -> String {
+    text.chars()
+        .map(|c| format!("{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn official_conformance() {
+    let data = read_data("NormalizationTest.txt");
+    let first = data.lines().next().unwrap_or_default();
+    assert!(
+        first.contains(&format!("NormalizationTest-{UNICODE_VERSION}.txt")),
+        "{first:?} is not the test file for Unicode {UNICODE_VERSION}"
+    );
+    assert_conforms(&data);
+}
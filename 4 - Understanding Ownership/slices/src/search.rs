@@ -0,0 +1,346 @@
+// Substring and byte search over `&[u8]` and `&str`.
+//
+// The lesson's `first_word` compares one byte per loop iteration. For a
+// single byte, `find_byte` compares eight at a time instead: XOR a 64-bit
+// word with the byte repeated eight times, and every matching byte becomes
+// zero, which a couple of additions and masks can detect (SWAR, "SIMD within
+// a register"). Longer patterns use Boyer-Moore-Horspool: compare the last
+// byte of the window first, and on a mismatch skip ahead by how far that
+// byte is from the end of the pattern, often the whole pattern length.
+//
+// Byte functions return offsets and ranges; the `str` functions return
+// slices of the haystack, so nothing is copied.
+
+use std::ops::Range;
+
+const WORD: usize = size_of::<u64>();
+const LOW_SEVEN: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+fn splat(byte: u8) -> u64 {
+    u64::from_le_bytes([byte; WORD])
+}
+
+// High bit set in every byte of `word` that is zero. Exact: unlike the
+// shorter `(x - 0x01..) & !x & 0x80..`, no borrow leaks into higher bytes,
+// so the highest flag can be trusted as well as the lowest.
+fn zero_bytes(word: u64) -> u64 {
+    !(((word & LOW_SEVEN) + LOW_SEVEN) | word | LOW_SEVEN)
+}
+
+fn load(chunk: &[u8]) -> u64 {
+    u64::from_le_bytes(chunk.try_into().unwrap())
+}
+
+// ============================================================================
+// Single bytes
+// ============================================================================
+
+/// Offset of the first `byte` in `haystack`, eight bytes per step
+pub fn find_byte(haystack: &[u8], byte: u8) -> Option<usize> {
+    let pattern = splat(byte);
+    let mut chunks = haystack.chunks_exact(WORD);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let found = zero_bytes(load(chunk) ^ pattern);
+        if found != 0 {
+            // Little-endian: byte k of the chunk is bits 8k..8k+8
+            return Some(i * WORD + found.trailing_zeros() as usize / 8);
+        }
+    }
+    let tail = chunks.remainder();
+    let tail_start = haystack.len() - tail.len();
+    tail.iter().position(|&b| b == byte).map(|i| tail_start + i)
+}
+
+/// Offset of the last `byte` in `haystack`
+pub fn rfind_byte(haystack: &[u8], byte: u8) -> Option<usize> {
+    let pattern = splat(byte);
+    let mut chunks = haystack.rchunks_exact(WORD);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let found = zero_bytes(load(chunk) ^ pattern);
+        if found != 0 {
+            let start = haystack.len() - (i + 1) * WORD;
+            return Some(start + WORD - 1 - found.leading_zeros() as usize / 8);
+        }
+    }
+    chunks.remainder().iter().rposition(|&b| b == byte)
+}
+
+// ============================================================================
+// Patterns
+// ============================================================================
+
+/// A pattern prepared for repeated searches
+#[derive(Debug, Clone)]
+pub struct Finder<'n> {
+    needle: &'n [u8],
+    /// How far to slide forward when `byte` is under the window's last byte
+    skip: [usize; 256],
+    /// How far to slide back when `byte` is under the window's first byte
+    skip_back: [usize; 256],
+}
+
+impl<'n> Finder<'n> {
+    pub fn new(needle: &'n [u8]) -> Finder<'n> {
+        let len = needle.len();
+        let mut skip = [len; 256];
+        let mut skip_back = [len; 256];
+        if len > 0 {
+            // The last occurrence before the final byte wins
+            for (i, &b) in needle[..len - 1].iter().enumerate() {
+                skip[b as usize] = len - 1 - i;
+            }
+            // The first occurrence after byte 0 wins
+            for (i, &b) in needle.iter().enumerate().skip(1).rev() {
+                skip_back[b as usize] = i;
+            }
+        }
+        Finder {
+            needle,
+            skip,
+            skip_back,
+        }
+    }
+
+    pub fn needle(&self) -> &'n [u8] {
+        self.needle
+    }
+
+    /// First occurrence at or after `from`
+    fn find_from(&self, haystack: &[u8], from: usize) -> Option<usize> {
+        let needle = self.needle;
+        match needle.len() {
+            0 => return (from <= haystack.len()).then_some(from),
+            1 => return find_byte(&haystack[from..], needle[0]).map(|i| from + i),
+            _ => {}
+        }
+        let last = needle.len() - 1;
+        let mut start = from;
+        while start + last < haystack.len() {
+            let byte = haystack[start + last];
+            if byte == needle[last] && haystack[start..start + last] == needle[..last] {
+                return Some(start);
+            }
+            start += self.skip[byte as usize];
+        }
+        None
+    }
+
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_from(haystack, 0)
+    }
+
+    pub fn rfind(&self, haystack: &[u8]) -> Option<usize> {
+        let needle = self.needle;
+        match needle.len() {
+            0 => return Some(haystack.len()),
+            1 => return rfind_byte(haystack, needle[0]),
+            _ => {}
+        }
+        let mut end = haystack.len();
+        while end >= needle.len() {
+            let start = end - needle.len();
+            let byte = haystack[start];
+            if byte == needle[0] && haystack[start + 1..end] == needle[1..] {
+                return Some(start);
+            }
+            end -= self.skip_back[byte as usize];
+        }
+        None
+    }
+
+    /// Non-overlapping matches, left to right
+    pub fn find_iter<'h>(&'h self, haystack: &'h [u8]) -> FindIter<'h, 'n> {
+        FindIter {
+            finder: self,
+            haystack,
+            position: 0,
+        }
+    }
+}
+
+/// Ranges of non-overlapping matches, from `Finder::find_iter`
+#[derive(Debug, Clone)]
+pub struct FindIter<'h, 'n> {
+    finder: &'h Finder<'n>,
+    haystack: &'h [u8],
+    position: usize,
+}
+
+impl Iterator for FindIter<'_, '_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        if self.position > self.haystack.len() {
+            return None;
+        }
+        let start = self.finder.find_from(self.haystack, self.position)?;
+        let end = start + self.finder.needle.len();
+        // An empty needle matches everywhere; step past each match
+        self.position = if start == end { end + 1 } else { end };
+        Some(start..end)
+    }
+}
+
+// ============================================================================
+// One-off searches
+// ============================================================================
+
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<Range<usize>> {
+    let start = Finder::new(needle).find(haystack)?;
+    Some(start..start + needle.len())
+}
+
+pub fn rfind(haystack: &[u8], needle: &[u8]) -> Option<Range<usize>> {
+    let start = Finder::new(needle).rfind(haystack)?;
+    Some(start..start + needle.len())
+}
+
+pub fn find_all(haystack: &[u8], needle: &[u8]) -> Vec<Range<usize>> {
+    Finder::new(needle).find_iter(haystack).collect()
+}
+
+/// The first match, as a slice of `haystack`
+pub fn find_str<'h>(haystack: &'h str, needle: &str) -> Option<&'h str> {
+    // A valid UTF-8 needle can only match on char boundaries
+    find(haystack.as_bytes(), needle.as_bytes()).map(|range| &haystack[range])
+}
+
+/// The last match, as a slice of `haystack`
+pub fn rfind_str<'h>(haystack: &'h str, needle: &str) -> Option<&'h str> {
+    rfind(haystack.as_bytes(), needle.as_bytes()).map(|range| &haystack[range])
+}
+
+/// Every non-overlapping match with its byte offset, like `str::match_indices`
+pub fn match_indices<'h>(haystack: &'h str, needle: &str) -> Vec<(usize, &'h str)> {
+    find_all(haystack.as_bytes(), needle.as_bytes())
+        .into_iter()
+        // Only an empty needle can "match" inside a char
+        .filter(|range| haystack.is_char_boundary(range.start))
+        .map(|range| (range.start, &haystack[range]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ownership::lcg::Lcg;
+
+    use super::*;
+
+    fn naive_find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        (0..=haystack.len().checked_sub(needle.len())?).find(|&i| haystack[i..].starts_with(needle))
+    }
+
+    fn naive_rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        (0..=haystack.len().checked_sub(needle.len())?)
+            .rev()
+            .find(|&i| haystack[i..].starts_with(needle))
+    }
+
+    fn naive_find_all(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+        let mut found = Vec::new();
+        let mut i = 0;
+        while i + needle.len() <= haystack.len() {
+            if haystack[i..].starts_with(needle) {
+                found.push(i);
+                i += needle.len().max(1);
+            } else {
+                i += 1;
+            }
+        }
+        found
+    }
+
+    // Every length and alignment around the 8-byte word size
+    #[test]
+    fn bytes_match_position() {
+        let mut rng = Lcg::new(42);
+        for len in 0..40 {
+            for _ in 0..50 {
+                let haystack: Vec<u8> = (0..len).map(|_| b"ab\x00\x80\xff"[rng.below(5)]).collect();
+                for byte in [b'a', 0x00, 0x80, 0xff, 0x01] {
+                    let first = haystack.iter().position(|&b| b == byte);
+                    let last = haystack.iter().rposition(|&b| b == byte);
+                    assert_eq!(find_byte(&haystack, byte), first, "{haystack:?}");
+                    assert_eq!(rfind_byte(&haystack, byte), last, "{haystack:?}");
+                }
+            }
+        }
+    }
+
+    // A lone match in every position of a haystack a few words long
+    #[test]
+    fn a_single_byte_anywhere() {
+        for len in 1..33 {
+            for at in 0..len {
+                let mut haystack = vec![b'.'; len];
+                haystack[at] = b'x';
+                assert_eq!(find_byte(&haystack, b'x'), Some(at));
+                assert_eq!(rfind_byte(&haystack, b'x'), Some(at));
+            }
+        }
+        assert_eq!(find_byte(b"", b'x'), None);
+        assert_eq!(rfind_byte(b"", b'x'), None);
+    }
+
+    // A small alphabet gives plenty of partial and overlapping matches
+    #[test]
+    fn finder_matches_naive_search() {
+        let mut rng = Lcg::new(7);
+        for _ in 0..5000 {
+            let haystack: Vec<u8> = (0..rng.below(60)).map(|_| b"aab"[rng.below(3)]).collect();
+            let needle: Vec<u8> = (0..rng.below(6)).map(|_| b"ab"[rng.below(2)]).collect();
+            let finder = Finder::new(&needle);
+            assert_eq!(finder.find(&haystack), naive_find(&haystack, &needle));
+            assert_eq!(finder.rfind(&haystack), naive_rfind(&haystack, &needle));
+            let all: Vec<usize> = finder.find_iter(&haystack).map(|r| r.start).collect();
+            assert_eq!(all, naive_find_all(&haystack, &needle), "{needle:?}");
+        }
+    }
+
+    // At the start, at the end, and straddling each 8-byte word boundary
+    #[test]
+    fn needles_at_the_edges() {
+        let filler = "abcdefghijklmnopqrstuvwxyz0123456789";
+        for needle in ["@", "#!", "needle", "a longer needle!"] {
+            for at in 0..=filler.len() {
+                let text = format!("{}{needle}{}", &filler[..at], &filler[at..]);
+                assert_eq!(
+                    find_str(&text, needle).map(|s| s.as_ptr()),
+                    Some(text[at..].as_ptr())
+                );
+                assert_eq!(text.find(needle), Some(at));
+                assert_eq!(
+                    rfind_str(&text, needle).map(|s| s.as_ptr()),
+                    Some(text[at..].as_ptr())
+                );
+                assert_eq!(
+                    find(text.as_bytes(), needle.as_bytes()),
+                    Some(at..at + needle.len())
+                );
+                assert_eq!(
+                    rfind(text.as_bytes(), needle.as_bytes()),
+                    Some(at..at + needle.len())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn strings_agree_with_str() {
+        let text = "ownership — borrowing — slices — नमस्ते — 🦀 — ";
+        for needle in ["—", " — ", "slices", "नम", "🦀", "", "missing", "own", "— "] {
+            assert_eq!(
+                find_str(text, needle).map(|s| s.as_ptr()),
+                text.find(needle).map(|i| text[i..].as_ptr())
+            );
+            assert_eq!(
+                rfind_str(text, needle).map(|s| s.as_ptr()),
+                text.rfind(needle).map(|i| text[i..].as_ptr())
+            );
+            let std: Vec<(usize, &str)> = text.match_indices(needle).collect();
+            assert_eq!(match_indices(text, needle), std, "{needle:?}");
+        }
+        assert_eq!(find_all(b"aaaa", b"aa"), [0..2, 2..4]);
+        assert_eq!(find(b"ab", b"abc"), None);
+    }
+}
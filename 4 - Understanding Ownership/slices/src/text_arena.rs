@@ -0,0 +1,496 @@
+// Span handles into texts that can still be edited.
+//
+// Section 6 of main.rs: `s5.clear()` is refused while `word = first_word(&s5)`
+// is alive, because `word` borrows `s5`. That is the right call for a `&str`,
+// but tooling that wants to remember word positions across edits ends up
+// fighting lifetimes. Here the arena owns the strings and hands out `Span`s:
+// plain `Copy` values holding a `TextId`, a byte range and the version of the
+// text they were made at. Nothing is borrowed, so the text can be edited at
+// any time. Every edit is logged, and a span from an older version is
+// replayed through the log when it is resolved:
+//
+//     edit entirely before the span  -> the span moves
+//     edit entirely after the span   -> the span stays
+//     edit overlapping the span      -> the span is stale
+//
+// Removing a text and reusing its slot bumps a generation number, so an old
+// `TextId` can't resolve into the new text.
+//
+// The log keeps every edit, and resolving a span replays everything since
+// the span was made, so a text edited for a long time grows a long log and
+// old spans get slow. `forget_edits` drops the log up to a version; spans
+// older than that stop resolving with `SpanError::Expired`.
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use crate::words;
+
+/// Handle to a text in a `TextArena`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextId {
+    index: u32,
+    generation: u32,
+}
+
+/// A byte range of a text, as of one version of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    text: TextId,
+    start: usize,
+    end: usize,
+    version: usize,
+}
+
+impl Span {
+    pub fn text(&self) -> TextId {
+        self.text
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The version of the text this range refers to
+    pub fn version(&self) -> usize {
+        self.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanError {
+    /// The text was removed (or its slot reused)
+    UnknownText(TextId),
+    /// Out of bounds or not on char boundaries
+    InvalidRange { range: Range<usize>, len: usize },
+    /// An edit overlapped the span; `version` is the edit that did it
+    Stale { span: Span, version: usize },
+    /// The edits since the span was made were forgotten; `oldest` is the
+    /// oldest version that still resolves
+    Expired { span: Span, oldest: usize },
+}
+
+impl fmt::Display for SpanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpanError::UnknownText(id) => write!(f, "text {} no longer exists", id.index),
+            SpanError::InvalidRange { range, len } => write!(
+                f,
+                "{}..{} is not a char-aligned range of a {len}-byte text",
+                range.start, range.end
+            ),
+            SpanError::Stale { span, version } => write!(
+                f,
+                "span {}..{} (version {}) was overwritten by edit {version}",
+                span.start, span.end, span.version
+            ),
+            SpanError::Expired { span, oldest } => write!(
+                f,
+                "span {}..{} (version {}) is older than the edit log (version {oldest})",
+                span.start, span.end, span.version
+            ),
+        }
+    }
+}
+
+impl Error for SpanError {}
+
+// One logged edit: `removed` bytes were replaced by `inserted` bytes
+#[derive(Debug, Clone)]
+struct Edit {
+    removed: Range<usize>,
+    inserted: usize,
+}
+
+#[derive(Debug)]
+struct Entry {
+    text: String,
+    /// Edits from version `first` on; `first + edits.len()` is the
+    /// current version
+    edits: Vec<Edit>,
+    first: usize,
+}
+
+impl Entry {
+    fn version(&self) -> usize {
+        self.first + self.edits.len()
+    }
+}
+
+#[derive(Debug)]
+struct Slot {
+    generation: u32,
+    entry: Option<Entry>,
+}
+
+// ============================================================================
+// TextArena
+// ============================================================================
+
+/// Owns texts and resolves spans into them
+#[derive(Debug, Default)]
+pub struct TextArena {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+}
+
+impl TextArena {
+    pub fn new() -> TextArena {
+        TextArena::default()
+    }
+
+    pub fn insert(&mut self, text: impl Into<String>) -> TextId {
+        let entry = Some(Entry {
+            text: text.into(),
+            edits: Vec::new(),
+            first: 0,
+        });
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.generation += 1;
+                slot.entry = entry;
+                TextId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many texts");
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry,
+                });
+                TextId {
+                    index,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Takes the text out; its id and spans stop resolving
+    pub fn remove(&mut self, id: TextId) -> Option<String> {
+        self.entry(id).ok()?;
+        self.free.push(id.index);
+        let entry = self.slots[id.index as usize].entry.take()?;
+        Some(entry.text)
+    }
+
+    pub fn get(&self, id: TextId) -> Option<&str> {
+        self.entry(id).ok().map(|entry| entry.text.as_str())
+    }
+
+    /// Number of edits made to the text so far
+    pub fn version(&self, id: TextId) -> Option<usize> {
+        self.entry(id).ok().map(Entry::version)
+    }
+
+    /// Drops the logged edits made before `version`, so spans from before
+    /// it no longer resolve. Returns how many edits were dropped.
+    pub fn forget_edits(&mut self, id: TextId, version: usize) -> Result<usize, SpanError> {
+        let entry = self.entry_mut(id)?;
+        let count = version.saturating_sub(entry.first).min(entry.edits.len());
+        entry.edits.drain(..count);
+        entry.first += count;
+        Ok(count)
+    }
+
+    /// Number of live texts
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn entry(&self, id: TextId) -> Result<&Entry, SpanError> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.entry.as_ref())
+            .ok_or(SpanError::UnknownText(id))
+    }
+
+    fn entry_mut(&mut self, id: TextId) -> Result<&mut Entry, SpanError> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.entry.as_mut())
+            .ok_or(SpanError::UnknownText(id))
+    }
+
+    // ========================================================================
+    // Edits
+    // ========================================================================
+
+    /// Replaces `range` with `with`, like `String::replace_range`
+    pub fn replace(
+        &mut self,
+        id: TextId,
+        range: Range<usize>,
+        with: &str,
+    ) -> Result<(), SpanError> {
+        let entry = self.entry_mut(id)?;
+        check_range(&entry.text, &range)?;
+        entry.text.replace_range(range.clone(), with);
+        entry.edits.push(Edit {
+            removed: range,
+            inserted: with.len(),
+        });
+        Ok(())
+    }
+
+    pub fn insert_str(&mut self, id: TextId, at: usize, text: &str) -> Result<(), SpanError> {
+        self.replace(id, at..at, text)
+    }
+
+    pub fn delete(&mut self, id: TextId, range: Range<usize>) -> Result<(), SpanError> {
+        self.replace(id, range, "")
+    }
+
+    pub fn push_str(&mut self, id: TextId, text: &str) -> Result<(), SpanError> {
+        let end = self.entry(id)?.text.len();
+        self.replace(id, end..end, text)
+    }
+
+    pub fn clear(&mut self, id: TextId) -> Result<(), SpanError> {
+        let end = self.entry(id)?.text.len();
+        self.replace(id, 0..end, "")
+    }
+
+    // ========================================================================
+    // Spans
+    // ========================================================================
+
+    /// A span of the text as it is now
+    pub fn span(&self, id: TextId, range: Range<usize>) -> Result<Span, SpanError> {
+        let entry = self.entry(id)?;
+        check_range(&entry.text, &range)?;
+        Ok(Span {
+            text: id,
+            start: range.start,
+            end: range.end,
+            version: entry.version(),
+        })
+    }
+
+    /// A span for every whitespace-separated word, in order
+    pub fn word_spans(&self, id: TextId) -> Result<Vec<Span>, SpanError> {
+        let entry = self.entry(id)?;
+        let version = entry.version();
+        Ok(words::words(&entry.text)
+            .map(|word| Span {
+                text: id,
+                start: word.start,
+                end: word.end,
+                version,
+            })
+            .collect())
+    }
+
+    /// The span moved up to the current version, or why it can't be
+    pub fn rebase(&self, span: Span) -> Result<Span, SpanError> {
+        let entry = self.entry(span.text)?;
+        let Some(skip) = span.version.checked_sub(entry.first) else {
+            return Err(SpanError::Expired {
+                span,
+                oldest: entry.first,
+            });
+        };
+        let (mut start, mut end) = (span.start, span.end);
+        for (i, edit) in entry.edits.iter().enumerate().skip(skip) {
+            let removed = &edit.removed;
+            if removed.end <= start {
+                // Before the span, including an insertion right at its start
+                start = start - removed.len() + edit.inserted;
+                end = end - removed.len() + edit.inserted;
+            } else if removed.start >= end {
+                // After the span, including an insertion right at its end
+            } else {
+                return Err(SpanError::Stale {
+                    span,
+                    version: entry.first + i,
+                });
+            }
+        }
+        Ok(Span {
+            start,
+            end,
+            version: entry.version(),
+            ..span
+        })
+    }
+
+    /// The text the span covers now
+    pub fn resolve(&self, span: Span) -> Result<&str, SpanError> {
+        let current = self.rebase(span)?;
+        Ok(&self.entry(span.text)?.text[current.range()])
+    }
+}
+
+fn check_range(text: &str, range: &Range<usize>) -> Result<(), SpanError> {
+    if range.start <= range.end && text.get(range.clone()).is_some() {
+        Ok(())
+    } else {
+        Err(SpanError::InvalidRange {
+            range: range.clone(),
+            len: text.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "hello world", with a span on "world" (6..11)
+    fn setup() -> (TextArena, TextId, Span) {
+        let mut arena = TextArena::new();
+        let id = arena.insert("hello world");
+        let span = arena.span(id, 6..11).unwrap();
+        (arena, id, span)
+    }
+
+    #[test]
+    fn edits_before_move_the_span() {
+        let (mut arena, id, span) = setup();
+        arena.replace(id, 0..5, "goodbye").unwrap();
+        assert_eq!(arena.resolve(span), Ok("world"));
+        assert_eq!(arena.rebase(span).unwrap().range(), 8..13);
+        arena.delete(id, 0..8).unwrap();
+        assert_eq!(arena.resolve(span), Ok("world"));
+        assert_eq!(arena.rebase(span).unwrap().range(), 0..5);
+    }
+
+    #[test]
+    fn edits_after_leave_the_span() {
+        let (mut arena, id, span) = setup();
+        arena.push_str(id, "!!").unwrap();
+        arena.delete(id, 11..12).unwrap();
+        assert_eq!(arena.get(id), Some("hello world!"));
+        let rebased = arena.rebase(span).unwrap();
+        assert_eq!((rebased.range(), rebased.version()), (6..11, 2));
+    }
+
+    #[test]
+    fn adjacent_edits_do_not_touch_the_span() {
+        let (mut arena, id, span) = setup();
+        // Inserting right at the start moves it, right at the end doesn't
+        arena.insert_str(id, 6, "big ").unwrap();
+        arena.insert_str(id, 15, "s").unwrap();
+        assert_eq!(arena.get(id), Some("hello big worlds"));
+        assert_eq!(arena.resolve(span), Ok("world"));
+        // Deleting up to the start, and from the end on
+        arena.delete(id, 5..10).unwrap();
+        arena.delete(id, 10..11).unwrap();
+        assert_eq!(arena.get(id), Some("helloworld"));
+        assert_eq!(arena.resolve(span), Ok("world"));
+    }
+
+    #[test]
+    fn overlapping_edits_make_the_span_stale() {
+        for range in [5..7, 10..11, 7..8, 0..11, 6..11] {
+            let (mut arena, id, span) = setup();
+            arena.push_str(id, "!").unwrap();
+            arena.replace(id, range.clone(), "x").unwrap();
+            assert_eq!(
+                arena.resolve(span),
+                Err(SpanError::Stale { span, version: 1 }),
+                "{range:?}"
+            );
+        }
+        // Inserting strictly inside counts as overlapping, even for an
+        // empty span there
+        let (mut arena, id, span) = setup();
+        let empty = arena.span(id, 8..8).unwrap();
+        arena.insert_str(id, 8, "r").unwrap();
+        assert!(matches!(arena.resolve(span), Err(SpanError::Stale { .. })));
+        assert_eq!(arena.resolve(empty), Ok(""));
+        assert_eq!(arena.rebase(empty).unwrap().range(), 9..9);
+    }
+
+    #[test]
+    fn word_spans_follow_edits() {
+        let mut arena = TextArena::new();
+        let id = arena.insert("one two  three");
+        let spans = arena.word_spans(id).unwrap();
+        arena.replace(id, 4..7, "2").unwrap();
+        let resolved: Vec<_> = spans.iter().map(|&span| arena.resolve(span)).collect();
+        assert_eq!(resolved[0], Ok("one"));
+        assert!(resolved[1].is_err());
+        assert_eq!(resolved[2], Ok("three"));
+    }
+
+    #[test]
+    fn ranges_must_be_char_aligned() {
+        let mut arena = TextArena::new();
+        let id = arena.insert("né");
+        assert_eq!(
+            arena.span(id, 0..2),
+            Err(SpanError::InvalidRange {
+                range: 0..2,
+                len: 3
+            })
+        );
+        assert!(arena.replace(id, Range { start: 3, end: 1 }, "").is_err());
+        assert!(arena.insert_str(id, 4, "x").is_err());
+        assert_eq!(arena.version(id), Some(0));
+    }
+
+    #[test]
+    fn reused_slots_reject_old_ids() {
+        let (mut arena, id, span) = setup();
+        assert_eq!(arena.remove(id).as_deref(), Some("hello world"));
+        assert_eq!(arena.remove(id), None);
+        let new = arena.insert("another text");
+        assert_eq!(new.index, id.index);
+        assert_ne!(new, id);
+        assert_eq!(arena.get(id), None);
+        assert_eq!(arena.resolve(span), Err(SpanError::UnknownText(id)));
+        assert_eq!(
+            arena.replace(id, 0..1, "x"),
+            Err(SpanError::UnknownText(id))
+        );
+        assert_eq!(arena.get(new), Some("another text"));
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn forgotten_edits_expire_old_spans() {
+        let (mut arena, id, old) = setup();
+        arena.insert_str(id, 0, "oh, ").unwrap();
+        let middle = arena.span(id, 10..15).unwrap();
+        arena.insert_str(id, 0, "well, ").unwrap();
+        assert_eq!(arena.forget_edits(id, 1), Ok(1));
+        assert_eq!(arena.forget_edits(id, 1), Ok(0));
+        assert_eq!(arena.version(id), Some(2));
+        assert_eq!(
+            arena.resolve(old),
+            Err(SpanError::Expired {
+                span: old,
+                oldest: 1
+            })
+        );
+        assert_eq!(arena.resolve(middle), Ok("world"));
+        // Forgetting past the current version drops everything there is
+        assert_eq!(arena.forget_edits(id, 10), Ok(1));
+        assert_eq!(arena.version(id), Some(2));
+        let now = arena.span(id, 0..4).unwrap();
+        arena.delete(id, 0..6).unwrap();
+        assert!(matches!(
+            arena.resolve(now),
+            Err(SpanError::Stale { version: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,429 @@
+// wc-style text statistics over a stream.
+//
+// Input is read in fixed-size chunks and cut at the last newline, so memory
+// stays at one chunk however large the file is. Each chunk is validated as
+// UTF-8 once and then only ever looked at through `&str` slices: words come
+// from `words::words`, graphemes from `text_metrics::graphemes`. The one
+// copy is the first time a word goes into the frequency table; after that
+// its count is bumped through the borrowed slice.
+//
+// A line longer than a chunk is cut before its last grapheme cluster, which
+// goes into the next chunk in case the next read extends it (a combining
+// mark, or the `\n` after a `\r`). A word cut in two is copied into
+// `partial` and counted once it ends, so a single word longer than a chunk
+// is the one thing that takes more memory than the buffer.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+use std::str;
+
+use borrowing_references::text_metrics;
+
+use crate::words;
+
+/// Bytes read at a time
+pub const CHUNK: usize = 64 * 1024;
+
+#[derive(Debug)]
+pub enum TextStatsError {
+    Io(io::Error),
+    /// `offset` is the byte offset of the first invalid byte in the stream
+    InvalidUtf8 {
+        offset: u64,
+        line: usize,
+    },
+}
+
+impl fmt::Display for TextStatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextStatsError::Io(err) => write!(f, "{err}"),
+            TextStatsError::InvalidUtf8 { offset, line } => {
+                write!(f, "invalid UTF-8 at byte {offset} (line {line})")
+            }
+        }
+    }
+}
+
+impl Error for TextStatsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextStatsError::Io(err) => Some(err),
+            TextStatsError::InvalidUtf8 { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for TextStatsError {
+    fn from(err: io::Error) -> TextStatsError {
+        TextStatsError::Io(err)
+    }
+}
+
+/// The longest line, by chars, not counting the line ending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongestLine {
+    /// 1-based
+    pub number: usize,
+    pub chars: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextStats {
+    /// Newline count, like `wc -l`
+    pub lines: usize,
+    /// Runs of non-whitespace, like `wc -w`
+    pub words: usize,
+    pub chars: usize,
+    pub bytes: u64,
+    pub graphemes: usize,
+    pub longest_line: Option<LongestLine>,
+    word_chars: usize,
+    /// Lowercased, punctuation-trimmed word -> count
+    frequencies: HashMap<String, usize>,
+    /// Chars so far on the line that is still being read
+    line_chars: usize,
+    /// The start of a word the previous chunk ended in
+    partial: String,
+}
+
+// Byte offset of the last grapheme cluster of `text`
+fn last_cluster_start(text: &str) -> usize {
+    let last = text_metrics::graphemes(text).last().map_or(0, str::len);
+    text.len() - last
+}
+
+impl TextStats {
+    pub fn new() -> TextStats {
+        TextStats::default()
+    }
+
+    pub fn from_text(text: &str) -> TextStats {
+        let mut stats = TextStats::new();
+        stats.feed(text);
+        stats.finish();
+        stats
+    }
+
+    /// Streams `reader` through a single `CHUNK`-sized buffer
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<TextStats, TextStatsError> {
+        let mut stats = TextStats::new();
+        let mut buffer = vec![0; CHUNK];
+        let mut filled = 0;
+
+        loop {
+            let read = match reader.read(&mut buffer[filled..]) {
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            filled += read;
+            let at_end = read == 0;
+
+            let valid = match str::from_utf8(&buffer[..filled]) {
+                Ok(text) => text.len(),
+                // A sequence cut off by the end of the buffer is completed
+                // by the next read, unless there is no next read
+                Err(err) if err.error_len().is_none() && !at_end => err.valid_up_to(),
+                Err(err) => {
+                    let before = &buffer[..err.valid_up_to()];
+                    return Err(TextStatsError::InvalidUtf8 {
+                        offset: stats.bytes + before.len() as u64,
+                        line: stats.lines + before.iter().filter(|&&b| b == b'\n').count() + 1,
+                    });
+                }
+            };
+
+            let cut = if at_end {
+                valid
+            } else if let Some(newline) = buffer[..valid].iter().rposition(|&b| b == b'\n') {
+                newline + 1
+            } else if filled == buffer.len() {
+                // One line longer than the buffer: keep the last cluster
+                // back, unless it fills the whole buffer
+                let text = str::from_utf8(&buffer[..valid]).expect("validated above");
+                match last_cluster_start(text) {
+                    0 => valid,
+                    start => start,
+                }
+            } else {
+                continue;
+            };
+
+            let text = str::from_utf8(&buffer[..cut]).expect("validated above");
+            stats.feed(text);
+            buffer.copy_within(cut..filled, 0);
+            filled -= cut;
+            if at_end {
+                break;
+            }
+        }
+        stats.finish();
+        Ok(stats)
+    }
+
+    fn feed(&mut self, text: &str) {
+        self.bytes += text.len() as u64;
+        self.graphemes += text_metrics::graphemes(text).count();
+
+        for piece in text.split_inclusive('\n') {
+            let chars = piece.chars().count();
+            self.chars += chars;
+            match piece.strip_suffix('\n') {
+                Some(line) => {
+                    let ending = if line.ends_with('\r') { 2 } else { 1 };
+                    self.end_line(self.line_chars + chars - ending);
+                    self.lines += 1;
+                    self.line_chars = 0;
+                }
+                None => self.line_chars += chars,
+            }
+        }
+
+        let mut rest = text;
+        if !self.partial.is_empty() {
+            // The word the previous chunk ended in goes on to the first space
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            self.partial.push_str(&rest[..end]);
+            rest = &rest[end..];
+            if rest.is_empty() {
+                return;
+            }
+            let partial = std::mem::take(&mut self.partial);
+            self.word(&partial);
+            self.partial = partial;
+            self.partial.clear();
+        }
+        // A word running into the end of the chunk may go on in the next one
+        if rest.chars().next_back().is_some_and(|c| !c.is_whitespace()) {
+            let start = rest.rfind(char::is_whitespace).map_or(0, |space| {
+                space + rest[space..].chars().next().unwrap().len_utf8()
+            });
+            self.partial.push_str(&rest[start..]);
+            rest = &rest[..start];
+        }
+        for word in words::words(rest).texts() {
+            self.word(word);
+        }
+    }
+
+    fn word(&mut self, word: &str) {
+        self.words += 1;
+        self.word_chars += word.chars().count();
+        let trimmed = word.trim_matches(words::is_punctuation);
+        if !trimmed.is_empty() {
+            self.count(trimmed);
+        }
+    }
+
+    fn count(&mut self, word: &str) {
+        let key = if word.chars().any(char::is_uppercase) {
+            Cow::Owned(word.to_lowercase())
+        } else {
+            Cow::Borrowed(word)
+        };
+        match self.frequencies.get_mut(key.as_ref()) {
+            Some(count) => *count += 1,
+            None => {
+                self.frequencies.insert(key.into_owned(), 1);
+            }
+        }
+    }
+
+    fn end_line(&mut self, chars: usize) {
+        let number = self.lines + 1;
+        if self
+            .longest_line
+            .is_none_or(|longest| chars > longest.chars)
+        {
+            self.longest_line = Some(LongestLine { number, chars });
+        }
+    }
+
+    // A last line without a trailing newline
+    fn finish(&mut self) {
+        if self.line_chars > 0 {
+            self.end_line(self.line_chars);
+            self.line_chars = 0;
+        }
+        if !self.partial.is_empty() {
+            let partial = std::mem::take(&mut self.partial);
+            self.word(&partial);
+        }
+    }
+
+    /// Average word length in chars, None without words
+    pub fn average_word_length(&self) -> Option<f64> {
+        (self.words > 0).then(|| self.word_chars as f64 / self.words as f64)
+    }
+
+    /// The `n` most frequent words, ties in alphabetical order. Words are
+    /// lowercased and stripped of surrounding punctuation.
+    pub fn top_words(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = self
+            .frequencies
+            .iter()
+            .map(|(word, &count)| (word.as_str(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        counts.truncate(n);
+        counts
+    }
+
+    /// Distinct words in the frequency table
+    pub fn vocabulary(&self) -> usize {
+        self.frequencies.len()
+    }
+
+    /// Adds another file's counts, as for a `total` row. Line numbers in
+    /// `longest_line` stay relative to their own file.
+    pub fn merge(&mut self, other: &TextStats) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.chars += other.chars;
+        self.bytes += other.bytes;
+        self.graphemes += other.graphemes;
+        self.word_chars += other.word_chars;
+        if let Some(theirs) = other.longest_line
+            && self
+                .longest_line
+                .is_none_or(|ours| theirs.chars > ours.chars)
+        {
+            self.longest_line = Some(theirs);
+        }
+        for (word, &count) in &other.frequencies {
+            match self.frequencies.get_mut(word) {
+                Some(total) => *total += count,
+                None => {
+                    self.frequencies.insert(word.clone(), count);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ownership::lcg::Lcg;
+
+    use super::*;
+
+    fn assert_same(text: &str) {
+        let streamed = TextStats::from_reader(text.as_bytes()).unwrap();
+        let whole = TextStats::from_text(text);
+        let counts = |s: &TextStats| {
+            (
+                s.lines,
+                s.words,
+                s.chars,
+                s.bytes,
+                s.graphemes,
+                s.longest_line,
+                s.word_chars,
+            )
+        };
+        assert_eq!(counts(&streamed), counts(&whole));
+        assert_eq!(streamed.frequencies, whole.frequencies);
+    }
+
+    #[test]
+    fn counts() {
+        let stats = TextStats::from_text("The cat,\r\nthe hat  and नमस्ते\n\nend");
+        assert_eq!(stats.lines, 3);
+        assert_eq!(stats.words, 7);
+        assert_eq!(stats.bytes, 46);
+        assert_eq!(stats.chars, 34);
+        assert_eq!(stats.graphemes, 30);
+        assert_eq!(
+            stats.longest_line,
+            Some(LongestLine {
+                number: 2,
+                chars: 19
+            })
+        );
+        assert_eq!(stats.top_words(2), [("the", 2), ("and", 1)]);
+        assert_eq!(stats.vocabulary(), 6);
+        assert_eq!(stats.average_word_length(), Some(25.0 / 7.0));
+        assert_eq!(TextStats::from_text("").average_word_length(), None);
+    }
+
+    #[test]
+    fn a_word_longer_than_the_buffer() {
+        let word = "ab".repeat(CHUNK / 2 + 1);
+        let text = format!("{word} tail {word}, Word\nx {word}");
+        assert_same(&text);
+        let stats = TextStats::from_reader(text.as_bytes()).unwrap();
+        assert_eq!(stats.words, 6);
+        assert_eq!(stats.top_words(1), [(word.as_str(), 3)]);
+    }
+
+    #[test]
+    fn chunk_edges_inside_a_line() {
+        let line = "x".repeat(CHUNK - 1);
+        // "\r\n", a combining mark and a word across the first chunk's end
+        for tail in ["\r\nnext", "e\u{301}", "e\u{301}\u{301} word", "\u{301}"] {
+            let text = format!("{line}{tail}");
+            assert_same(&text);
+            assert_same(&format!("a {text}"));
+        }
+        let stats = TextStats::from_reader(format!("{line}\r\nnext").as_bytes()).unwrap();
+        assert_eq!(stats.longest_line.unwrap().chars, CHUNK - 1);
+    }
+
+    #[test]
+    fn random_long_lines() {
+        let pieces = [
+            "word",
+            "Word,",
+            "é",
+            "e\u{301}",
+            "日本",
+            "🇫🇷",
+            " ",
+            "  ",
+            "\t",
+            "\u{A0}",
+            "\r\n",
+            "\r",
+            "-",
+            "नमस्ते",
+        ];
+        let mut rng = Lcg::new(42);
+        for _ in 0..3 {
+            let length = CHUNK + rng.below(2 * CHUNK);
+            let mut text = String::new();
+            while text.len() < length {
+                text.push_str(pieces[rng.below(pieces.len())]);
+                // Newlines are rare, so lines run past the buffer
+                if rng.below(20_000) == 0 {
+                    text.push('\n');
+                }
+            }
+            assert_same(&text);
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_is_located() {
+        let mut bytes = b"ok\nfine\nbad \xff here".to_vec();
+        let err = TextStats::from_reader(bytes.as_slice()).unwrap_err();
+        assert!(matches!(
+            err,
+            TextStatsError::InvalidUtf8 {
+                offset: 12,
+                line: 3
+            }
+        ));
+        // Cut off at the very end
+        bytes.truncate(8);
+        bytes.extend_from_slice("é".as_bytes().split_at(1).0);
+        let err = TextStats::from_reader(bytes.as_slice()).unwrap_err();
+        assert!(matches!(
+            err,
+            TextStatsError::InvalidUtf8 { offset: 8, line: 3 }
+        ));
+    }
+}
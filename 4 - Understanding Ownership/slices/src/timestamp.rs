@@ -0,0 +1,701 @@
+// RFC 3339 / ISO 8601 timestamps.
+//
+// Section 10 of main.rs pulls the date and time out of "2023-12-04T15:30:00"
+// with `&data[0..10]` and `&data[11..19]`. That only works for exactly that
+// layout, and it happily accepts "2023-02-30T25:61:00". `Timestamp` parses
+//
+//     2023-12-04T15:30:00             no offset (local time)
+//     2023-12-04T15:30:00.250Z        fractional seconds, UTC
+//     2023-12-04 15:30:00-05:00       space separator, fixed offset
+//     2023-12-04T15:30:00-00:00       UTC, local offset unknown
+//
+// checks every field against the calendar (leap years included), and says
+// at which byte parsing went wrong. Timestamps format back to the same text,
+// compare as instants and can be subtracted.
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+/// Offset from UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    /// No offset given. Compared and subtracted as if it were UTC.
+    Local,
+    /// `Z`
+    Utc,
+    /// `-00:00`: the time is in UTC and the local offset is unknown
+    /// (RFC 3339 section 4.3)
+    UnknownLocal,
+    /// `+hh:mm` or `-hh:mm`, in minutes; `+00:00` is `Fixed(0)`
+    Fixed(i16),
+}
+
+impl Offset {
+    fn minutes(self) -> i64 {
+        match self {
+            Offset::Local | Offset::Utc | Offset::UnknownLocal => 0,
+            Offset::Fixed(minutes) => minutes as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampError {
+    /// The text stopped where `expected` should have been
+    UnexpectedEnd {
+        position: usize,
+        expected: &'static str,
+    },
+    /// `found` is not what the format allows here
+    Unexpected {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+    /// A well-formed field with an impossible value, like month 13
+    OutOfRange {
+        position: usize,
+        field: &'static str,
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+}
+
+impl TimestampError {
+    /// Byte offset of the problem in the input
+    pub fn position(&self) -> usize {
+        match *self {
+            TimestampError::UnexpectedEnd { position, .. }
+            | TimestampError::Unexpected { position, .. }
+            | TimestampError::OutOfRange { position, .. } => position,
+        }
+    }
+}
+
+impl fmt::Display for TimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimestampError::UnexpectedEnd { position, expected } => {
+                write!(
+                    f,
+                    "byte {position}: expected {expected}, found end of input"
+                )
+            }
+            TimestampError::Unexpected {
+                position,
+                found,
+                expected,
+            } => write!(f, "byte {position}: expected {expected}, found {found:?}"),
+            TimestampError::OutOfRange {
+                position,
+                field,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "byte {position}: {field} {value} is out of range {min}..={max}"
+            ),
+        }
+    }
+}
+
+impl Error for TimestampError {}
+
+// ============================================================================
+// Calendar
+// ============================================================================
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01 (proleptic Gregorian), after Howard Hinnant's
+// `days_from_civil`
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    let year = year as i64 - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u8;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+// ============================================================================
+// Timestamp
+// ============================================================================
+
+/// A parsed date and time with an optional offset.
+///
+/// Equality and ordering compare instants, so `12:00:00Z` equals
+/// `13:00:00+01:00`; use `to_string()` to compare the written form.
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamp {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+    /// How many fractional digits were written, so formatting round-trips
+    fraction_digits: u8,
+    offset: Offset,
+}
+
+impl Timestamp {
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// 0..=60; 60 is a leap second, which is only accepted at 23:59 UTC
+    /// (or 23:59 for a timestamp with no offset)
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub fn offset(&self) -> Offset {
+        self.offset
+    }
+
+    /// `YYYY-MM-DD`
+    pub fn date(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// `hh:mm:ss` plus any fractional seconds
+    pub fn time(&self) -> String {
+        let mut time = format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second);
+        if self.fraction_digits > 0 {
+            let digits = self.fraction_digits as usize;
+            let fraction = self.nanosecond / 10u32.pow(9 - digits as u32);
+            time.push_str(&format!(".{fraction:0digits$}"));
+        }
+        time
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z. A leap second counts as the
+    /// first second of the next minute.
+    pub fn unix_seconds(&self) -> i64 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        let seconds = self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        days * 86_400 + seconds - self.offset.minutes() * 60
+    }
+
+    fn unix_nanos(&self) -> i128 {
+        self.unix_seconds() as i128 * NANOS_PER_SECOND + self.nanosecond as i128
+    }
+
+    /// The same instant written in UTC, or None if that falls outside
+    /// years 0000..=9999
+    pub fn to_utc(&self) -> Option<Timestamp> {
+        Some(Timestamp {
+            nanosecond: self.nanosecond,
+            fraction_digits: self.fraction_digits,
+            ..Timestamp::from_unix_seconds(self.unix_seconds())?
+        })
+    }
+
+    /// The UTC timestamp `seconds` after 1970-01-01T00:00:00Z, or None if
+    /// that falls outside years 0000..=9999
+    pub fn from_unix_seconds(seconds: i64) -> Option<Timestamp> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+        if !(0..=9999).contains(&year) {
+            return None;
+        }
+        let second_of_day = seconds.rem_euclid(86_400);
+        Some(Timestamp {
+            year: year as i32,
+            month,
+            day,
+            hour: (second_of_day / 3600) as u8,
+            minute: (second_of_day / 60 % 60) as u8,
+            second: (second_of_day % 60) as u8,
+            nanosecond: 0,
+            fraction_digits: 0,
+            offset: Offset::Utc,
+        })
+    }
+
+    /// The current time in UTC, to the second
+    pub fn now() -> Timestamp {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64);
+        Timestamp::from_unix_seconds(seconds).expect("the clock is before year 10000")
+    }
+
+    /// How much later `self` is than `earlier`, or None if it is earlier
+    pub fn duration_since(&self, earlier: &Timestamp) -> Option<Duration> {
+        let nanos = self.unix_nanos() - earlier.unix_nanos();
+        let nanos = u128::try_from(nanos).ok()?;
+        Some(Duration::new(
+            (nanos / NANOS_PER_SECOND as u128) as u64,
+            (nanos % NANOS_PER_SECOND as u128) as u32,
+        ))
+    }
+
+    /// `later - earlier` in seconds, negative if `later` is earlier
+    pub fn seconds_between(earlier: &Timestamp, later: &Timestamp) -> f64 {
+        (later.unix_nanos() - earlier.unix_nanos()) as f64 / NANOS_PER_SECOND as f64
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Timestamp) -> bool {
+        self.unix_nanos() == other.unix_nanos()
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Timestamp) -> Ordering {
+        self.unix_nanos().cmp(&other.unix_nanos())
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}T{}", self.date(), self.time())?;
+        match self.offset {
+            Offset::Local => Ok(()),
+            Offset::Utc => write!(f, "Z"),
+            Offset::UnknownLocal => write!(f, "-00:00"),
+            Offset::Fixed(minutes) => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+struct Cursor<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn error(&self, expected: &'static str) -> TimestampError {
+        match self.text[self.position..].chars().next() {
+            Some(found) => TimestampError::Unexpected {
+                position: self.position,
+                found,
+                expected,
+            },
+            None => TimestampError::UnexpectedEnd {
+                position: self.position,
+                expected,
+            },
+        }
+    }
+
+    fn expect(&mut self, byte: u8, expected: &'static str) -> Result<(), TimestampError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(expected));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Exactly `count` digits, checked against `min..=max`
+    fn field(
+        &mut self,
+        count: usize,
+        field: &'static str,
+        min: u32,
+        max: u32,
+    ) -> Result<u32, TimestampError> {
+        let start = self.position;
+        let mut value = 0;
+        for _ in 0..count {
+            match self.peek() {
+                Some(digit @ b'0'..=b'9') => value = value * 10 + (digit - b'0') as u32,
+                _ => return Err(self.error("a digit")),
+            }
+            self.position += 1;
+        }
+        if !(min..=max).contains(&value) {
+            return Err(TimestampError::OutOfRange {
+                position: start,
+                field,
+                value,
+                min,
+                max,
+            });
+        }
+        Ok(value)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = TimestampError;
+
+    fn from_str(text: &str) -> Result<Timestamp, TimestampError> {
+        let mut cursor = Cursor { text, position: 0 };
+
+        let year = cursor.field(4, "year", 0, 9999)? as i32;
+        cursor.expect(b'-', "'-'")?;
+        let month = cursor.field(2, "month", 1, 12)? as u8;
+        cursor.expect(b'-', "'-'")?;
+        let max_day = days_in_month(year, month) as u32;
+        let day = cursor.field(2, "day", 1, max_day)? as u8;
+
+        match cursor.peek() {
+            Some(b'T' | b't' | b' ') => cursor.position += 1,
+            _ => return Err(cursor.error("'T' or ' '")),
+        }
+        let hour = cursor.field(2, "hour", 0, 23)? as u8;
+        cursor.expect(b':', "':'")?;
+        let minute = cursor.field(2, "minute", 0, 59)? as u8;
+        cursor.expect(b':', "':'")?;
+        // RFC 3339 allows a leap second, checked against the offset below
+        let second_position = cursor.position;
+        let second = cursor.field(2, "second", 0, 60)? as u8;
+
+        let mut nanosecond = 0;
+        let mut fraction_digits = 0;
+        if cursor.peek() == Some(b'.') {
+            cursor.position += 1;
+            while let Some(digit @ b'0'..=b'9') = cursor.peek() {
+                if fraction_digits == 9 {
+                    return Err(cursor.error("at most 9 fractional digits"));
+                }
+                nanosecond = nanosecond * 10 + (digit - b'0') as u32;
+                fraction_digits += 1;
+                cursor.position += 1;
+            }
+            if fraction_digits == 0 {
+                return Err(cursor.error("a digit"));
+            }
+            nanosecond *= 10u32.pow(9 - fraction_digits as u32);
+        }
+
+        let offset = match cursor.peek() {
+            None => Offset::Local,
+            Some(b'Z' | b'z') => {
+                cursor.position += 1;
+                Offset::Utc
+            }
+            Some(sign @ (b'+' | b'-')) => {
+                cursor.position += 1;
+                let hours = cursor.field(2, "offset hour", 0, 23)? as i16;
+                cursor.expect(b':', "':'")?;
+                let minutes = cursor.field(2, "offset minute", 0, 59)? as i16;
+                let minutes = hours * 60 + minutes;
+                match sign {
+                    b'-' if minutes == 0 => Offset::UnknownLocal,
+                    b'-' => Offset::Fixed(-minutes),
+                    _ => Offset::Fixed(minutes),
+                }
+            }
+            Some(_) => return Err(cursor.error("'Z', '+', '-' or the end")),
+        };
+        if cursor.peek().is_some() {
+            return Err(cursor.error("the end"));
+        }
+
+        // Leap seconds are inserted at the end of a UTC day
+        let utc_minute = (hour as i64 * 60 + minute as i64 - offset.minutes()).rem_euclid(1440);
+        if second == 60 && utc_minute != 23 * 60 + 59 {
+            return Err(TimestampError::OutOfRange {
+                position: second_position,
+                field: "second",
+                value: 60,
+                min: 0,
+                max: 59,
+            });
+        }
+
+        Ok(Timestamp {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            fraction_digits,
+            offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Timestamp {
+        text.parse().unwrap_or_else(|err| panic!("{text}: {err}"))
+    }
+
+    fn error_at(text: &str) -> usize {
+        match text.parse::<Timestamp>() {
+            Ok(ts) => panic!("{text} parsed as {ts}"),
+            Err(err) => err.position(),
+        }
+    }
+
+    #[test]
+    fn fields() {
+        let ts = parse("2023-12-04T15:30:00.125+05:30");
+        assert_eq!((ts.year(), ts.month(), ts.day()), (2023, 12, 4));
+        assert_eq!((ts.hour(), ts.minute(), ts.second()), (15, 30, 0));
+        assert_eq!(ts.nanosecond(), 125_000_000);
+        assert_eq!(ts.offset(), Offset::Fixed(330));
+        assert_eq!(ts.date(), "2023-12-04");
+        assert_eq!(ts.time(), "15:30:00.125");
+        assert_eq!(
+            parse("2023-12-04T06:00:00-05:00").offset(),
+            Offset::Fixed(-300)
+        );
+        assert_eq!(parse("2023-12-04T06:00:00").offset(), Offset::Local);
+        assert_eq!(parse("2023-12-04T06:00:00z").offset(), Offset::Utc);
+    }
+
+    #[test]
+    fn round_trips() {
+        for text in [
+            "2023-12-04T15:30:00",
+            "2023-12-04T15:30:00Z",
+            "2023-12-04T15:30:00.5-08:00",
+            "2024-02-29T23:59:59.123456789+00:00",
+            "2016-12-31T23:59:60Z",
+            "2023-12-04T15:30:00-00:00",
+            "0000-01-01T00:00:00Z",
+            "9999-12-31T23:59:59Z",
+        ] {
+            assert_eq!(parse(text).to_string(), text);
+        }
+        // Lowercase and space separators are accepted but written canonically
+        assert_eq!(
+            parse("2023-12-04 15:30:00z").to_string(),
+            "2023-12-04T15:30:00Z"
+        );
+    }
+
+    #[test]
+    fn unknown_local_offset_is_not_plus_zero() {
+        let unknown = parse("2023-12-04T15:30:00-00:00");
+        let zero = parse("2023-12-04T15:30:00+00:00");
+        assert_eq!(unknown.offset(), Offset::UnknownLocal);
+        assert_eq!(zero.offset(), Offset::Fixed(0));
+        // Same instant, different meaning
+        assert_eq!(unknown, zero);
+        assert_ne!(unknown.to_string(), zero.to_string());
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let bad = [
+            ("2023-02-30T25:61:00", 8),
+            ("2023-13-01T00:00:00", 5),
+            ("2023-02-29T00:00:00", 8),
+            ("2024-02-30T00:00:00", 8),
+            ("1900-02-29T00:00:00", 8),
+            ("2023-04-31T00:00:00", 8),
+            ("2023-12-04T24:00:00", 11),
+            ("2023-12-04T12:30:60", 17),
+            ("2023-12-04X15:30:00", 10),
+            ("2023-12-4T15:30:00", 9),
+            ("2023-12-04T15:30", 16),
+            ("2023-12-04T15:30:00.", 20),
+            ("2023-12-04T15:30:00.1234567891Z", 29),
+            ("2023-12-04T15:30:00+5:30", 21),
+            ("2023-12-04T15:30:00+05:30 UTC", 25),
+            ("2023-12-04T15:30:00→", 19),
+        ];
+        for (text, position) in bad {
+            assert_eq!(error_at(text), position, "{text}");
+        }
+        assert_eq!(
+            "2023-13-01T00:00:00".parse::<Timestamp>().unwrap_err(),
+            TimestampError::OutOfRange {
+                position: 5,
+                field: "month",
+                value: 13,
+                min: 1,
+                max: 12,
+            }
+        );
+        // 2000 is a leap year, 1900 is not
+        assert!("2000-02-29T00:00:00Z".parse::<Timestamp>().is_ok());
+    }
+
+    #[test]
+    fn leap_seconds_only_end_a_utc_day() {
+        for text in [
+            "2016-12-31T23:59:60Z",
+            "2016-12-31T23:59:60-00:00",
+            "2016-12-31T23:59:60.5+00:00",
+            "2017-01-01T05:29:60+05:30",
+            "2016-12-31T18:59:60-05:00",
+            "2016-12-31T23:59:60",
+        ] {
+            assert_eq!(parse(text).second(), 60, "{text}");
+        }
+        for text in [
+            "2016-12-31T12:59:60Z",
+            "2016-12-31T23:59:60+01:00",
+            "2017-01-01T05:59:60+05:30",
+            "2016-12-31T12:30:60",
+        ] {
+            assert_eq!(error_at(text), 17, "{text}");
+        }
+        // A leap second counts as the first second of the next minute
+        let leap = parse("2016-12-31T23:59:60Z");
+        assert_eq!(leap, parse("2017-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn instants() {
+        let utc = parse("2023-12-04T10:00:00Z");
+        let india = parse("2023-12-04T15:30:00+05:30");
+        let new_york = parse("2023-12-04T06:00:00.250-05:00");
+        assert_eq!(utc, india);
+        assert_eq!(
+            new_york.to_utc().unwrap().to_string(),
+            "2023-12-04T11:00:00.250Z"
+        );
+        assert_eq!(
+            new_york.duration_since(&india).unwrap().as_millis(),
+            3_600_250
+        );
+        assert_eq!(india.duration_since(&new_york), None);
+        assert_eq!(Timestamp::seconds_between(&new_york, &utc), -3600.25);
+
+        // Across a leap day and a year boundary
+        let before = parse("2023-12-31T23:00:00-01:00");
+        let after = parse("2024-03-01T00:00:00Z");
+        assert_eq!(
+            after.duration_since(&before).unwrap().as_secs() / 86_400,
+            60
+        );
+        assert_eq!(before.to_utc().unwrap().to_string(), "2024-01-01T00:00:00Z");
+
+        let mut sorted = vec![after, new_york, before, utc];
+        sorted.sort();
+        assert_eq!(sorted, [utc, new_york, before, after]);
+
+        // Out of range once moved to UTC
+        assert_eq!(parse("9999-12-31T23:30:00-01:00").to_utc(), None);
+    }
+
+    #[test]
+    fn from_unix_seconds() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (-1, "1969-12-31T23:59:59Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_760_918_400, "2025-10-20T00:00:00Z"),
+            (-62_167_219_200, "0000-01-01T00:00:00Z"),
+            (253_402_300_799, "9999-12-31T23:59:59Z"),
+        ];
+        for (seconds, text) in cases {
+            let ts = Timestamp::from_unix_seconds(seconds).unwrap();
+            assert_eq!(ts.to_string(), text);
+            assert_eq!(ts.offset(), Offset::Utc);
+            assert_eq!(ts.unix_seconds(), seconds);
+        }
+        assert_eq!(Timestamp::from_unix_seconds(-62_167_219_201), None);
+        assert_eq!(Timestamp::from_unix_seconds(253_402_300_800), None);
+        assert_eq!(Timestamp::from_unix_seconds(i64::MIN), None);
+        assert_eq!(Timestamp::from_unix_seconds(i64::MAX), None);
+
+        // Every second in range survives the trip through the calendar
+        let mut rng = ownership::lcg::Lcg::new(7);
+        for _ in 0..10_000 {
+            let seconds = rng.below(253_402_300_800 + 62_167_219_200) as i64 - 62_167_219_200;
+            let ts = Timestamp::from_unix_seconds(seconds).unwrap();
+            assert_eq!(ts.unix_seconds(), seconds);
+            assert_eq!(parse(&ts.to_string()), ts);
+        }
+    }
+
+    #[test]
+    fn now_is_the_system_clock_in_utc() {
+        let seconds = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64
+        };
+        let before = seconds();
+        let now = Timestamp::now();
+        let after = seconds();
+        assert!((before..=after).contains(&now.unix_seconds()));
+        assert_eq!(now.offset(), Offset::Utc);
+        assert_eq!(now.nanosecond(), 0);
+        assert!(now.to_string().ends_with('Z'));
+        assert!(Timestamp::now() >= now);
+    }
+}
@@ -0,0 +1,491 @@
+// Devanagari to Latin transliteration.
+//
+// Devanagari is an abugida: a consonant letter carries an inherent "a",
+// a vowel sign (matra) after it replaces that vowel, and a virama (्)
+// removes it so the next consonant joins it in a conjunct:
+//
+//     न      na        consonant with inherent a
+//     ते     te        त + matra े
+//     स्ते    ste       स + virama, then त + े
+//     नमस्ते   namaste
+//
+// Two schemes are supported. IAST is the scholarly one (ā, ṭ, ś, ṃ, ...).
+// Ascii drops the diacritics for readable plain text (a, t, sh, n, ...).
+// Both write the inherent "a" wherever the script has it, so Hindi words
+// keep the final a that speech drops: "कमल" is "kamala".
+//
+// Neither can be turned back into Devanagari in general. IAST has no
+// letters for ळ or the nukta consonants, and the usual stand-ins collide
+// with vowels: ṛ is both ऋ and ड़, ḷ both ऌ and ळ. And as in any IAST
+// text, "kh" may be ख or क्ह. A nukta on a consonant with no letter of
+// its own (त़) is copied into the output unchanged.
+//
+// Each piece of the output remembers the byte range of the source it came
+// from, so a slice of the Latin text can be traced back to the Devanagari.
+
+use std::fmt;
+use std::ops::Range;
+
+/// Which Latin spelling to produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scheme {
+    /// International Alphabet of Sanskrit Transliteration
+    #[default]
+    Iast,
+    /// Plain ASCII without diacritics
+    Ascii,
+}
+
+/// One piece of the output and the source it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Byte range in the Devanagari text
+    pub source: Range<usize>,
+    /// Byte range in the transliteration
+    pub output: Range<usize>,
+}
+
+// ============================================================================
+// Letters
+// ============================================================================
+
+const VIRAMA: char = '\u{94D}';
+const NUKTA: char = '\u{93C}';
+const ZWNJ: char = '\u{200C}';
+const ZWJ: char = '\u{200D}';
+
+/// (letter, IAST, ASCII), sorted by letter
+const CONSONANTS: &[(char, &str, &str)] = &[
+    ('क', "k", "k"),
+    ('ख', "kh", "kh"),
+    ('ग', "g", "g"),
+    ('घ', "gh", "gh"),
+    ('ङ', "ṅ", "n"),
+    ('च', "c", "ch"),
+    ('छ', "ch", "chh"),
+    ('ज', "j", "j"),
+    ('झ', "jh", "jh"),
+    ('ञ', "ñ", "n"),
+    ('ट', "ṭ", "t"),
+    ('ठ', "ṭh", "th"),
+    ('ड', "ḍ", "d"),
+    ('ढ', "ḍh", "dh"),
+    ('ण', "ṇ", "n"),
+    ('त', "t", "t"),
+    ('थ', "th", "th"),
+    ('द', "d", "d"),
+    ('ध', "dh", "dh"),
+    ('न', "n", "n"),
+    ('\u{929}', "ṉ", "n"),
+    ('प', "p", "p"),
+    ('फ', "ph", "ph"),
+    ('ब', "b", "b"),
+    ('भ', "bh", "bh"),
+    ('म', "m", "m"),
+    ('य', "y", "y"),
+    ('र', "r", "r"),
+    ('\u{931}', "ṟ", "r"),
+    ('ल', "l", "l"),
+    ('ळ', "ḷ", "l"),
+    ('\u{934}', "ḻ", "zh"),
+    ('व', "v", "v"),
+    ('श', "ś", "sh"),
+    ('ष', "ṣ", "sh"),
+    ('स', "s", "s"),
+    ('ह', "h", "h"),
+    ('\u{958}', "q", "q"),
+    ('\u{959}', "k͟h", "kh"),
+    ('\u{95A}', "ġ", "gh"),
+    ('\u{95B}', "z", "z"),
+    ('\u{95C}', "ṛ", "r"),
+    ('\u{95D}', "ṛh", "rh"),
+    ('\u{95E}', "f", "f"),
+    ('\u{95F}', "ẏ", "y"),
+];
+
+/// Consonant + nukta as one letter: क + ़ is क़
+const NUKTA_FORMS: &[(char, char)] = &[
+    ('क', '\u{958}'),
+    ('ख', '\u{959}'),
+    ('ग', '\u{95A}'),
+    ('ज', '\u{95B}'),
+    ('ड', '\u{95C}'),
+    ('ढ', '\u{95D}'),
+    ('न', '\u{929}'),
+    ('फ', '\u{95E}'),
+    ('य', '\u{95F}'),
+    ('र', '\u{931}'),
+    ('ळ', '\u{934}'),
+];
+
+/// (independent vowel, vowel sign, IAST, ASCII); अ has no sign
+const VOWELS: &[(char, Option<char>, &str, &str)] = &[
+    ('अ', None, "a", "a"),
+    ('आ', Some('ा'), "ā", "a"),
+    ('इ', Some('ि'), "i", "i"),
+    ('ई', Some('ी'), "ī", "i"),
+    ('उ', Some('ु'), "u", "u"),
+    ('ऊ', Some('ू'), "ū", "u"),
+    ('ऋ', Some('ृ'), "ṛ", "ri"),
+    ('ॠ', Some('ॄ'), "ṝ", "ri"),
+    ('ऌ', Some('ॢ'), "ḷ", "li"),
+    ('ॡ', Some('ॣ'), "ḹ", "li"),
+    ('ऍ', Some('ॅ'), "ê", "e"),
+    ('ऎ', Some('ॆ'), "ĕ", "e"),
+    ('ए', Some('े'), "e", "e"),
+    ('ऐ', Some('ै'), "ai", "ai"),
+    ('ऑ', Some('ॉ'), "ô", "o"),
+    ('ऒ', Some('ॊ'), "ŏ", "o"),
+    ('ओ', Some('ो'), "o", "o"),
+    ('औ', Some('ौ'), "au", "au"),
+];
+
+/// Everything else with a fixed spelling: (char, IAST, ASCII)
+const SIGNS: &[(char, &str, &str)] = &[
+    ('ँ', "m̐", "n"),
+    ('ः', "ḥ", "h"),
+    ('ऽ', "'", "'"),
+    ('।', "|", "."),
+    ('॥', "||", "."),
+    ('॰', ".", "."),
+    ('ॐ', "oṃ", "om"),
+];
+
+const ANUSVARA: char = 'ं';
+const LABIALS: [char; 5] = ['प', 'फ', 'ब', 'भ', 'म'];
+
+fn pick<'s>(scheme: Scheme, iast: &'s str, ascii: &'s str) -> &'s str {
+    match scheme {
+        Scheme::Iast => iast,
+        Scheme::Ascii => ascii,
+    }
+}
+
+fn consonant(c: char, scheme: Scheme) -> Option<&'static str> {
+    CONSONANTS
+        .iter()
+        .find(|&&(letter, ..)| letter == c)
+        .map(|&(_, iast, ascii)| pick(scheme, iast, ascii))
+}
+
+fn vowel_sign(c: char, scheme: Scheme) -> Option<&'static str> {
+    VOWELS
+        .iter()
+        .find(|&&(_, sign, ..)| sign == Some(c))
+        .map(|&(.., iast, ascii)| pick(scheme, iast, ascii))
+}
+
+fn independent_vowel(c: char, scheme: Scheme) -> Option<&'static str> {
+    VOWELS
+        .iter()
+        .find(|&&(letter, ..)| letter == c)
+        .map(|&(.., iast, ascii)| pick(scheme, iast, ascii))
+}
+
+fn sign(c: char, scheme: Scheme) -> Option<&'static str> {
+    SIGNS
+        .iter()
+        .find(|&&(letter, ..)| letter == c)
+        .map(|&(_, iast, ascii)| pick(scheme, iast, ascii))
+}
+
+fn digit(c: char) -> Option<char> {
+    let n = (c as u32).checked_sub('०' as u32).filter(|&n| n < 10)?;
+    char::from_digit(n, 10)
+}
+
+// ============================================================================
+// Transliteration
+// ============================================================================
+
+/// Latin text for a Devanagari source, with the mapping between the two
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transliteration<'a> {
+    source: &'a str,
+    text: String,
+    segments: Vec<Segment>,
+}
+
+/// Transliterates `source`; anything that isn't Devanagari is copied as is
+pub fn transliterate(source: &str, scheme: Scheme) -> Transliteration<'_> {
+    let mut text = String::with_capacity(source.len());
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let output_start = text.len();
+        if let Some(mut letter) = consonant(c, scheme) {
+            // A nukta makes a different consonant: ज + ़ is ज़ (z)
+            let nukta = chars.next_if(|&(_, next)| next == NUKTA).is_some();
+            let with_nukta = NUKTA_FORMS.iter().find(|&&(base, _)| base == c);
+            if nukta && let Some(&(_, with_nukta)) = with_nukta {
+                letter = consonant(with_nukta, scheme).unwrap();
+            }
+            text.push_str(letter);
+            if nukta && with_nukta.is_none() {
+                text.push(NUKTA);
+            }
+            // Virama: no vowel; a joiner after it only changes the shape
+            // of the conjunct. A vowel sign is its own segment.
+            if chars.next_if(|&(_, next)| next == VIRAMA).is_some() {
+                chars.next_if(|&(_, next)| next == ZWJ || next == ZWNJ);
+            } else if chars
+                .peek()
+                .is_none_or(|&(_, next)| vowel_sign(next, scheme).is_none())
+            {
+                text.push('a');
+            }
+        } else if let Some(latin) = independent_vowel(c, scheme).or_else(|| vowel_sign(c, scheme)) {
+            text.push_str(latin);
+        } else if c == ANUSVARA {
+            // ASCII spells it as the nasal of the next consonant: saṃbandha
+            // is "sambandha" but saṃskṛta is "sanskrita"
+            let labial = chars.peek().is_some_and(|(_, next)| LABIALS.contains(next));
+            text.push_str(match scheme {
+                Scheme::Iast => "ṃ",
+                Scheme::Ascii if labial => "m",
+                Scheme::Ascii => "n",
+            });
+        } else if let Some(latin) = sign(c, scheme) {
+            text.push_str(latin);
+        } else if let Some(d) = digit(c) {
+            text.push(d);
+        } else if matches!(c, VIRAMA | NUKTA | ZWJ | ZWNJ)
+            && let Some(previous) = segments.last_mut()
+        {
+            // Nothing to write; the mark belongs to what came before
+            previous.source.end = start + c.len_utf8();
+            continue;
+        } else {
+            text.push(c);
+        }
+        let end = chars.peek().map_or(source.len(), |&(i, _)| i);
+        segments.push(Segment {
+            source: start..end,
+            output: output_start..text.len(),
+        });
+    }
+
+    Transliteration {
+        source,
+        text,
+        segments,
+    }
+}
+
+pub fn iast(source: &str) -> Transliteration<'_> {
+    transliterate(source, Scheme::Iast)
+}
+
+pub fn ascii(source: &str) -> Transliteration<'_> {
+    transliterate(source, Scheme::Ascii)
+}
+
+impl<'a> Transliteration<'a> {
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The pieces in order; they cover both texts without gaps
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Smallest source range that produced `output`, or None if `output`
+    /// is out of bounds or splits a char of the transliteration
+    pub fn source_range(&self, output: Range<usize>) -> Option<Range<usize>> {
+        self.text.get(output.clone())?;
+        Some(map_range(
+            &self.segments,
+            output,
+            self.source.len(),
+            |s| &s.output,
+            |s| &s.source,
+        ))
+    }
+
+    /// Smallest output range produced from `source`, or None if `source`
+    /// is out of bounds or splits a char of the source
+    pub fn output_range(&self, source: Range<usize>) -> Option<Range<usize>> {
+        self.source.get(source.clone())?;
+        Some(map_range(
+            &self.segments,
+            source,
+            self.text.len(),
+            |s| &s.source,
+            |s| &s.output,
+        ))
+    }
+
+    /// The Devanagari behind a slice of the transliteration
+    pub fn source_of(&self, output: Range<usize>) -> Option<&'a str> {
+        let source = self.source;
+        self.source_range(output).map(|range| &source[range])
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+}
+
+// Segments are in order on both sides, so the segments overlapping `range`
+// on one side are a run, and their union is the range on the other side. An
+// empty range between two segments maps to an empty range between them.
+fn map_range(
+    segments: &[Segment],
+    range: Range<usize>,
+    other_len: usize,
+    from: impl Fn(&Segment) -> &Range<usize>,
+    to: impl Fn(&Segment) -> &Range<usize>,
+) -> Range<usize> {
+    let first = segments.partition_point(|s| from(s).end <= range.start);
+    let last = segments.partition_point(|s| from(s).start < range.end);
+    if first >= last {
+        let at = segments.get(first).map_or(other_len, |s| to(s).start);
+        return at..at;
+    }
+    to(&segments[first]).start..to(&segments[last - 1]).end
+}
+
+impl fmt::Display for Transliteration<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn both(source: &str) -> (String, String) {
+        (iast(source).into_string(), ascii(source).into_string())
+    }
+
+    #[test]
+    fn inherent_vowels_and_matras() {
+        assert_eq!(both("कमल"), ("kamala".into(), "kamala".into()));
+        assert_eq!(both("नमस्ते"), ("namaste".into(), "namaste".into()));
+        let matras = [
+            ("का", "kā"),
+            ("कि", "ki"),
+            ("की", "kī"),
+            ("कु", "ku"),
+            ("कू", "kū"),
+            ("कृ", "kṛ"),
+            ("के", "ke"),
+            ("कै", "kai"),
+            ("को", "ko"),
+            ("कौ", "kau"),
+        ];
+        for (source, expected) in matras {
+            assert_eq!(iast(source).as_str(), expected, "{source}");
+        }
+        assert_eq!(both("आई"), ("āī".into(), "ai".into()));
+        assert_eq!(both("ऋषि"), ("ṛṣi".into(), "rishi".into()));
+    }
+
+    #[test]
+    fn conjuncts() {
+        let cases = [
+            ("क्ष", "kṣa", "ksha"),
+            ("ज्ञान", "jñāna", "jnana"),
+            ("त्र", "tra", "tra"),
+            ("श्री", "śrī", "shri"),
+            ("द्ध", "ddha", "ddha"),
+            ("कर्म", "karma", "karma"),
+            // A joiner after the virama only changes the shape
+            ("क्\u{200D}ष", "kṣa", "ksha"),
+            ("क्\u{200C}ष", "kṣa", "ksha"),
+            // A final virama drops the vowel
+            ("जगत्", "jagat", "jagat"),
+        ];
+        for (source, iast_text, ascii_text) in cases {
+            assert_eq!(
+                both(source),
+                (iast_text.into(), ascii_text.into()),
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn anusvara_and_other_signs() {
+        assert_eq!(both("संस्कृत"), ("saṃskṛta".into(), "sanskrita".into()));
+        assert_eq!(both("संबंध"), ("saṃbaṃdha".into(), "sambandha".into()));
+        assert_eq!(both("हं"), ("haṃ".into(), "han".into()));
+        assert_eq!(both("चाँद"), ("cām̐da".into(), "chanda".into()));
+        assert_eq!(both("दुःख"), ("duḥkha".into(), "duhkha".into()));
+        assert_eq!(both("ॐ।"), ("oṃ|".into(), "om.".into()));
+        assert_eq!(both("२०२४ AD"), ("2024 AD".into(), "2024 AD".into()));
+    }
+
+    #[test]
+    fn nukta() {
+        // Precomposed and with a combining nukta are the same letter
+        assert_eq!(iast("ज़रूर").as_str(), "zarūra");
+        assert_eq!(iast("\u{95B}रूर").as_str(), "zarūra");
+        assert_eq!(ascii("फ़िल्म").as_str(), "filma");
+        // No letter for त + nukta: the nukta is kept, not dropped
+        assert_eq!(iast("त\u{93C}").as_str(), "t\u{93C}a");
+        assert_eq!(ascii("त\u{93C}ि").as_str(), "t\u{93C}i");
+        let latin = iast("त\u{93C}क");
+        assert_eq!(latin.source_of(0..5), Some("त\u{93C}"));
+    }
+
+    // The stand-ins IAST borrows are ambiguous, as the module notes say
+    #[test]
+    fn iast_is_not_reversible() {
+        assert_eq!(
+            iast("ऋ").as_str(),
+            iast("\u{95C}").as_str().trim_end_matches('a')
+        );
+        assert_eq!(iast("ऌ").as_str(), iast("ळ").as_str().trim_end_matches('a'));
+        assert_eq!(iast("खा").as_str(), iast("क्हा").as_str());
+    }
+
+    #[test]
+    fn segments_cover_both_texts() {
+        for source in ["नमस्ते दुनिया", "संस्कृत, ज़रूर!", "त\u{93C}", "", "abc"]
+        {
+            let latin = iast(source);
+            let (mut at_source, mut at_output) = (0, 0);
+            for segment in latin.segments() {
+                assert_eq!(segment.source.start, at_source, "{source}");
+                assert_eq!(segment.output.start, at_output, "{source}");
+                at_source = segment.source.end;
+                at_output = segment.output.end;
+            }
+            assert_eq!(at_source, source.len());
+            assert_eq!(at_output, latin.as_str().len());
+        }
+    }
+
+    #[test]
+    fn ranges_map_both_ways() {
+        let latin = iast("नमस्ते");
+        assert_eq!(latin.as_str(), "namaste");
+        // "ste" comes from स्ते, and "t" alone from त
+        assert_eq!(latin.source_of(4..7), Some("स्ते"));
+        assert_eq!(latin.source_of(5..6), Some("त"));
+        assert_eq!(latin.source_of(5..7), Some("ते"));
+        // The matra of ते is its own segment
+        assert_eq!(latin.output_range(15..18), Some(6..7));
+        assert_eq!(latin.output_range(0..3), Some(0..2));
+        // Empty ranges map to the gap at the same place
+        assert_eq!(latin.output_range(3..3), Some(2..2));
+        assert_eq!(latin.source_range(7..7), Some(18..18));
+        // Inside a char, or past the end
+        assert_eq!(latin.output_range(0..1), None);
+        assert_eq!(latin.output_range(0..19), None);
+        assert_eq!(latin.source_range(0..8), None);
+
+        let sanskrit = iast("संस्कृत");
+        // ṃ is two bytes: cutting it in half is refused
+        assert_eq!(sanskrit.source_range(0..3), None);
+        assert_eq!(sanskrit.source_of(0..5), Some("सं"));
+    }
+}
@@ -0,0 +1,496 @@
+// Windowed operations over numeric slices.
+//
+// Section 10 of main.rs takes `&numbers[3..7]` as one fixed window. Here
+// windows come in three shapes, each a subslice of the input:
+//
+//     Tumbling(3)              [0 1 2] [3 4 5] [6 7 8]
+//     Sliding(3)               [0 1 2] [1 2 3] [2 3 4] ...
+//     Hopping { size 3, hop 2 } [0 1 2] [2 3 4] [4 5 6] ...
+//
+// Moving sums and means keep a running total (add the value coming in,
+// subtract the one going out) and moving min/max keep a monotonic deque of
+// candidate indices, so all of them are O(n) however large the window is.
+// Totals use `Sample::Sum` from the stats module, so integer sums can't
+// overflow. A plain running float total never recovers from a value like
+// 1e20 or inf passing through, so floats get a compensated total with
+// infinities and NaN counted on the side (see `float_moving_sum`).
+
+use std::collections::VecDeque;
+use std::ops::{Add, Range, Sub};
+
+use borrowing_references::stats::Sample;
+
+/// How windows are laid over a slice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Windowing {
+    /// Back-to-back windows of this size
+    Tumbling(usize),
+    /// A window starting at every index
+    Sliding(usize),
+    /// Windows of `size` starting every `hop` elements
+    Hopping { size: usize, hop: usize },
+}
+
+impl Windowing {
+    pub fn size(self) -> usize {
+        match self {
+            Windowing::Tumbling(size) | Windowing::Sliding(size) => size,
+            Windowing::Hopping { size, .. } => size,
+        }
+    }
+
+    pub fn hop(self) -> usize {
+        match self {
+            Windowing::Tumbling(size) => size,
+            Windowing::Sliding(_) => 1,
+            Windowing::Hopping { hop, .. } => hop,
+        }
+    }
+
+    /// Index range of window `n`
+    pub fn range(self, n: usize) -> Range<usize> {
+        let start = n * self.hop();
+        start..start + self.size()
+    }
+
+    /// How many whole windows fit in `len` elements
+    pub fn count(self, len: usize) -> usize {
+        self.check();
+        match len.checked_sub(self.size()) {
+            Some(rest) => rest / self.hop() + 1,
+            None => 0,
+        }
+    }
+
+    fn check(self) {
+        assert!(self.size() > 0, "window size must be non-zero");
+        assert!(self.hop() > 0, "window hop must be non-zero");
+    }
+}
+
+/// Whole windows only; a short tail that can't fill one is left out
+pub fn windows<T>(data: &[T], windowing: Windowing) -> Windows<'_, T> {
+    windowing.check();
+    Windows {
+        data,
+        windowing,
+        next: 0,
+    }
+}
+
+/// Iterator of window subslices, from `windows`
+#[derive(Debug, Clone)]
+pub struct Windows<'a, T> {
+    data: &'a [T],
+    windowing: Windowing,
+    next: usize,
+}
+
+impl<'a, T> Iterator for Windows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        let window = self.data.get(self.windowing.range(self.next))?;
+        self.next += 1;
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.windowing.count(self.data.len()) - self.next;
+        (left, Some(left))
+    }
+}
+
+impl<T> ExactSizeIterator for Windows<'_, T> {}
+
+/// Windows as fixed-size arrays, starting every `hop` elements
+pub fn array_windows<const N: usize, T>(data: &[T], hop: usize) -> impl Iterator<Item = &[T; N]> {
+    windows(data, Windowing::Hopping { size: N, hop })
+        .map(|window| window.try_into().expect("window has N elements"))
+}
+
+/// The windows `keep` returns true for
+pub fn windows_where<T>(
+    data: &[T],
+    windowing: Windowing,
+    mut keep: impl FnMut(&[T]) -> bool,
+) -> impl Iterator<Item = &[T]> {
+    windows(data, windowing).filter(move |window| keep(window))
+}
+
+/// Windows whose sum is above `threshold`, from `moving_sum`
+pub fn windows_with_sum_above<T: Sample>(
+    data: &[T],
+    windowing: Windowing,
+    threshold: T::Sum,
+) -> Vec<&[T]> {
+    moving_sum(data, windowing)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, sum)| sum > threshold)
+        .map(|(n, _)| &data[windowing.range(n)])
+        .collect()
+}
+
+// ============================================================================
+// Moving statistics
+// ============================================================================
+
+/// Sum of every window
+pub fn moving_sum<T: Sample>(data: &[T], windowing: Windowing) -> Vec<T::Sum> {
+    if !T::EXACT_SUM {
+        return float_moving_sum(data, windowing);
+    }
+    let (size, hop) = (windowing.size(), windowing.hop());
+    let mut sums = Vec::with_capacity(windowing.count(data.len()));
+    let mut sum = T::Sum::default();
+    for (i, value) in data.iter().enumerate() {
+        sum = sum + value.widen();
+        if i >= size {
+            sum = sum - data[i - size].widen();
+        }
+        // Window ending at i starts at i + 1 - size
+        if let Some(start) = (i + 1).checked_sub(size)
+            && start % hop == 0
+        {
+            sums.push(sum);
+        }
+    }
+    sums
+}
+
+// Adding 1e20 and subtracting it again loses the small values added in
+// between, so finite floats go into a Neumaier sum, which keeps the rounding
+// error of every addition in a second total. And inf - inf is NaN, so
+// infinities and NaN are counted instead of added; while a window holds any,
+// its sum is +inf, -inf or NaN, whichever the counts say, taken from the
+// newest such value in it.
+fn float_moving_sum<T: Sample>(data: &[T], windowing: Windowing) -> Vec<T::Sum> {
+    let (size, hop) = (windowing.size(), windowing.hop());
+    let mut sums = Vec::with_capacity(windowing.count(data.len()));
+    let mut total = Compensated::default();
+    // For +inf, -inf and NaN: how many the window holds, and where the
+    // newest one is
+    let mut special = [(0, 0); 3];
+    for (i, value) in data.iter().enumerate() {
+        match special_kind(*value) {
+            Some(kind) => special[kind] = (special[kind].0 + 1, i),
+            None => total.add(value.widen()),
+        }
+        if i >= size {
+            let old = data[i - size];
+            match special_kind(old) {
+                Some(kind) => special[kind].0 -= 1,
+                None => total.add(T::Sum::default() - old.widen()),
+            }
+        }
+        if let Some(start) = (i + 1).checked_sub(size)
+            && start % hop == 0
+        {
+            let [(pos, pos_at), (neg, neg_at), (nan, nan_at)] = special;
+            let sum = match (pos > 0, neg > 0) {
+                _ if nan > 0 => data[nan_at].widen(),
+                (true, true) => data[pos_at].widen() + data[neg_at].widen(),
+                (true, false) => data[pos_at].widen(),
+                (false, true) => data[neg_at].widen(),
+                (false, false) => total.value(),
+            };
+            sums.push(sum);
+        }
+    }
+    sums
+}
+
+// 0 for +inf, 1 for -inf, 2 for NaN
+fn special_kind<T: Sample>(value: T) -> Option<usize> {
+    let value = value.to_f64();
+    match value {
+        _ if value.is_nan() => Some(2),
+        f64::INFINITY => Some(0),
+        f64::NEG_INFINITY => Some(1),
+        _ => None,
+    }
+}
+
+// Neumaier's variant of Kahan summation
+#[derive(Debug, Clone, Copy, Default)]
+struct Compensated<S> {
+    sum: S,
+    error: S,
+}
+
+impl<S> Compensated<S>
+where
+    S: Copy + Default + PartialOrd + Add<Output = S> + Sub<Output = S>,
+{
+    fn add(&mut self, value: S) {
+        let abs = |x: S| {
+            if x < S::default() {
+                S::default() - x
+            } else {
+                x
+            }
+        };
+        let total = self.sum + value;
+        // Whatever the rounding lost of the smaller operand
+        let lost = if abs(self.sum) >= abs(value) {
+            (self.sum - total) + value
+        } else {
+            (value - total) + self.sum
+        };
+        self.error = self.error + lost;
+        self.sum = total;
+    }
+
+    fn value(self) -> S {
+        self.sum + self.error
+    }
+}
+
+/// Mean of every window
+pub fn moving_mean<T: Sample>(data: &[T], windowing: Windowing) -> Vec<f64> {
+    let size = windowing.size() as f64;
+    moving_sum(data, windowing)
+        .into_iter()
+        .map(|sum| T::sum_to_f64(sum) / size)
+        .collect()
+}
+
+/// Smallest value of every window
+pub fn moving_min<T: Sample>(data: &[T], windowing: Windowing) -> Vec<T> {
+    monotonic(data, windowing, |a, b| a.total_cmp(b).is_le())
+}
+
+/// Largest value of every window
+pub fn moving_max<T: Sample>(data: &[T], windowing: Windowing) -> Vec<T> {
+    monotonic(data, windowing, |a, b| a.total_cmp(b).is_ge())
+}
+
+// The deque holds indices of the current window whose values are in `keeps`
+// order from front to back; the front is the answer. A new value evicts
+// every candidate from the back that it beats, since those can never be the
+// answer again, and the front leaves once it slides out of the window. Each
+// index is pushed and popped at most once.
+fn monotonic<T: Sample>(
+    data: &[T],
+    windowing: Windowing,
+    keeps: impl Fn(&T, &T) -> bool,
+) -> Vec<T> {
+    let (size, hop) = (windowing.size(), windowing.hop());
+    let mut result = Vec::with_capacity(windowing.count(data.len()));
+    let mut candidates: VecDeque<usize> = VecDeque::with_capacity(size);
+    for (i, value) in data.iter().enumerate() {
+        while let Some(&back) = candidates.back() {
+            if keeps(&data[back], value) {
+                break;
+            }
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+        if let Some(start) = (i + 1).checked_sub(size) {
+            while candidates[0] < start {
+                candidates.pop_front();
+            }
+            if start % hop == 0 {
+                result.push(data[candidates[0]]);
+            }
+        }
+    }
+    result
+}
+
+// ============================================================================
+// Chunks
+// ============================================================================
+
+/// Folds each chunk of `size` elements on its own, starting from `init`.
+/// Unlike `windows`, the last chunk may be shorter.
+pub fn chunked_reduce<T, A: Clone>(
+    data: &[T],
+    size: usize,
+    init: A,
+    mut fold: impl FnMut(A, &T) -> A,
+) -> Vec<A> {
+    data.chunks(size)
+        .map(|chunk| chunk.iter().fold(init.clone(), &mut fold))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ownership::lcg::Lcg;
+
+    use super::*;
+
+    const SIZES: [usize; 5] = [1, 2, 7, 64, 500];
+
+    fn shapes(size: usize) -> [Windowing; 4] {
+        [
+            Windowing::Tumbling(size),
+            Windowing::Sliding(size),
+            Windowing::Hopping { size, hop: 3 },
+            Windowing::Hopping {
+                size,
+                hop: size + 2,
+            },
+        ]
+    }
+
+    #[test]
+    fn window_shapes() {
+        let numbers = [10, 20, 30, 40, 50, 60, 70, 80, 90];
+        let windows_of = |windowing| windows(&numbers, windowing).collect::<Vec<_>>();
+        assert_eq!(
+            windows_of(Windowing::Tumbling(4)),
+            [&numbers[0..4], &numbers[4..8]]
+        );
+        assert_eq!(windows_of(Windowing::Sliding(4)).len(), 6);
+        assert!(windows_of(Windowing::Sliding(4)).contains(&&numbers[3..7]));
+        assert_eq!(
+            windows_of(Windowing::Hopping { size: 4, hop: 3 }),
+            [&numbers[0..4], &numbers[3..7]]
+        );
+        for windowing in shapes(4) {
+            let iter = windows(&numbers, windowing);
+            assert_eq!(iter.len(), windowing.count(numbers.len()));
+            assert_eq!(iter.count(), windowing.count(numbers.len()));
+        }
+        assert_eq!(windows_of(Windowing::Sliding(10)).len(), 0);
+
+        let pairs: Vec<&[i32; 2]> = array_windows(&numbers, 2).collect();
+        assert_eq!(pairs, [&[10, 20], &[30, 40], &[50, 60], &[70, 80]]);
+        assert_eq!(
+            chunked_reduce(&numbers, 4, 0, |sum, &x| sum + x),
+            [100, 260, 90]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn empty_windows_are_refused() {
+        windows(&[1, 2, 3], Windowing::Sliding(0));
+    }
+
+    #[test]
+    fn moving_statistics() {
+        let readings: [i32; 10] = [3, -1, 4, 1, -5, 9, 2, -6, 5, 3];
+        let sliding = Windowing::Sliding(3);
+        assert_eq!(moving_sum(&readings, sliding), [6_i128, 4, 0, 5, 6, 5, 1, 2]);
+        assert_eq!(moving_mean(&readings, sliding)[0], 2.0);
+        assert_eq!(
+            moving_min(&readings, sliding),
+            [-1, -1, -5, -5, -5, -6, -6, -6]
+        );
+        assert_eq!(moving_max(&readings, sliding), [4, 4, 4, 9, 9, 9, 5, 5]);
+        assert_eq!(
+            windows_with_sum_above(&readings, sliding, 5),
+            [&[3, -1, 4][..], &[-5, 9, 2]]
+        );
+        // No overflow: i64 values sum into i128
+        let sums = moving_sum(&[i64::MAX; 4], Windowing::Tumbling(2));
+        assert_eq!(sums, [2 * i64::MAX as i128; 2]);
+    }
+
+    // Every shape and size against brute force on random data
+    #[test]
+    fn integers_match_brute_force() {
+        let mut rng = Lcg::new(42);
+        let data: Vec<i32> = (0..500).map(|_| rng.below(2001) as i32 - 1000).collect();
+        for windowing in SIZES.into_iter().flat_map(shapes) {
+            let windows: Vec<&[i32]> = windows(&data, windowing).collect();
+            let sums: Vec<i128> = windows
+                .iter()
+                .map(|w| w.iter().map(|&x| x as i128).sum())
+                .collect();
+            let mins: Vec<i32> = windows.iter().map(|w| *w.iter().min().unwrap()).collect();
+            let maxs: Vec<i32> = windows.iter().map(|w| *w.iter().max().unwrap()).collect();
+            assert_eq!(moving_sum(&data, windowing), sums, "{windowing:?}");
+            assert_eq!(moving_min(&data, windowing), mins, "{windowing:?}");
+            assert_eq!(moving_max(&data, windowing), maxs, "{windowing:?}");
+        }
+    }
+
+    // The exact sum of a window, rounded once: every finite value is a
+    // multiple of 1/8 and below 2^100, so eighths add up exactly in i128
+    fn exact_sum(window: &[f64]) -> f64 {
+        let count = |kind| {
+            window
+                .iter()
+                .filter(|&&x| special_kind(x) == Some(kind))
+                .count()
+        };
+        match (count(0) > 0, count(1) > 0) {
+            _ if count(2) > 0 => f64::NAN,
+            (true, true) => f64::NAN,
+            (true, false) => f64::INFINITY,
+            (false, true) => f64::NEG_INFINITY,
+            (false, false) => {
+                let eighths: i128 = window.iter().map(|&x| (x * 8.0) as i128).sum();
+                eighths as f64 / 8.0
+            }
+        }
+    }
+
+    #[test]
+    fn floats_match_the_exact_sums() {
+        let mut rng = Lcg::new(7);
+        let floats: Vec<f64> = (0..2000)
+            .map(|i| match rng.below(50) {
+                0 => 1e20 * if i % 2 == 0 { 1.0 } else { -1.0 },
+                1 => f64::INFINITY,
+                2 => f64::NEG_INFINITY,
+                3 => f64::NAN,
+                _ => rng.below(2001) as f64 / 8.0 - 125.0,
+            })
+            .collect();
+        for windowing in SIZES.into_iter().flat_map(shapes) {
+            let expected: Vec<f64> = windows(&floats, windowing).map(exact_sum).collect();
+            let sums = moving_sum(&floats, windowing);
+            assert_eq!(sums.len(), expected.len());
+            for (n, (&got, &want)) in sums.iter().zip(&expected).enumerate() {
+                let close = got == want
+                    || (got.is_nan() && want.is_nan())
+                    || (got - want).abs() <= want.abs() * 1e-15;
+                assert!(close, "{windowing:?} window {n}: {got} != {want}");
+            }
+        }
+    }
+
+    // A spike leaves no trace once it is out of the window
+    #[test]
+    fn floats_recover_after_spikes() {
+        let spiky = [1e20, 1.0, 1.0, f64::INFINITY, 2.0, f64::NAN, 3.0, 4.0];
+        assert_eq!(
+            moving_sum(&spiky, Windowing::Sliding(1))[..3],
+            [1e20, 1.0, 1.0]
+        );
+        let sums = moving_sum(&spiky, Windowing::Sliding(2));
+        assert_eq!(sums[..3], [1e20 + 1.0, 2.0, f64::INFINITY]);
+        assert!(sums[4].is_nan());
+        assert_eq!(sums[6], 7.0);
+        assert_eq!(moving_mean(&spiky, Windowing::Sliding(2))[6], 3.5);
+
+        let long: Vec<f64> = (0..100_000)
+            .map(|i| if i % 1000 == 0 { 1e20 } else { 0.1 })
+            .collect();
+        let sums = moving_sum(&long, Windowing::Sliding(10));
+        assert!((sums[5] - 1.0).abs() < 1e-12, "{}", sums[5]);
+        assert!((sums[99_990] - 1.0).abs() < 1e-12, "{}", sums[99_990]);
+
+        let mixed = [f64::INFINITY, f64::NEG_INFINITY, 1.0, 2.0];
+        let sums = moving_sum(&mixed, Windowing::Sliding(2));
+        assert!(sums[0].is_nan());
+        assert_eq!(sums[1..], [f64::NEG_INFINITY, 3.0]);
+        assert_eq!(
+            moving_sum(&[f32::MAX, f32::MAX], Windowing::Sliding(2)),
+            [2.0 * f32::MAX as f64]
+        );
+    }
+
+    #[test]
+    fn float_thresholds() {
+        let temperatures = [21.5, 22.0, 23.5, 25.0, 24.5, 22.0, 20.5];
+        let warm = windows_with_sum_above(&temperatures, Windowing::Sliding(2), 47.0);
+        assert_eq!(warm, [&[23.5, 25.0][..], &[25.0, 24.5]]);
+    }
+}
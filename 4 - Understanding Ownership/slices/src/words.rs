@@ -0,0 +1,150 @@
+// Zero-copy word splitting.
+//
+// `first_word` and friends in main.rs scan for the byte `b' '`, so a tab,
+// a newline, two spaces in a row or a non-breaking space all confuse them.
+// `Words` splits on every Unicode whitespace character instead, and yields
+// each word as a `&str` slice of the input together with its byte span, so
+// nothing is ever copied.
+//
+// What happens to punctuation is configurable:
+//
+//     Keep   "Hello, world!"  -> "Hello,"  "world!"
+//     Trim   "Hello, world!"  -> "Hello"   "world"    ("don't" stays whole)
+//     Split  "don't"          -> "don"     "t"
+
+use std::ops::Range;
+
+/// What to do with punctuation next to or inside words
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Punctuation {
+    /// Only whitespace separates words; punctuation stays in the word
+    #[default]
+    Keep,
+    /// Punctuation at the start or end of a word is dropped, and words made
+    /// only of punctuation are skipped
+    Trim,
+    /// Every punctuation character separates words
+    Split,
+}
+
+/// A word and where it is in the text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Word<'a> {
+    pub text: &'a str,
+    /// Byte offset of the first byte
+    pub start: usize,
+    /// Byte offset just past the last byte
+    pub end: usize,
+}
+
+impl Word<'_> {
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// ASCII punctuation plus common Unicode punctuation: dashes, quotes,
+/// ellipsis, inverted marks, guillemets, CJK and Devanagari full stops
+pub fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '¿'
+                | '«'
+                | '»'
+                | '·'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{0964}'..='\u{0965}'
+                | '\u{060C}'
+                | '\u{061F}'
+        )
+}
+
+/// Iterator over the words of a string
+#[derive(Debug, Clone)]
+pub struct Words<'a> {
+    text: &'a str,
+    position: usize,
+    punctuation: Punctuation,
+    is_punctuation: fn(char) -> bool,
+}
+
+/// Words separated by any Unicode whitespace, punctuation kept
+pub fn words(text: &str) -> Words<'_> {
+    Words {
+        text,
+        position: 0,
+        punctuation: Punctuation::Keep,
+        is_punctuation,
+    }
+}
+
+/// The `n`th word (0-based), if there is one
+pub fn nth_word(text: &str, n: usize) -> Option<&str> {
+    words(text).nth(n).map(|word| word.text)
+}
+
+impl<'a> Words<'a> {
+    pub fn punctuation(self, punctuation: Punctuation) -> Words<'a> {
+        Words {
+            punctuation,
+            ..self
+        }
+    }
+
+    /// Replaces `is_punctuation` with a custom set
+    pub fn punctuation_chars(self, is_punctuation: fn(char) -> bool) -> Words<'a> {
+        Words {
+            is_punctuation,
+            ..self
+        }
+    }
+
+    /// Just the word slices, without spans
+    pub fn texts(self) -> impl Iterator<Item = &'a str> {
+        self.map(|word| word.text)
+    }
+
+    fn is_separator(&self, c: char) -> bool {
+        c.is_whitespace() || (self.punctuation == Punctuation::Split && (self.is_punctuation)(c))
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Word<'a>> {
+        loop {
+            let rest = &self.text[self.position..];
+            let skipped = rest.find(|c| !self.is_separator(c))?;
+            let start = self.position + skipped;
+            let length = self.text[start..]
+                .find(|c| self.is_separator(c))
+                .unwrap_or(self.text.len() - start);
+            let end = start + length;
+            self.position = end;
+
+            let word = &self.text[start..end];
+            if self.punctuation != Punctuation::Trim {
+                return Some(Word {
+                    text: word,
+                    start,
+                    end,
+                });
+            }
+            let trimmed = word.trim_matches(self.is_punctuation);
+            if !trimmed.is_empty() {
+                let start = start + word.len() - word.trim_start_matches(self.is_punctuation).len();
+                return Some(Word {
+                    text: trimmed,
+                    start,
+                    end: start + trimmed.len(),
+                });
+            }
+        }
+    }
+}
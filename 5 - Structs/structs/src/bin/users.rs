@@ -0,0 +1,437 @@
+// User store CLI: the accounts from structure.rs, kept in a file.
+//
+//   cargo run --bin users                            # demo in a temp directory
+//   cargo run --bin users -- [--store FILE] [--actor NAME] COMMAND
+//
+// Commands (users are kept in FILE, default users.csv; every sign-up and
+// change is logged by NAME, default $USER, in FILE with extension
+// .audit.csv):
+//   add USERNAME EMAIL          sign up a new user
+//   list                        print every user
+//   show USERNAME               print one user
+//   deactivate USERNAME         stop a user from signing in
+//   rename USERNAME NEW_NAME    change a username
+//   record-login USERNAME       count a sign-in
+//   history [USERNAME]          print the audit log, or one user's part
+//   rollback ID                 undo the change logged as #ID (undoing a
+//                               sign-up deactivates the user)
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use slices::timestamp::Timestamp;
+use structs::user::{Field, User, ValidationError};
+use structs::user_audit::{self, AuditError, AuditLog, Change, Entry, Patch};
+use structs::user_store::{StoreError, UserStore};
+
+const USAGE: &str = "usage: users [--store FILE] [--actor NAME] \
+    (add USERNAME EMAIL | list | show USERNAME | deactivate USERNAME \
+    | rename USERNAME NEW_NAME | record-login USERNAME | history [USERNAME] | rollback ID)";
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        demo();
+        return;
+    }
+
+    let mut store_path = String::from("users.csv");
+    let mut actor = std::env::var("USER").unwrap_or_else(|_| String::from("unknown"));
+    while matches!(
+        args.first().map(String::as_str),
+        Some("--store" | "--actor")
+    ) {
+        if args.len() < 3 {
+            exit_with(USAGE);
+        }
+        let value = args.remove(1);
+        match args.remove(0).as_str() {
+            "--store" => store_path = value,
+            _ => actor = value,
+        }
+    }
+
+    if let Err(err) = run(&store_path, &actor, &args) {
+        exit_with(&format!("error: {err}"));
+    }
+}
+
+fn run(store_path: &str, actor: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let log_path = Path::new(store_path).with_extension("audit.csv");
+    let mut store = UserStore::open(store_path)?;
+    let mut log = AuditLog::open(&log_path)?;
+    // Runs a change on one user, logs it and saves both files
+    let mut change = |username: &str,
+                      apply: &dyn Fn(&mut UserStore) -> Result<String, StoreError>|
+     -> Result<(), Box<dyn Error>> {
+        let before = find(&store, username)?.clone();
+        let new_name = apply(&mut store)?;
+        let after = find(&store, &new_name)?.clone();
+        if let Some(entry) = log.record(actor, &before, &after) {
+            println!("#{} {}", entry.id, entry.patch);
+        }
+        store.save(store_path)?;
+        log.save(&log_path)?;
+        Ok(())
+    };
+    match args.as_slice() {
+        ["add", username, email] => {
+            let user = store.add(email, username)?.clone();
+            let entry = log.record_created(actor, &user);
+            println!("#{} added {}", entry.id, user.username);
+            store.save(store_path)?;
+            log.save(&log_path)?;
+        }
+        ["list"] => print_users(&store),
+        ["show", username] => print_user(find(&store, username)?),
+        ["deactivate", username] => change(username, &|store| {
+            Ok(store.deactivate(username)?.username.clone())
+        })?,
+        ["rename", username, new_username] => change(username, &|store| {
+            Ok(store.rename(username, new_username)?.username.clone())
+        })?,
+        ["record-login", username] => change(username, &|store| {
+            store.record_login(username)?;
+            Ok(username.to_string())
+        })?,
+        ["history"] => print_history(log.entries().iter()),
+        ["history", username] => print_history(log.for_user(username).into_iter()),
+        ["rollback", id] => {
+            let Ok(id) = id.parse() else {
+                exit_with(USAGE);
+            };
+            // The user may have been renamed since
+            let username = log.current_username(id)?.to_string();
+            let mut user = find(&store, &username)?.clone();
+            let entry = log.rollback(id, actor, &mut user)?.clone();
+            store.replace(&username, user)?;
+            store.save(store_path)?;
+            log.save(&log_path)?;
+            println!("#{} rolled back #{id}: {}", entry.id, entry.patch);
+        }
+        _ => exit_with(USAGE),
+    }
+    Ok(())
+}
+
+fn find<'a>(store: &'a UserStore, username: &str) -> Result<&'a User, StoreError> {
+    store
+        .get(username)
+        .ok_or_else(|| StoreError::NotFound(username.to_string()))
+}
+
+fn print_history<'a>(entries: impl Iterator<Item = &'a Entry>) {
+    let mut any = false;
+    for entry in entries {
+        any = true;
+        let reverts = entry
+            .reverts
+            .map_or(String::new(), |id| format!(" (rolls back #{id})"));
+        println!(
+            "   #{:<3} {}  {:<16} {:<16} {}{reverts}",
+            entry.id, entry.at, entry.actor, entry.username, entry.patch
+        );
+    }
+    if !any {
+        println!("   No changes logged");
+    }
+}
+
+fn print_users(store: &UserStore) {
+    if store.is_empty() {
+        println!("   No users");
+        return;
+    }
+    println!(
+        "   {:<20} {:<28} {:<8} sign-ins",
+        "username", "email", "active"
+    );
+    for user in store.users() {
+        println!(
+            "   {:<20} {:<28} {:<8} {}",
+            user.username, user.email, user.active, user.sign_in_count
+        );
+    }
+}
+
+fn print_user(user: &User) {
+    println!("   username:      {}", user.username);
+    println!("   email:         {}", user.email);
+    println!("   active:        {}", user.active);
+    println!("   sign_in_count: {}", user.sign_in_count);
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::exit(1);
+}
+
+// The users from structure.rs, saved and loaded again
+fn demo() {
+    println!("=== USER STORE ===\n");
+
+    let dir = std::env::temp_dir().join(format!("users-demo-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("users.csv");
+
+    // ========================================================================
+    // 1. SIGNING UP
+    // ========================================================================
+    println!("1. ADD USERS:");
+    let mut store = UserStore::open(&path).unwrap();
+    assert!(store.is_empty());
+    store.add("someone@gmail.com", "someoneusername").unwrap();
+    store.add("another@example.com", "another").unwrap();
+    store.add("ferris@rust-lang.org", "ferris").unwrap();
+    print_users(&store);
+
+    // ========================================================================
+    // 2. VALIDATION
+    // ========================================================================
+    println!("\n2. VALIDATED AND NORMALIZED:");
+    let user1 = User::new("  Someone@GMAIL.com ", " someoneusername").unwrap();
+    println!("   User::new(\"  Someone@GMAIL.com \", \" someoneusername\")");
+    println!("      -> {:?} {:?}", user1.username, user1.email);
+    assert_eq!(user1.email, "Someone@gmail.com");
+
+    // Every problem at once, each naming its field
+    let errors = User::new("john..doe@-example.c0m.", "4dmin!").unwrap_err();
+    println!("   User::new(\"john..doe@-example.c0m.\", \"4dmin!\"):");
+    for error in errors.errors() {
+        println!("      {error}");
+    }
+    assert_eq!(errors.for_field(Field::Username).count(), 2);
+    assert_eq!(errors.for_field(Field::Email).count(), 3);
+    for (email, username) in [
+        ("root@example.com", "Root"),
+        ("a@b", "ab"),
+        ("ferris@rust_lang.org", "ferris the crab"),
+        ("no-at-sign", &"x".repeat(40)),
+    ] {
+        let errors = User::new(email, username).unwrap_err();
+        println!("   ({email:?}, {username:?}): {errors}");
+    }
+    assert!(matches!(
+        User::new("root@example.com", "Root").unwrap_err().errors(),
+        [ValidationError::Reserved { .. }]
+    ));
+
+    // Struct update syntax still works; `validated` checks the result
+    let user2 = User {
+        email: String::from("Another@Example.COM"),
+        ..user1.clone()
+    }
+    .validated()
+    .unwrap();
+    println!(
+        "   User {{ email: \"Another@Example.COM\", ..user1 }}.validated() -> {:?}",
+        user2.email
+    );
+    assert_eq!(user2.email, "Another@example.com");
+    let user3 = User {
+        email: String::from("not an email"),
+        ..user1
+    };
+    println!(
+        "   User {{ email: \"not an email\", ..user1 }}.validated() -> {}",
+        user3.validated().unwrap_err()
+    );
+
+    // ========================================================================
+    // 3. UNIQUENESS
+    // ========================================================================
+    println!("\n3. DUPLICATES ARE REJECTED:");
+    for (email, username) in [
+        ("new@example.com", "Ferris"),
+        ("ANOTHER@example.com", "someone_else"),
+        ("x@example.com", "  "),
+    ] {
+        let err = store.add(email, username).unwrap_err();
+        println!("   add({username:?}, {email:?}): {err}");
+    }
+    assert!(matches!(
+        store.add("new@example.com", "FERRIS"),
+        Err(StoreError::DuplicateUsername(_))
+    ));
+    assert!(matches!(
+        store.add("Someone@Gmail.com", "new"),
+        Err(StoreError::DuplicateEmail(_))
+    ));
+    assert_eq!(store.len(), 3);
+
+    // ========================================================================
+    // 4. CHANGES
+    // ========================================================================
+    println!("\n4. SIGN-INS, RENAMES AND DEACTIVATION:");
+    assert_eq!(store.record_login("ferris").unwrap(), 2);
+    assert_eq!(store.record_login("ferris").unwrap(), 3);
+    store.rename("someoneusername", "someone").unwrap();
+    store.rename("another", "Another").unwrap();
+    store.deactivate("another").unwrap();
+    print_users(&store);
+    for err in [
+        store.record_login("another").unwrap_err(),
+        store.deactivate("another").unwrap_err(),
+        store.rename("ferris", "someone").unwrap_err(),
+        store.rename("ferris", "admin").unwrap_err(),
+        store.record_login("nobody").unwrap_err(),
+    ] {
+        println!("   {err}");
+    }
+
+    // ========================================================================
+    // 5. SAVING AND LOADING
+    // ========================================================================
+    println!("\n5. SAVE AND LOAD:");
+    store.save(&path).unwrap();
+    for line in fs::read_to_string(&path).unwrap().lines() {
+        println!("   {line}");
+    }
+    let loaded = UserStore::open(&path).unwrap();
+    assert_eq!(loaded, store);
+    assert!(!path.with_extension("tmp").exists());
+    println!("   loaded {} users, same as saved", loaded.len());
+
+    // A hand-edited file is checked like new sign-ups
+    fs::write(
+        &path,
+        "username,email,active,sign_in_count\nalice,a@x.org,true,1\nALICE,b@x.org,true,1\n",
+    )
+    .unwrap();
+    println!(
+        "   duplicate in file: {}",
+        UserStore::open(&path).unwrap_err()
+    );
+    fs::write(
+        &path,
+        "username,email,active,sign_in_count\nalice,a@x.org,yes,1\n",
+    )
+    .unwrap();
+    println!(
+        "   bad value in file: {}",
+        UserStore::open(&path).unwrap_err()
+    );
+    fs::write(
+        &path,
+        "username,email,active,sign_in_count\nalice,not-an-email,true,1\n",
+    )
+    .unwrap();
+    println!(
+        "   invalid user in file: {}",
+        UserStore::open(&path).unwrap_err()
+    );
+
+    // Saving fails before anything is renamed
+    let missing = dir.join("no-such-dir").join("users.csv");
+    println!(
+        "   save into a missing directory: {}",
+        store.save(&missing).unwrap_err()
+    );
+
+    // ========================================================================
+    // 6. CHANGE TRACKING
+    // ========================================================================
+    println!("\n6. DIFF, PATCH AND AUDIT LOG:");
+    let user1 = User::new("someone@gmail.com", "someoneusername").unwrap();
+    let user2 = User {
+        email: String::from("another@example.com"),
+        ..user1.clone()
+    };
+    let user3 = User {
+        active: false,
+        sign_in_count: 5,
+        ..user2.clone()
+    };
+    let patch = user_audit::diff(&user1, &user3);
+    println!(
+        "   diff(user1, user2): {}",
+        user_audit::diff(&user1, &user2)
+    );
+    println!("   diff(user1, user3): {patch}");
+    assert_eq!(
+        patch.fields().collect::<Vec<_>>(),
+        [Field::Active, Field::Email, Field::SignInCount]
+    );
+    assert!(user_audit::diff(&user1, &user1.clone()).is_empty());
+
+    // A patch and its inverse take user1 to user3 and back
+    let mut user = user1.clone();
+    patch.apply(&mut user).unwrap();
+    assert_eq!(user, user3);
+    patch.invert().apply(&mut user).unwrap();
+    assert_eq!(user, user1);
+    let mut again = user3.clone();
+    let conflict = patch.apply(&mut again).unwrap_err();
+    println!("   applying it to user3 again: {conflict}");
+    assert!(matches!(
+        conflict,
+        AuditError::Conflict {
+            field: Field::Active,
+            ..
+        }
+    ));
+    assert_eq!(again, user3);
+
+    let mut csv = Vec::new();
+    patch.write(&mut csv).unwrap();
+    println!("   as CSV:");
+    for line in String::from_utf8(csv.clone()).unwrap().lines() {
+        println!("      {line}");
+    }
+    assert_eq!(Patch::read(&csv[..]).unwrap(), patch);
+    let unknown = Patch::read("field,old,new\nage,30,31\n".as_bytes()).unwrap_err();
+    println!("   reading a patch for `age`: {unknown}");
+    let twice = Patch::read("field,old,new\nactive,true,false\nactive,false,true\n".as_bytes())
+        .unwrap_err();
+    println!("   reading a patch that changes `active` twice: {twice}");
+    assert!(matches!(
+        Patch::new(vec![Change::SignInCount { old: 1, new: 2 }])
+            .unwrap()
+            .changes(),
+        [Change::SignInCount { .. }]
+    ));
+
+    // Fixed times so the log prints the same every run
+    let at = |time: &str| -> Timestamp { format!("2026-10-19T{time}Z").parse().unwrap() };
+    let mut log = AuditLog::new();
+    let mut edit = |time: &str, actor: &str, change: &dyn Fn(&mut User)| {
+        let before = user.clone();
+        change(&mut user);
+        log.record_at(at(time), actor, &before, &user).is_some()
+    };
+    assert!(edit("09:00:00", "someoneusername", &|u| u.sign_in_count += 1));
+    assert!(edit("09:30:00", "support", &|u| {
+        u.email = String::from("someone@example.org")
+    }));
+    assert!(edit("10:00:00", "ferris", &|u| u.username =
+        String::from("someone")));
+    assert!(!edit("10:15:00", "ferris", &|_| {}));
+    println!("   log:");
+    print_history(log.entries().iter());
+
+    let entry = log
+        .rollback_at(at("11:00:00"), 2, "ferris", &mut user)
+        .unwrap();
+    println!("   rollback of #2 logged as #{}: {}", entry.id, entry.patch);
+    assert_eq!(user.email, "someone@gmail.com");
+    let conflict = log
+        .rollback_at(at("11:05:00"), 2, "ferris", &mut user)
+        .unwrap_err();
+    println!("   rolling #2 back again: {conflict}");
+    println!("   log for \"someone\", following the rename:");
+    print_history(log.for_user("someone").into_iter());
+    assert_eq!(log.for_user("someone").len(), 4);
+
+    let log_path = path.with_extension("audit.csv");
+    log.save(&log_path).unwrap();
+    let loaded = AuditLog::open(&log_path).unwrap();
+    assert_eq!(loaded, log);
+    println!("   saved and loaded {} entries:", loaded.entries().len());
+    for line in fs::read_to_string(&log_path).unwrap().lines() {
+        println!("      {line}");
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,3 @@
+pub mod user;
+pub mod user_audit;
+pub mod user_store;
@@ -0,0 +1,651 @@
+// The `User` struct from structure.rs, shared by the user modules.
+//
+// structure.rs defines it inside a bin, so every value is thrown away when
+// `main` returns. Here it is a library type that can be stored, loaded and
+// built by the same `build_user` function as in the lesson.
+//
+// `build_user` takes any strings. `User::new` checks and normalizes them
+// first, and reports every problem at once instead of stopping at the
+// first:
+//
+//     username  3 to 32 ASCII letters, digits, '_', '-' or '.', starting
+//               with a letter, and not a reserved name like "admin"
+//     email     local@domain, where the local part is an RFC 5322 dot-atom
+//               and the domain is DNS labels; the domain is lowercased
+//
+// The fields stay public, so struct update syntax works as in the lesson:
+// `User { email, ..user1 }`. Such a value hasn't been checked, so pass it
+// through `validated` before trusting it.
+
+use std::error::Error;
+use std::fmt;
+
+/// An account, with the same fields as in structure.rs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub active: bool,
+    pub username: String,
+    pub email: String,
+    pub sign_in_count: u64,
+}
+
+/// A new active user that has signed in once, as in structure.rs. Nothing
+/// is checked; `User::new` is the validating version.
+pub fn build_user(email: String, username: String) -> User {
+    User {
+        active: true,
+        username,
+        email,
+        sign_in_count: 1,
+    }
+}
+
+impl User {
+    /// `build_user` with a normalized, valid email and username
+    pub fn new(email: &str, username: &str) -> Result<User, ValidationErrors> {
+        build_user(email.to_string(), username.to_string()).validated()
+    }
+
+    /// The same user with normalized fields, or everything wrong with it
+    pub fn validated(self) -> Result<User, ValidationErrors> {
+        let mut errors = Vec::new();
+        let username = check_username(&self.username, &mut errors);
+        let email = check_email(&self.email, &mut errors);
+        if !errors.is_empty() {
+            return Err(ValidationErrors(errors));
+        }
+        Ok(User {
+            username,
+            email,
+            ..self
+        })
+    }
+}
+
+/// `username` trimmed, if it is valid
+pub fn normalize_username(username: &str) -> Result<String, ValidationErrors> {
+    let mut errors = Vec::new();
+    let username = check_username(username, &mut errors);
+    ValidationErrors::or(errors, username)
+}
+
+/// `email` trimmed with its domain lowercased, if it is valid
+pub fn normalize_email(email: &str) -> Result<String, ValidationErrors> {
+    let mut errors = Vec::new();
+    let email = check_email(email, &mut errors);
+    ValidationErrors::or(errors, email)
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// A field of `User`; validation errors are about Username or Email
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    Active,
+    Username,
+    Email,
+    SignInCount,
+}
+
+impl Field {
+    pub const ALL: [Field; 4] = [
+        Field::Active,
+        Field::Username,
+        Field::Email,
+        Field::SignInCount,
+    ];
+
+    /// The name of the struct field
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Active => "active",
+            Field::Username => "username",
+            Field::Email => "email",
+            Field::SignInCount => "sign_in_count",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Field> {
+        Field::ALL.into_iter().find(|field| field.name() == name)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    Empty {
+        field: Field,
+    },
+    /// Lengths are in chars
+    TooShort {
+        field: Field,
+        min: usize,
+        found: usize,
+    },
+    TooLong {
+        field: Field,
+        max: usize,
+        found: usize,
+    },
+    /// A char that isn't allowed, at a byte offset of the trimmed value
+    InvalidChar {
+        field: Field,
+        found: char,
+        position: usize,
+    },
+    /// The username is kept for the system, ignoring case
+    Reserved {
+        field: Field,
+        name: String,
+    },
+    /// The chars are allowed but not in this arrangement
+    Malformed {
+        field: Field,
+        reason: &'static str,
+    },
+}
+
+impl ValidationError {
+    pub fn field(&self) -> Field {
+        match *self {
+            ValidationError::Empty { field }
+            | ValidationError::TooShort { field, .. }
+            | ValidationError::TooLong { field, .. }
+            | ValidationError::InvalidChar { field, .. }
+            | ValidationError::Reserved { field, .. }
+            | ValidationError::Malformed { field, .. } => field,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.field())?;
+        match self {
+            ValidationError::Empty { .. } => write!(f, "must not be empty"),
+            ValidationError::TooShort { min, found, .. } => {
+                write!(f, "{found} characters, needs at least {min}")
+            }
+            ValidationError::TooLong { max, found, .. } => {
+                write!(f, "{found} characters, allows at most {max}")
+            }
+            ValidationError::InvalidChar {
+                found, position, ..
+            } => write!(f, "{found:?} at byte {position} is not allowed"),
+            ValidationError::Reserved { name, .. } => write!(f, "{name:?} is reserved"),
+            ValidationError::Malformed { reason, .. } => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// Every problem found with a user, in field order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(Vec<ValidationError>);
+
+impl ValidationErrors {
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.0
+    }
+
+    /// The problems with one field
+    pub fn for_field(&self, field: Field) -> impl Iterator<Item = &ValidationError> {
+        self.0.iter().filter(move |error| error.field() == field)
+    }
+
+    fn or<T>(errors: Vec<ValidationError>, value: T) -> Result<T, ValidationErrors> {
+        if errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(ValidationErrors(errors))
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ValidationErrors {}
+
+impl IntoIterator for ValidationErrors {
+    type Item = ValidationError;
+    type IntoIter = std::vec::IntoIter<ValidationError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+// ============================================================================
+// Username
+// ============================================================================
+
+const USERNAME_MIN: usize = 3;
+const USERNAME_MAX: usize = 32;
+
+/// Names that would look official, compared ignoring case
+pub const RESERVED_USERNAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "anonymous",
+    "api",
+    "help",
+    "moderator",
+    "null",
+    "root",
+    "support",
+    "system",
+    "www",
+];
+
+fn check_username(username: &str, errors: &mut Vec<ValidationError>) -> String {
+    let field = Field::Username;
+    let username = username.trim();
+    if username.is_empty() {
+        errors.push(ValidationError::Empty { field });
+        return String::new();
+    }
+
+    let len = username.chars().count();
+    if len < USERNAME_MIN {
+        errors.push(ValidationError::TooShort {
+            field,
+            min: USERNAME_MIN,
+            found: len,
+        });
+    } else if len > USERNAME_MAX {
+        errors.push(ValidationError::TooLong {
+            field,
+            max: USERNAME_MAX,
+            found: len,
+        });
+    }
+    invalid_chars(field, username, 0, |c| {
+        c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')
+    })
+    .for_each(|error| errors.push(error));
+    if !username.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        errors.push(ValidationError::Malformed {
+            field,
+            reason: "must start with a letter",
+        });
+    }
+    if RESERVED_USERNAMES
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(username))
+    {
+        errors.push(ValidationError::Reserved {
+            field,
+            name: username.to_string(),
+        });
+    }
+    username.to_string()
+}
+
+// Each disallowed char once, at its first position
+fn invalid_chars(
+    field: Field,
+    text: &str,
+    offset: usize,
+    allowed: impl Fn(char) -> bool,
+) -> impl Iterator<Item = ValidationError> {
+    let mut seen = Vec::new();
+    text.char_indices()
+        .filter(move |&(_, c)| {
+            let new = !allowed(c) && !seen.contains(&c);
+            if new {
+                seen.push(c);
+            }
+            new
+        })
+        .map(move |(i, found)| ValidationError::InvalidChar {
+            field,
+            found,
+            position: offset + i,
+        })
+}
+
+// ============================================================================
+// Email
+// ============================================================================
+
+const EMAIL_MAX: usize = 254;
+const LOCAL_MAX: usize = 64;
+const LABEL_MAX: usize = 63;
+
+// RFC 5322 atext: what a dot-atom may contain besides dots
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+}
+
+fn check_email(email: &str, errors: &mut Vec<ValidationError>) -> String {
+    let field = Field::Email;
+    let email = email.trim();
+    if email.is_empty() {
+        errors.push(ValidationError::Empty { field });
+        return String::new();
+    }
+    let malformed = |reason| ValidationError::Malformed { field, reason };
+
+    let len = email.chars().count();
+    if len > EMAIL_MAX {
+        errors.push(ValidationError::TooLong {
+            field,
+            max: EMAIL_MAX,
+            found: len,
+        });
+    }
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        errors.push(malformed("missing @"));
+        return email.to_string();
+    };
+
+    // Local part: a dot-atom, kept as written since it may be case-sensitive
+    if local.is_empty() {
+        errors.push(malformed("nothing before @"));
+    } else if local.len() > LOCAL_MAX {
+        errors.push(malformed("more than 64 characters before @"));
+    }
+    errors.extend(invalid_chars(field, local, 0, |c| is_atext(c) || c == '.'));
+    if local.starts_with('.') || local.ends_with('.') || local.contains("..") {
+        errors.push(malformed("dots before @ must be between other characters"));
+    }
+
+    // Domain: DNS labels, ASCII only (internationalized domains need to be
+    // written in their xn-- form)
+    let domain_start = local.len() + 1;
+    if domain.is_empty() {
+        errors.push(malformed("nothing after @"));
+        return email.to_string();
+    }
+    errors.extend(invalid_chars(field, domain, domain_start, |c| {
+        c.is_ascii_alphanumeric() || c == '-' || c == '.'
+    }));
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        errors.push(malformed("domain needs at least one dot"));
+    }
+    if labels.iter().any(|label| label.is_empty()) {
+        errors.push(malformed("domain has an empty label"));
+    }
+    if labels.iter().any(|label| label.len() > LABEL_MAX) {
+        errors.push(malformed("domain label longer than 63 characters"));
+    }
+    if labels
+        .iter()
+        .any(|label| label.starts_with('-') || label.ends_with('-'))
+    {
+        errors.push(malformed("domain labels can't start or end with '-'"));
+    }
+    if labels.len() >= 2
+        && labels
+            .last()
+            .is_some_and(|tld| !tld.is_empty() && tld.chars().all(|c| c.is_ascii_digit()))
+    {
+        errors.push(malformed("top-level domain can't be all digits"));
+    }
+
+    format!("{local}@{}", domain.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ValidationError::*;
+
+    fn username_errors(username: &str) -> Vec<ValidationError> {
+        normalize_username(username).map_or_else(|errors| errors.0, |_| Vec::new())
+    }
+
+    fn email_errors(email: &str) -> Vec<ValidationError> {
+        normalize_email(email).map_or_else(|errors| errors.0, |_| Vec::new())
+    }
+
+    fn malformed(field: Field, reason: &'static str) -> ValidationError {
+        Malformed { field, reason }
+    }
+
+    #[test]
+    fn username_errors_by_variant() {
+        let field = Field::Username;
+        let long = format!("a{}", "b".repeat(32));
+        let cases = [
+            ("ferris", vec![]),
+            ("  a.b-c_9  ", vec![]),
+            ("", vec![Empty { field }]),
+            ("   ", vec![Empty { field }]),
+            (
+                "ab",
+                vec![TooShort {
+                    field,
+                    min: 3,
+                    found: 2,
+                }],
+            ),
+            // Lengths are in chars, not bytes
+            (
+                "éé",
+                vec![
+                    TooShort {
+                        field,
+                        min: 3,
+                        found: 2,
+                    },
+                    InvalidChar {
+                        field,
+                        found: 'é',
+                        position: 0,
+                    },
+                    malformed(field, "must start with a letter"),
+                ],
+            ),
+            (
+                &long,
+                vec![TooLong {
+                    field,
+                    max: 32,
+                    found: 33,
+                }],
+            ),
+            // Each bad char once, at its first byte
+            (
+                "a b!c d",
+                vec![
+                    InvalidChar {
+                        field,
+                        found: ' ',
+                        position: 1,
+                    },
+                    InvalidChar {
+                        field,
+                        found: '!',
+                        position: 3,
+                    },
+                ],
+            ),
+            (
+                "_ferris",
+                vec![malformed(field, "must start with a letter")],
+            ),
+            ("9lives", vec![malformed(field, "must start with a letter")]),
+            (
+                "Admin",
+                vec![Reserved {
+                    field,
+                    name: "Admin".to_string(),
+                }],
+            ),
+        ];
+        for (username, expected) in cases {
+            assert_eq!(username_errors(username), expected, "{username:?}");
+        }
+    }
+
+    #[test]
+    fn email_errors_by_variant() {
+        let field = Field::Email;
+        let long = format!("a@{}.com", vec!["b".repeat(60); 5].join("."));
+        let cases = [
+            ("a@example.com", vec![]),
+            ("", vec![Empty { field }]),
+            (
+                &long,
+                vec![TooLong {
+                    field,
+                    max: 254,
+                    found: 310,
+                }],
+            ),
+            (
+                "a b@example.com",
+                vec![InvalidChar {
+                    field,
+                    found: ' ',
+                    position: 1,
+                }],
+            ),
+            // Positions in the domain count from the start of the email
+            (
+                "a@exa_mple.com",
+                vec![InvalidChar {
+                    field,
+                    found: '_',
+                    position: 5,
+                }],
+            ),
+            ("example.com", vec![malformed(field, "missing @")]),
+            ("@example.com", vec![malformed(field, "nothing before @")]),
+            ("a@", vec![malformed(field, "nothing after @")]),
+            (
+                "a@localhost",
+                vec![malformed(field, "domain needs at least one dot")],
+            ),
+            (
+                "a@example..com",
+                vec![malformed(field, "domain has an empty label")],
+            ),
+            (
+                "a@-example.com",
+                vec![malformed(
+                    field,
+                    "domain labels can't start or end with '-'",
+                )],
+            ),
+            (
+                "a@10.0.0.1",
+                vec![malformed(field, "top-level domain can't be all digits")],
+            ),
+        ];
+        for (email, expected) in cases {
+            assert_eq!(email_errors(email), expected, "{email:?}");
+        }
+
+        let local = "a".repeat(65);
+        assert_eq!(
+            email_errors(&format!("{local}@example.com")),
+            [malformed(field, "more than 64 characters before @")]
+        );
+        let label = "a".repeat(64);
+        assert_eq!(
+            email_errors(&format!("a@{label}.com")),
+            [malformed(field, "domain label longer than 63 characters")]
+        );
+    }
+
+    #[test]
+    fn local_parts_are_dot_atoms() {
+        let dots = malformed(
+            Field::Email,
+            "dots before @ must be between other characters",
+        );
+        let cases = [
+            ("john.doe", vec![]),
+            ("o'brien+tag", vec![]),
+            ("!#$%&'*+-/=?^_`{|}~", vec![]),
+            (".john", vec![dots.clone()]),
+            ("john.", vec![dots.clone()]),
+            ("john..doe", vec![dots.clone()]),
+            // Quoted local parts and comments aren't dot-atoms
+            (
+                "\"john\"",
+                vec![InvalidChar {
+                    field: Field::Email,
+                    found: '"',
+                    position: 0,
+                }],
+            ),
+            (
+                "john(x)",
+                vec![
+                    InvalidChar {
+                        field: Field::Email,
+                        found: '(',
+                        position: 4,
+                    },
+                    InvalidChar {
+                        field: Field::Email,
+                        found: ')',
+                        position: 6,
+                    },
+                ],
+            ),
+        ];
+        for (local, expected) in cases {
+            assert_eq!(
+                email_errors(&format!("{local}@example.com")),
+                expected,
+                "{local:?}"
+            );
+        }
+        // The last @ splits, so an @ in the local part is a bad char
+        assert_eq!(
+            email_errors("a@b@example.com"),
+            [InvalidChar {
+                field: Field::Email,
+                found: '@',
+                position: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn only_the_domain_is_lowercased() {
+        let cases = [
+            ("John.Doe@Example.COM", "John.Doe@example.com"),
+            ("  someone@GMAIL.com ", "someone@gmail.com"),
+            ("ABC@xn--BCHER-KVA.example", "ABC@xn--bcher-kva.example"),
+        ];
+        for (email, expected) in cases {
+            assert_eq!(normalize_email(email).unwrap(), expected);
+        }
+
+        let user = User::new(" Someone@GMAIL.com", " Someone ").unwrap();
+        assert_eq!(user.email, "Someone@gmail.com");
+        assert_eq!(user.username, "Someone");
+    }
+
+    #[test]
+    fn every_problem_is_reported_in_field_order() {
+        let errors = User::new("john..doe@-example.c0m.", "4dmin!").unwrap_err();
+        let fields: Vec<Field> = errors.errors().iter().map(ValidationError::field).collect();
+        assert!(fields.is_sorted());
+        assert_eq!(errors.for_field(Field::Username).count(), 2);
+        assert_eq!(errors.for_field(Field::Email).count(), 3);
+    }
+}
@@ -0,0 +1,823 @@
+// What changed between two `User` values, and a log of who changed what.
+//
+// structure.rs makes `user2` and `user3` from `user1` with struct update
+// syntax, and afterwards nothing says which fields differ. `diff` compares
+// two users field by field and returns a `Patch`:
+//
+//     email: "someone@gmail.com" -> "another@example.com"
+//     sign_in_count: 1 -> 2
+//
+// A patch remembers the old value of every field it changes, so it can be
+// inverted to undo itself, and `apply` refuses to run on a user whose
+// fields no longer hold those old values. Patches are written as CSV rows
+// of `field,old,new`.
+//
+// `AuditLog` keeps one entry per change: when, who, which user and the
+// patch. Rolling an entry back applies its inverse and is logged like any
+// other change. A sign-up is logged as the diff from a blank user, with an
+// empty username before it; users are never deleted, so rolling a sign-up
+// back deactivates the user instead. The log file adds the entry columns to
+// every patch row:
+//
+//     id,at,actor,username,reverts,field,old,new
+//     1,2026-10-19T08:00:00Z,ferris,,,active,false,true
+//     1,2026-10-19T08:00:00Z,ferris,,,username,,someone
+//     ...
+//     2,2026-10-19T08:05:00Z,ferris,someone,,email,someone@gmail.com,another@example.com
+//     3,2026-10-19T08:10:00Z,ferris,someone,2,email,another@example.com,someone@gmail.com
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+use datatypes::typed_rows::{Dialect, ParseError, TupleReader, TupleWriter};
+use slices::timestamp::{Timestamp, TimestampError};
+
+use crate::user::{Field, User};
+use crate::user_store::write_atomically;
+
+#[derive(Debug)]
+pub enum AuditError {
+    Io(io::Error),
+    Csv(ParseError),
+    /// A saved field name that `User` doesn't have
+    UnknownField(String),
+    /// A patch may change each field only once
+    DuplicateField(Field),
+    /// A saved value that doesn't fit its field
+    InvalidValue {
+        field: Field,
+        value: String,
+    },
+    InvalidTimestamp {
+        text: String,
+        error: TimestampError,
+    },
+    /// The user no longer holds the value the patch expects to replace
+    Conflict {
+        field: Field,
+        expected: String,
+        found: String,
+    },
+    /// Entries are numbered from 1
+    NoSuchEntry {
+        id: u64,
+        entries: usize,
+    },
+    /// A saved entry is out of order or refers to a missing entry
+    InvalidEntry {
+        id: u64,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for AuditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditError::Io(err) => write!(f, "I/O error: {err}"),
+            AuditError::Csv(err) => write!(f, "{err}"),
+            AuditError::UnknownField(name) => write!(f, "User has no field {name:?}"),
+            AuditError::DuplicateField(field) => write!(f, "{field} is changed twice"),
+            AuditError::InvalidValue { field, value } => {
+                write!(f, "{value:?} is not a valid {field}")
+            }
+            AuditError::InvalidTimestamp { text, error } => {
+                write!(f, "invalid timestamp {text:?}: {error}")
+            }
+            AuditError::Conflict {
+                field,
+                expected,
+                found,
+            } => write!(f, "{field} is {found}, but the patch expects {expected}"),
+            AuditError::NoSuchEntry { id, entries } => {
+                write!(f, "no entry #{id} ({entries} in the log)")
+            }
+            AuditError::InvalidEntry { id, reason } => write!(f, "entry #{id}: {reason}"),
+        }
+    }
+}
+
+impl Error for AuditError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AuditError::Io(err) => Some(err),
+            AuditError::Csv(err) => Some(err),
+            AuditError::InvalidTimestamp { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AuditError {
+    fn from(err: io::Error) -> AuditError {
+        AuditError::Io(err)
+    }
+}
+
+impl From<ParseError> for AuditError {
+    fn from(err: ParseError) -> AuditError {
+        AuditError::Csv(err)
+    }
+}
+
+// ============================================================================
+// Changes and patches
+// ============================================================================
+
+/// One field's old and new value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Active { old: bool, new: bool },
+    Username { old: String, new: String },
+    Email { old: String, new: String },
+    SignInCount { old: u64, new: u64 },
+}
+
+impl Change {
+    pub fn field(&self) -> Field {
+        match self {
+            Change::Active { .. } => Field::Active,
+            Change::Username { .. } => Field::Username,
+            Change::Email { .. } => Field::Email,
+            Change::SignInCount { .. } => Field::SignInCount,
+        }
+    }
+
+    /// The change that undoes this one
+    pub fn invert(&self) -> Change {
+        match self.clone() {
+            Change::Active { old, new } => Change::Active { old: new, new: old },
+            Change::Username { old, new } => Change::Username { old: new, new: old },
+            Change::Email { old, new } => Change::Email { old: new, new: old },
+            Change::SignInCount { old, new } => Change::SignInCount { old: new, new: old },
+        }
+    }
+
+    /// (field, old, new) as text
+    pub fn to_strings(&self) -> (Field, String, String) {
+        let field = self.field();
+        match self {
+            Change::Active { old, new } => (field, old.to_string(), new.to_string()),
+            Change::Username { old, new } | Change::Email { old, new } => {
+                (field, old.clone(), new.clone())
+            }
+            Change::SignInCount { old, new } => (field, old.to_string(), new.to_string()),
+        }
+    }
+
+    /// Parses the text written by `to_strings`
+    pub fn from_strings(field: &str, old: &str, new: &str) -> Result<Change, AuditError> {
+        let field =
+            Field::from_name(field).ok_or_else(|| AuditError::UnknownField(field.to_string()))?;
+        let parse = |value: &str| AuditError::InvalidValue {
+            field,
+            value: value.to_string(),
+        };
+        Ok(match field {
+            Field::Active => Change::Active {
+                old: old.parse().map_err(|_| parse(old))?,
+                new: new.parse().map_err(|_| parse(new))?,
+            },
+            Field::Username => Change::Username {
+                old: old.to_string(),
+                new: new.to_string(),
+            },
+            Field::Email => Change::Email {
+                old: old.to_string(),
+                new: new.to_string(),
+            },
+            Field::SignInCount => Change::SignInCount {
+                old: old.parse().map_err(|_| parse(old))?,
+                new: new.parse().map_err(|_| parse(new))?,
+            },
+        })
+    }
+
+    // The value this field of `user` holds now, as text
+    fn current(&self, user: &User) -> String {
+        match self {
+            Change::Active { .. } => user.active.to_string(),
+            Change::Username { .. } => user.username.clone(),
+            Change::Email { .. } => user.email.clone(),
+            Change::SignInCount { .. } => user.sign_in_count.to_string(),
+        }
+    }
+
+    fn set(&self, user: &mut User) {
+        match self {
+            Change::Active { new, .. } => user.active = *new,
+            Change::Username { new, .. } => user.username = new.clone(),
+            Change::Email { new, .. } => user.email = new.clone(),
+            Change::SignInCount { new, .. } => user.sign_in_count = *new,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Username { old, new } | Change::Email { old, new } => {
+                write!(f, "{}: {old:?} -> {new:?}", self.field())
+            }
+            _ => {
+                let (field, old, new) = self.to_strings();
+                write!(f, "{field}: {old} -> {new}")
+            }
+        }
+    }
+}
+
+/// The changed fields between two users, in field order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    changes: Vec<Change>,
+}
+
+/// Every field that differs from `before` to `after`
+pub fn diff(before: &User, after: &User) -> Patch {
+    let mut changes = Vec::new();
+    if before.active != after.active {
+        changes.push(Change::Active {
+            old: before.active,
+            new: after.active,
+        });
+    }
+    if before.username != after.username {
+        changes.push(Change::Username {
+            old: before.username.clone(),
+            new: after.username.clone(),
+        });
+    }
+    if before.email != after.email {
+        changes.push(Change::Email {
+            old: before.email.clone(),
+            new: after.email.clone(),
+        });
+    }
+    if before.sign_in_count != after.sign_in_count {
+        changes.push(Change::SignInCount {
+            old: before.sign_in_count,
+            new: after.sign_in_count,
+        });
+    }
+    Patch { changes }
+}
+
+impl Patch {
+    /// A patch from changes to different fields, in any order
+    pub fn new(changes: Vec<Change>) -> Result<Patch, AuditError> {
+        let mut patch = Patch::default();
+        for change in changes {
+            patch.insert(change)?;
+        }
+        Ok(patch)
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = Field> + '_ {
+        self.changes.iter().map(Change::field)
+    }
+
+    /// The patch that undoes this one
+    pub fn invert(&self) -> Patch {
+        Patch {
+            changes: self.changes.iter().map(Change::invert).collect(),
+        }
+    }
+
+    /// Sets every changed field, after checking that each one still holds
+    /// its old value; on a conflict `user` is left as it was
+    pub fn apply(&self, user: &mut User) -> Result<(), AuditError> {
+        for change in &self.changes {
+            let (field, expected, _) = change.to_strings();
+            let found = change.current(user);
+            if found != expected {
+                return Err(AuditError::Conflict {
+                    field,
+                    expected,
+                    found,
+                });
+            }
+        }
+        for change in &self.changes {
+            change.set(user);
+        }
+        Ok(())
+    }
+
+    // Keeps the changes in field order
+    fn insert(&mut self, change: Change) -> Result<(), AuditError> {
+        let field = change.field();
+        match self.changes.binary_search_by_key(&field, Change::field) {
+            Ok(_) => Err(AuditError::DuplicateField(field)),
+            Err(index) => {
+                self.changes.insert(index, change);
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes `field,old,new` rows after a header
+    pub fn write(&self, output: impl Write) -> io::Result<()> {
+        let mut writer = TupleWriter::new(output, Dialect::csv());
+        writer.write_header(&["field", "old", "new"])?;
+        for change in &self.changes {
+            let (field, old, new) = change.to_strings();
+            writer.write(&(field.name().to_string(), old, new))?;
+        }
+        writer.into_inner().flush()
+    }
+
+    pub fn read(input: impl BufRead) -> Result<Patch, AuditError> {
+        let rows = TupleReader::<_, (String, String, String)>::new(
+            input,
+            Dialect::csv().with_header(true),
+        );
+        let mut changes = Vec::new();
+        for row in rows {
+            let (field, old, new) = row?;
+            changes.push(Change::from_strings(&field, &old, &new)?);
+        }
+        Patch::new(changes)
+    }
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return write!(f, "no changes");
+        }
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Audit log
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Numbered from 1 in the order recorded
+    pub id: u64,
+    pub at: Timestamp,
+    /// Who made the change
+    pub actor: String,
+    /// The user's name before the change; empty for a sign-up
+    pub username: String,
+    pub patch: Patch,
+    /// The entry this one rolled back, if any
+    pub reverts: Option<u64>,
+}
+
+impl Entry {
+    /// Whether this entry logs a new user signing up
+    pub fn is_creation(&self) -> bool {
+        self.username.is_empty()
+    }
+
+    /// The user's name after the change
+    pub fn new_username(&self) -> &str {
+        self.patch
+            .changes()
+            .iter()
+            .find_map(|change| match change {
+                Change::Username { new, .. } => Some(new.as_str()),
+                _ => None,
+            })
+            .unwrap_or(&self.username)
+    }
+}
+
+/// Changes to users, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    entries: Vec<Entry>,
+}
+
+const HEADER: [&str; 8] = [
+    "id", "at", "actor", "username", "reverts", "field", "old", "new",
+];
+
+impl AuditLog {
+    pub fn new() -> AuditLog {
+        AuditLog::default()
+    }
+
+    /// Loads the log at `path`, or an empty one if there is no file yet
+    pub fn open(path: impl AsRef<Path>) -> Result<AuditLog, AuditError> {
+        match File::open(path) {
+            Ok(file) => AuditLog::read(BufReader::new(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(AuditLog::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Reads rows written by `write`; rows of one entry are consecutive
+    /// and agree on everything but the change
+    pub fn read(input: impl BufRead) -> Result<AuditLog, AuditError> {
+        let rows =
+            TupleReader::<_, (u64, String, String, String, String, String, String, String)>::new(
+                input,
+                Dialect::csv().with_header(true),
+            );
+        let mut log = AuditLog::new();
+        for row in rows {
+            let (id, at, actor, username, reverts, field, old, new) = row?;
+            let change = Change::from_strings(&field, &old, &new)?;
+            let invalid = |reason| AuditError::InvalidEntry { id, reason };
+            let reverts = match reverts.as_str() {
+                "" => None,
+                text => match text.parse() {
+                    Ok(reverted) if reverted < id => Some(reverted),
+                    _ => return Err(invalid("reverts an entry that comes later or not at all")),
+                },
+            };
+            let at = at
+                .parse()
+                .map_err(|error| AuditError::InvalidTimestamp { text: at, error })?;
+            if let Some(entry) = log.entries.last_mut().filter(|entry| entry.id == id) {
+                if entry.at != at
+                    || entry.at.offset() != at.offset()
+                    || entry.actor != actor
+                    || entry.username != username
+                    || entry.reverts != reverts
+                {
+                    return Err(invalid("rows disagree on at, actor, username or reverts"));
+                }
+                entry
+                    .patch
+                    .insert(change)
+                    .map_err(|_| invalid("changes a field twice"))?;
+                continue;
+            }
+            if id != log.entries.len() as u64 + 1 {
+                return Err(invalid("ids must count up from 1"));
+            }
+            log.entries.push(Entry {
+                id,
+                at,
+                actor,
+                username,
+                patch: Patch {
+                    changes: vec![change],
+                },
+                reverts,
+            });
+        }
+        Ok(log)
+    }
+
+    pub fn write(&self, output: impl Write) -> io::Result<()> {
+        let mut writer = TupleWriter::new(output, Dialect::csv());
+        writer.write_header(&HEADER)?;
+        for entry in &self.entries {
+            let reverts = entry.reverts.map_or(String::new(), |id| id.to_string());
+            for change in entry.patch.changes() {
+                let (field, old, new) = change.to_strings();
+                writer.write(&(
+                    entry.id,
+                    entry.at.to_string(),
+                    entry.actor.clone(),
+                    entry.username.clone(),
+                    reverts.clone(),
+                    field.name().to_string(),
+                    old,
+                    new,
+                ))?;
+            }
+        }
+        writer.into_inner().flush()
+    }
+
+    /// Writes through a temporary file, like `UserStore::save`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AuditError> {
+        write_atomically(path.as_ref(), |output| self.write(output))?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Result<&Entry, AuditError> {
+        let index = id.checked_sub(1).and_then(|i| usize::try_from(i).ok());
+        index
+            .and_then(|i| self.entries.get(i))
+            .ok_or(AuditError::NoSuchEntry {
+                id,
+                entries: self.entries.len(),
+            })
+    }
+
+    /// Entries about one user, following renames: an entry that renamed
+    /// "a" to "b" belongs to "b" as well
+    pub fn for_user(&self, username: &str) -> Vec<&Entry> {
+        let mut names = vec![username.to_string()];
+        let mut found = Vec::new();
+        for entry in self.entries.iter().rev() {
+            let matches = |name: &str| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+            if !matches(entry.new_username()) && !matches(&entry.username) {
+                continue;
+            }
+            if entry.is_creation() {
+                // Earlier entries under this name were about someone else
+                let name = entry.new_username();
+                names.retain(|n| !n.eq_ignore_ascii_case(name));
+            } else if !matches(&entry.username) {
+                names.push(entry.username.clone());
+            }
+            found.push(entry);
+        }
+        found.reverse();
+        found
+    }
+
+    /// The name of the user entry `id` changed, as of the end of the log:
+    /// the name right after that entry, followed through later renames
+    pub fn current_username(&self, id: u64) -> Result<&str, AuditError> {
+        let mut name = self.get(id)?.new_username();
+        for entry in &self.entries[id as usize..] {
+            if entry.username.eq_ignore_ascii_case(name) {
+                name = entry.new_username();
+            }
+        }
+        Ok(name)
+    }
+
+    /// Logs a new user signing up now
+    pub fn record_created(&mut self, actor: &str, user: &User) -> &Entry {
+        self.record_created_at(Timestamp::now(), actor, user)
+    }
+
+    pub fn record_created_at(&mut self, at: Timestamp, actor: &str, user: &User) -> &Entry {
+        let blank = User {
+            active: false,
+            username: String::new(),
+            email: String::new(),
+            sign_in_count: 0,
+        };
+        let patch = diff(&blank, user);
+        self.push(at, actor, "", patch, None)
+            .expect("a user differs from a blank one")
+    }
+
+    /// Logs the change from `before` to `after` now; nothing is logged if
+    /// they are equal
+    pub fn record(&mut self, actor: &str, before: &User, after: &User) -> Option<&Entry> {
+        self.record_at(Timestamp::now(), actor, before, after)
+    }
+
+    pub fn record_at(
+        &mut self,
+        at: Timestamp,
+        actor: &str,
+        before: &User,
+        after: &User,
+    ) -> Option<&Entry> {
+        let patch = diff(before, after);
+        self.push(at, actor, &before.username, patch, None)
+    }
+
+    /// Undoes entry `id` on `user` and logs that as a new entry. Fails with
+    /// a conflict if a later change touched the same fields. Undoing a
+    /// sign-up deactivates the user.
+    pub fn rollback(
+        &mut self,
+        id: u64,
+        actor: &str,
+        user: &mut User,
+    ) -> Result<&Entry, AuditError> {
+        self.rollback_at(Timestamp::now(), id, actor, user)
+    }
+
+    pub fn rollback_at(
+        &mut self,
+        at: Timestamp,
+        id: u64,
+        actor: &str,
+        user: &mut User,
+    ) -> Result<&Entry, AuditError> {
+        let entry = self.get(id)?;
+        let inverse = if entry.is_creation() {
+            Patch {
+                changes: vec![Change::Active {
+                    old: true,
+                    new: false,
+                }],
+            }
+        } else {
+            entry.patch.invert()
+        };
+        let username = user.username.clone();
+        inverse.apply(user)?;
+        Ok(self
+            .push(at, actor, &username, inverse, Some(id))
+            .expect("a logged patch is never empty"))
+    }
+
+    fn push(
+        &mut self,
+        at: Timestamp,
+        actor: &str,
+        username: &str,
+        patch: Patch,
+        reverts: Option<u64>,
+    ) -> Option<&Entry> {
+        if patch.is_empty() {
+            return None;
+        }
+        self.entries.push(Entry {
+            id: self.entries.len() as u64 + 1,
+            at,
+            actor: actor.to_string(),
+            username: username.to_string(),
+            patch,
+            reverts,
+        });
+        self.entries.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> Timestamp {
+        format!("2026-10-19T{time}Z").parse().unwrap()
+    }
+
+    fn rename(log: &mut AuditLog, user: &mut User, time: &str, name: &str) -> u64 {
+        let before = user.clone();
+        user.username = name.to_string();
+        log.record_at(at(time), "ferris", &before, user).unwrap().id
+    }
+
+    #[test]
+    fn rollback_finds_the_user_after_later_renames() {
+        let mut log = AuditLog::new();
+        let mut user = User::new("alice@example.com", "alice").unwrap();
+        let first = rename(&mut log, &mut user, "09:00:00", "alex");
+        let second = rename(&mut log, &mut user, "09:05:00", "ally");
+        assert_eq!(log.current_username(first).unwrap(), "ally");
+        assert_eq!(log.current_username(second).unwrap(), "ally");
+
+        // Rolling back the first rename conflicts with the second
+        let err = log
+            .rollback_at(at("09:10:00"), first, "ferris", &mut user)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AuditError::Conflict { field: Field::Username, ref expected, ref found }
+                if expected == "alex" && found == "ally"
+        ));
+
+        let undo = log
+            .rollback_at(at("09:15:00"), second, "ferris", &mut user)
+            .unwrap()
+            .id;
+        assert_eq!(log.current_username(first).unwrap(), "alex");
+        assert_eq!(log.current_username(undo).unwrap(), "alex");
+        log.rollback_at(at("09:20:00"), first, "ferris", &mut user)
+            .unwrap();
+        assert_eq!(user.username, "alice");
+    }
+
+    #[test]
+    fn sign_ups_are_logged_and_rolled_back_by_deactivating() {
+        let mut log = AuditLog::new();
+        let mut user = User::new("alice@example.com", "alice").unwrap();
+        let entry = log.record_created_at(at("09:00:00"), "ferris", &user);
+        assert!(entry.is_creation());
+        assert_eq!(entry.new_username(), "alice");
+        assert_eq!(entry.patch.fields().count(), 4);
+        let created = entry.id;
+
+        let mut csv = Vec::new();
+        log.write(&mut csv).unwrap();
+        assert_eq!(AuditLog::read(&csv[..]).unwrap(), log);
+
+        log.rollback_at(at("09:05:00"), created, "ferris", &mut user)
+            .unwrap();
+        assert!(!user.active);
+        assert_eq!(user.username, "alice");
+        let err = log
+            .rollback_at(at("09:10:00"), created, "ferris", &mut user)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AuditError::Conflict {
+                field: Field::Active,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn history_stops_at_the_sign_up_of_a_reused_name() {
+        let mut log = AuditLog::new();
+        let mut alice = User::new("alice@example.com", "alice").unwrap();
+        log.record_created_at(at("09:00:00"), "ferris", &alice);
+        rename(&mut log, &mut alice, "09:05:00", "ally");
+        let bob = User::new("bob@example.com", "alice").unwrap();
+        log.record_created_at(at("09:10:00"), "ferris", &bob);
+
+        let ids = |name| {
+            log.for_user(name)
+                .iter()
+                .map(|entry| entry.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("ally"), [1, 2]);
+        assert_eq!(ids("alice"), [3]);
+    }
+
+    #[test]
+    fn patches_change_each_field_once() {
+        let patch = Patch::new(vec![
+            Change::SignInCount { old: 1, new: 2 },
+            Change::Active {
+                old: true,
+                new: false,
+            },
+        ])
+        .unwrap();
+        assert_eq!(
+            patch.fields().collect::<Vec<_>>(),
+            [Field::Active, Field::SignInCount]
+        );
+
+        let twice = vec![
+            Change::SignInCount { old: 1, new: 2 },
+            Change::SignInCount { old: 2, new: 3 },
+        ];
+        assert!(matches!(
+            Patch::new(twice),
+            Err(AuditError::DuplicateField(Field::SignInCount))
+        ));
+        let csv =
+            "field,old,new\nemail,a@example.com,b@example.com\nemail,b@example.com,c@example.com\n";
+        assert!(matches!(
+            Patch::read(csv.as_bytes()),
+            Err(AuditError::DuplicateField(Field::Email))
+        ));
+    }
+
+    #[test]
+    fn rows_of_one_entry_must_agree() {
+        const HEADER_ROW: &str = "id,at,actor,username,reverts,field,old,new\n";
+        let read = |rows: &str| AuditLog::read(format!("{HEADER_ROW}{rows}").as_bytes());
+        let entry = read(
+            "1,2026-10-19T09:00:00Z,ferris,alice,,sign_in_count,1,2\n\
+             1,2026-10-19T09:00:00Z,ferris,alice,,active,true,false\n",
+        )
+        .unwrap()
+        .entries()[0]
+            .clone();
+        assert_eq!(
+            entry.patch.fields().collect::<Vec<_>>(),
+            [Field::Active, Field::SignInCount]
+        );
+
+        let first = "1,2026-10-19T09:00:00Z,ferris,alice,,active,true,false\n";
+        let disagreeing = [
+            "1,2026-10-19T09:00:01Z,ferris,alice,,email,a@example.com,b@example.com\n",
+            // The same instant written with another offset
+            "1,2026-10-19T11:00:00+02:00,ferris,alice,,email,a@example.com,b@example.com\n",
+            "1,2026-10-19T09:00:00Z,corro,alice,,email,a@example.com,b@example.com\n",
+            "1,2026-10-19T09:00:00Z,ferris,bob,,email,a@example.com,b@example.com\n",
+            "2,2026-10-19T09:00:00Z,ferris,alice,1,email,a@example.com,b@example.com\n\
+             2,2026-10-19T09:00:00Z,ferris,alice,,sign_in_count,1,2\n",
+        ];
+        for rows in disagreeing {
+            let err = read(&format!("{first}{rows}")).unwrap_err();
+            assert!(
+                matches!(err, AuditError::InvalidEntry { reason, .. } if reason.contains("disagree")),
+                "{rows}"
+            );
+        }
+
+        let err = read(&format!("{first}{first}")).unwrap_err();
+        assert!(matches!(
+            err,
+            AuditError::InvalidEntry {
+                id: 1,
+                reason: "changes a field twice"
+            }
+        ));
+    }
+}
@@ -0,0 +1,394 @@
+// A user store kept in a CSV file.
+//
+// Usernames and emails are unique, ignoring ASCII case, so "Alice" and
+// "alice" can't both sign up. Users are never deleted, only deactivated,
+// and a deactivated user can't sign in. The file has one user per line
+// after a header:
+//
+//     username,email,active,sign_in_count
+//     someoneusername,someone@gmail.com,true,1
+//
+// Every user is validated and normalized by `User::validated`, whether it
+// is added, renamed or loaded from a hand-edited file.
+//
+// `save` writes a temporary file next to the store and renames it over the
+// old one, so a crash or a full disk never leaves a half-written store.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use datatypes::typed_rows::{Dialect, ParseError, TupleReader, TupleWriter};
+
+use crate::user::{self, User, ValidationErrors};
+
+const HEADER: [&str; 4] = ["username", "email", "active", "sign_in_count"];
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Csv(ParseError),
+    /// The username or email is not valid
+    Invalid(ValidationErrors),
+    /// Another user already has this username
+    DuplicateUsername(String),
+    /// Another user already has this email
+    DuplicateEmail(String),
+    /// No user has this username
+    NotFound(String),
+    /// The user was deactivated already
+    AlreadyInactive(String),
+    /// Deactivated users can't sign in
+    Inactive(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(err) => write!(f, "I/O error: {err}"),
+            StoreError::Csv(err) => write!(f, "{err}"),
+            StoreError::Invalid(errors) => write!(f, "invalid user: {errors}"),
+            StoreError::DuplicateUsername(name) => write!(f, "username {name:?} is taken"),
+            StoreError::DuplicateEmail(email) => write!(f, "email {email:?} is taken"),
+            StoreError::NotFound(name) => write!(f, "no user named {name:?}"),
+            StoreError::AlreadyInactive(name) => write!(f, "{name:?} is already deactivated"),
+            StoreError::Inactive(name) => {
+                write!(f, "{name:?} is deactivated and can't sign in")
+            }
+        }
+    }
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StoreError::Io(err) => Some(err),
+            StoreError::Csv(err) => Some(err),
+            StoreError::Invalid(errors) => Some(errors),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(err: io::Error) -> StoreError {
+        StoreError::Io(err)
+    }
+}
+
+impl From<ValidationErrors> for StoreError {
+    fn from(errors: ValidationErrors) -> StoreError {
+        StoreError::Invalid(errors)
+    }
+}
+
+impl From<ParseError> for StoreError {
+    fn from(err: ParseError) -> StoreError {
+        StoreError::Csv(err)
+    }
+}
+
+// ============================================================================
+// Store
+// ============================================================================
+
+/// Users in the order they were added
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserStore {
+    users: Vec<User>,
+}
+
+impl UserStore {
+    pub fn new() -> UserStore {
+        UserStore::default()
+    }
+
+    /// Loads the store at `path`, or an empty one if there is no file yet
+    pub fn open(path: impl AsRef<Path>) -> Result<UserStore, StoreError> {
+        match File::open(path) {
+            Ok(file) => UserStore::read(BufReader::new(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(UserStore::new()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Reads the CSV format above, checking uniqueness like `insert` does
+    pub fn read(input: impl io::BufRead) -> Result<UserStore, StoreError> {
+        let rows = TupleReader::<_, (String, String, bool, u64)>::new(
+            input,
+            Dialect::csv().with_header(true),
+        );
+        let mut store = UserStore::new();
+        for row in rows {
+            let (username, email, active, sign_in_count) = row?;
+            store.insert(User {
+                active,
+                username,
+                email,
+                sign_in_count,
+            })?;
+        }
+        Ok(store)
+    }
+
+    pub fn write(&self, output: impl Write) -> io::Result<()> {
+        let mut writer = TupleWriter::new(output, Dialect::csv());
+        writer.write_header(&HEADER)?;
+        for user in &self.users {
+            writer.write(&(
+                user.username.clone(),
+                user.email.clone(),
+                user.active,
+                user.sign_in_count,
+            ))?;
+        }
+        writer.into_inner().flush()
+    }
+
+    /// Writes to a temporary file, syncs it and renames it over `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StoreError> {
+        write_atomically(path.as_ref(), |output| self.write(output))?;
+        Ok(())
+    }
+
+    pub fn users(&self) -> &[User] {
+        &self.users
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    pub fn get(&self, username: &str) -> Option<&User> {
+        self.users
+            .iter()
+            .find(|user| user.username.eq_ignore_ascii_case(username))
+    }
+
+    /// Signs up a new user with `User::new`
+    pub fn add(&mut self, email: &str, username: &str) -> Result<&User, StoreError> {
+        let user = User::new(email, username)?;
+        self.insert(user)
+    }
+
+    /// Adds an existing user value, as long as it is valid and unique
+    pub fn insert(&mut self, user: User) -> Result<&User, StoreError> {
+        let user = user.validated()?;
+        self.check_unique(&user, None)?;
+        self.users.push(user);
+        Ok(self.users.last().unwrap())
+    }
+
+    /// Replaces a user with a changed copy, as long as it is valid and
+    /// unique; used to apply patches and roll changes back
+    pub fn replace(&mut self, username: &str, user: User) -> Result<&User, StoreError> {
+        let index = self.index(username)?;
+        let user = user.validated()?;
+        self.check_unique(&user, Some(index))?;
+        self.users[index] = user;
+        Ok(&self.users[index])
+    }
+
+    pub fn deactivate(&mut self, username: &str) -> Result<&User, StoreError> {
+        let user = self.get_mut(username)?;
+        if !user.active {
+            return Err(StoreError::AlreadyInactive(user.username.clone()));
+        }
+        user.active = false;
+        Ok(user)
+    }
+
+    /// Changes a username; changing only its case is allowed
+    pub fn rename(&mut self, username: &str, new_username: &str) -> Result<&User, StoreError> {
+        let index = self.index(username)?;
+        let new_username = user::normalize_username(new_username)?;
+        let renamed = User {
+            username: new_username.clone(),
+            ..self.users[index].clone()
+        };
+        self.check_unique(&renamed, Some(index))?;
+        let user = &mut self.users[index];
+        user.username = new_username;
+        Ok(user)
+    }
+
+    /// Counts a sign-in and returns the new count
+    pub fn record_login(&mut self, username: &str) -> Result<u64, StoreError> {
+        let user = self.get_mut(username)?;
+        if !user.active {
+            return Err(StoreError::Inactive(user.username.clone()));
+        }
+        user.sign_in_count += 1;
+        Ok(user.sign_in_count)
+    }
+
+    fn index(&self, username: &str) -> Result<usize, StoreError> {
+        self.users
+            .iter()
+            .position(|user| user.username.eq_ignore_ascii_case(username))
+            .ok_or_else(|| StoreError::NotFound(username.to_string()))
+    }
+
+    fn get_mut(&mut self, username: &str) -> Result<&mut User, StoreError> {
+        let index = self.index(username)?;
+        Ok(&mut self.users[index])
+    }
+
+    // `except` is the user being changed, who may keep their own name
+    fn check_unique(&self, user: &User, except: Option<usize>) -> Result<(), StoreError> {
+        let others = self
+            .users
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != except)
+            .map(|(_, other)| other);
+        for other in others {
+            if other.username.eq_ignore_ascii_case(&user.username) {
+                return Err(StoreError::DuplicateUsername(user.username.clone()));
+            }
+            if other.email.eq_ignore_ascii_case(&user.email) {
+                return Err(StoreError::DuplicateEmail(user.email.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes a file through a temporary file next to it: the old file is
+/// replaced only once the new one is complete and synced to disk
+pub(crate) fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let result = File::create(&tmp).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()
+    });
+    let result = result.and_then(|()| fs::rename(&tmp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // A fresh directory per test, removed again at the end
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let dir =
+                std::env::temp_dir().join(format!("user-store-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn store() -> UserStore {
+        let mut store = UserStore::new();
+        store.add("alice@example.com", "alice").unwrap();
+        store.add("bob@example.com", "bob").unwrap();
+        store
+    }
+
+    #[test]
+    fn usernames_and_emails_are_unique_ignoring_case() {
+        let mut store = store();
+        let err = store.add("carol@example.com", "ALICE").unwrap_err();
+        assert!(matches!(err, StoreError::DuplicateUsername(ref name) if name == "ALICE"));
+        // The domain is lowercased, the local part compared ignoring case
+        let err = store.add("Alice@EXAMPLE.com", "carol").unwrap_err();
+        assert!(
+            matches!(err, StoreError::DuplicateEmail(ref email) if email == "Alice@example.com")
+        );
+        assert_eq!(store.len(), 2);
+
+        // Renaming onto another user is refused, changing case is not
+        let err = store.rename("bob", "Alice").unwrap_err();
+        assert!(matches!(err, StoreError::DuplicateUsername(_)));
+        assert_eq!(store.rename("bob", "Bob").unwrap().username, "Bob");
+
+        let mut bob = store.get("bob").unwrap().clone();
+        bob.email = "alice@example.com".to_string();
+        let err = store.replace("bob", bob).unwrap_err();
+        assert!(matches!(err, StoreError::DuplicateEmail(_)));
+        assert_eq!(store.get("bob").unwrap().email, "bob@example.com");
+    }
+
+    #[test]
+    fn hand_edited_duplicates_are_rejected_on_load() {
+        let csv = "\
+username,email,active,sign_in_count
+alice,alice@example.com,true,1
+Alice,other@example.com,true,1
+";
+        let err = UserStore::read(csv.as_bytes()).unwrap_err();
+        assert!(matches!(err, StoreError::DuplicateUsername(ref name) if name == "Alice"));
+
+        let csv = "\
+username,email,active,sign_in_count
+alice,alice@example.com,true,1
+bob,ALICE@Example.COM,false,3
+";
+        let err = UserStore::read(csv.as_bytes()).unwrap_err();
+        assert!(
+            matches!(err, StoreError::DuplicateEmail(ref email) if email == "ALICE@example.com")
+        );
+
+        // Rows are validated too, not just compared
+        let csv = "\
+username,email,active,sign_in_count
+root,root@example.com,true,1
+";
+        let err = UserStore::read(csv.as_bytes()).unwrap_err();
+        assert!(matches!(err, StoreError::Invalid(_)));
+    }
+
+    #[test]
+    fn save_and_open_round_trip() {
+        let dir = TempDir::new("round-trip");
+        let path = dir.0.join("users.csv");
+        assert!(UserStore::open(&path).unwrap().is_empty());
+
+        let mut store = store();
+        store.add("o'brien+tag@example.com", "o.brien").unwrap();
+        store.deactivate("bob").unwrap();
+        assert_eq!(store.record_login("alice").unwrap(), 2);
+        store.save(&path).unwrap();
+        assert_eq!(UserStore::open(&path).unwrap(), store);
+
+        // Saving again replaces the file and leaves no temporary behind
+        store.rename("alice", "alicia").unwrap();
+        store.save(&path).unwrap();
+        let reopened = UserStore::open(&path).unwrap();
+        assert_eq!(reopened, store);
+        assert!(reopened.get("alice").is_none());
+        assert!(!path.with_extension("tmp").exists());
+        assert!(matches!(
+            reopened.clone().record_login("bob"),
+            Err(StoreError::Inactive(_))
+        ));
+    }
+}